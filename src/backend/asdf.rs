@@ -1,43 +1,90 @@
+use std::cell::Cell;
 use std::collections::{BTreeMap, HashMap};
 use std::ffi::OsString;
-use std::fmt::{Debug, Formatter};
+use std::fmt::{Debug, Display, Formatter};
 use std::fs;
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::process::exit;
-use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
 use clap::Command;
-use color_eyre::eyre::{bail, eyre, Result, WrapErr};
+use color_eyre::eyre::{bail, eyre, Report, Result, WrapErr};
 use color_eyre::Section;
 use console::style;
 use itertools::Itertools;
+use once_cell::sync::Lazy;
 use rayon::prelude::*;
+use tempfile::tempdir_in;
 use url::Url;
+use versions::Versioning;
 
-use crate::backend::{ABackend, Backend, BackendList, BackendType};
+use crate::backend::{
+    probe_with_timeout, ABackend, Backend, BackendList, BackendType, Capability, Health,
+    InstallStatus, REMOTE_VERSIONS_SCHEMA_VERSION,
+};
 use crate::cache::CacheManager;
 use crate::cli::args::BackendArg;
+use crate::cli::version;
+use crate::config::settings::{SettingsPluginPinPolicy, SettingsPluginPlatformCheck};
 use crate::config::{Config, Settings};
 use crate::default_shorthands::{DEFAULT_SHORTHANDS, TRUSTED_SHORTHANDS};
 use crate::env::MISE_FETCH_REMOTE_VERSIONS_TIMEOUT;
 use crate::env_diff::{EnvDiff, EnvDiffOperation};
-use crate::errors::Error::PluginNotInstalled;
+use crate::errors::Error::{Cancelled, NoRemoteVersions, PluginNotInstalled, PluginNotTrusted};
+use crate::errors::{Error, ErrorKind};
 use crate::file::{display_path, remove_all};
-use crate::git::Git;
+use crate::git::{filter_tag_refs, parse_refspec, Git};
 use crate::hash::hash_to_str;
-use crate::http::HTTP_FETCH;
+use crate::http::{HTTP, HTTP_FETCH};
 use crate::install_context::InstallContext;
+use crate::lock_file::LockFile;
+use crate::plugins::asdf_plugin::{worktree_path, worktrees_dir, AsdfPlugin};
 use crate::plugins::mise_plugin_toml::MisePluginToml;
 use crate::plugins::Script::{Download, ExecEnv, Install, ParseLegacyFile};
-use crate::plugins::{PluginType, Script, ScriptManager};
+use crate::plugins::{
+    self, with_list_concurrency, Plugin, PluginCommand, PluginType, Script, ScriptManager,
+    ShorthandComparison, TrustStatus,
+};
+use crate::shims;
+use crate::shorthands::{expand_git_host_shorthand, get_shorthands};
 use crate::tera::{get_tera, BASE_CONTEXT};
 use crate::timeout::run_with_timeout;
 use crate::toolset::{ToolRequest, ToolVersion, Toolset};
 use crate::ui::multi_progress_report::MultiProgressReport;
-use crate::ui::progress_report::SingleReport;
+use crate::ui::progress_report::{QuietReport, SingleReport};
 use crate::ui::prompt;
-use crate::{dirs, env, file, http};
+use crate::{dirs, disk_space, env, file, http, timings};
+
+/// an unreviewed ref of a plugin's repo to test changes before they are merged, see
+/// `mise plugins install --pr`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DevRef {
+    /// number of a GitHub pull request
+    Pr(u32),
+    Branch(String),
+}
+
+impl DevRef {
+    /// fetched into a remote-tracking ref, forced since these are often rebased
+    pub fn refspec(&self) -> String {
+        match self {
+            DevRef::Pr(n) => format!("+refs/pull/{n}/head:refs/remotes/origin/pr-{n}"),
+            DevRef::Branch(b) => format!("+refs/heads/{b}:refs/remotes/origin/{b}"),
+        }
+    }
+}
+
+impl Display for DevRef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DevRef::Pr(n) => write!(f, "PR #{n}"),
+            DevRef::Branch(b) => write!(f, "branch {b}"),
+        }
+    }
+}
 
 /// This represents a plugin installed to ~/.local/share/mise/plugins
 pub struct Asdf {
@@ -45,6 +92,14 @@ pub struct Asdf {
     pub name: String,
     pub plugin_path: PathBuf,
     pub repo_url: Option<String>,
+    /// semver constraint (e.g.: "^1.2") the plugin tag to install must match, used when the url
+    /// has no #ref
+    pub ref_constraint: Option<String>,
+    /// aborts installing the plugin before its next phase once set, e.g.: by an application
+    /// embedding mise that can't send it a signal
+    pub cancel: Option<Arc<AtomicBool>>,
+    /// unmerged PR or branch to install instead of the default branch, e.g.: to test it
+    pub dev_ref: Option<DevRef>,
     pub toml: MisePluginToml,
     script_man: ScriptManager,
     cache: ExternalPluginCache,
@@ -56,38 +111,68 @@ pub struct Asdf {
 
 impl Asdf {
     pub fn new(name: String) -> Self {
-        let plugin_path = dirs::PLUGINS.join(&name);
+        Self::from_arg(BackendArg::new(BackendType::Asdf, &name))
+    }
+
+    /// the plugin named `fa.name` with its installs and caches under `fa`'s dirs, e.g.: those of
+    /// "asdf:node" rather than the core node plugin's
+    pub fn from_arg(fa: BackendArg) -> Self {
+        let plugin_path = dirs::plugin_path(&fa.name);
+        let cache_path = fa.cache_path.clone();
+        Self::with_plugin_path(fa.name.clone(), plugin_path, fa, &cache_path)
+    }
+
+    /// the plugin checked out at `gitref` in a worktree added with [`AsdfPlugin::add_worktree`].
+    /// Its remote versions, aliases, etc. are cached separately from the plugin's
+    pub fn worktree(name: String, gitref: &str) -> Self {
+        let plugin_path = worktree_path(&name, gitref);
+        let fa = BackendArg::new(BackendType::Asdf, &name);
+        let cache_path = fa
+            .cache_path
+            .join("worktrees")
+            .join(plugin_path.file_name().unwrap());
+        Self::with_plugin_path(name, plugin_path, fa, &cache_path)
+    }
+
+    fn with_plugin_path(
+        name: String,
+        plugin_path: PathBuf,
+        fa: BackendArg,
+        cache_path: &Path,
+    ) -> Self {
         let mut toml_path = plugin_path.join("mise.plugin.toml");
         if plugin_path.join("rtx.plugin.toml").exists() {
             toml_path = plugin_path.join("rtx.plugin.toml");
         }
         let toml = MisePluginToml::from_file(&toml_path).unwrap();
-        let fa = BackendArg::new(BackendType::Asdf, &name);
         Self {
             script_man: build_script_man(&name, &plugin_path),
             cache: ExternalPluginCache::default(),
             remote_version_cache: CacheManager::new(
-                fa.cache_path.join("remote_versions-$KEY.msgpack.z"),
-            )
-            .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE)
-            .with_fresh_file(plugin_path.clone())
-            .with_fresh_file(plugin_path.join("bin/list-all")),
-            latest_stable_cache: CacheManager::new(
-                fa.cache_path.join("latest_stable-$KEY.msgpack.z"),
+                cache_path.join("remote_versions-$KEY.msgpack.z"),
             )
             .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE)
             .with_fresh_file(plugin_path.clone())
-            .with_fresh_file(plugin_path.join("bin/latest-stable")),
-            alias_cache: CacheManager::new(fa.cache_path.join("aliases-$KEY.msgpack.z"))
+            .with_fresh_file(plugin_path.join("bin/list-all"))
+            .with_stampede_protection(Duration::from_secs(60))
+            .with_schema_version(REMOTE_VERSIONS_SCHEMA_VERSION),
+            latest_stable_cache: CacheManager::new(cache_path.join("latest_stable-$KEY.msgpack.z"))
+                .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE)
+                .with_fresh_file(plugin_path.clone())
+                .with_fresh_file(plugin_path.join("bin/latest-stable")),
+            alias_cache: CacheManager::new(cache_path.join("aliases-$KEY.msgpack.z"))
                 .with_fresh_file(plugin_path.clone())
                 .with_fresh_file(plugin_path.join("bin/list-aliases")),
             legacy_filename_cache: CacheManager::new(
-                fa.cache_path.join("legacy_filenames-$KEY.msgpack.z"),
+                cache_path.join("legacy_filenames-$KEY.msgpack.z"),
             )
             .with_fresh_file(plugin_path.clone())
             .with_fresh_file(plugin_path.join("bin/list-legacy-filenames")),
             plugin_path,
             repo_url: None,
+            ref_constraint: None,
+            cancel: None,
+            dev_ref: None,
             toml,
             name,
             fa,
@@ -95,22 +180,202 @@ impl Asdf {
     }
 
     pub fn list() -> Result<BackendList> {
-        Ok(file::dir_subdirs(&dirs::PLUGINS)?
-            .into_par_iter()
-            .map(|name| Arc::new(Self::new(name)) as ABackend)
-            .collect())
+        let mut names = vec![];
+        for dir in dirs::plugins_dirs() {
+            names.extend(file::dir_subdirs(&dir)?);
+        }
+        let names = names.into_iter().unique().collect_vec();
+        with_list_concurrency(|| {
+            names
+                .into_par_iter()
+                .map(|name| Arc::new(Self::new(name)) as ABackend)
+                .collect()
+        })
+    }
+
+    /// re-clones the plugin from the url it was installed from, e.g.: when its scripts were
+    /// broken by local edits. Unlike reinstalling, the remove hooks don't run and its shims and
+    /// installed versions are left alone. The old plugin dir is restored if this fails
+    pub fn refresh_plugin(&self, pr: &dyn SingleReport) -> Result<()> {
+        let _lock = self.get_lock(&self.plugin_path, false)?;
+        ensure_plugins_dir_writable(&self.plugin_path)?;
+        let mut plugin = Asdf::new(self.name.clone());
+        plugin.repo_url = self.repo_url.clone().or_else(|| self.get_remote_url());
+        if !self.is_installed() {
+            return plugin.install(pr);
+        }
+        file::create_dir_all(*dirs::DOWNLOADS)?;
+        let backup_dir = tempdir_in(*dirs::DOWNLOADS)?;
+        let backup = backup_dir.path().join(&self.name);
+        file::rename(&self.plugin_path, &backup)?;
+        match plugin.install(pr) {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                file::remove_all(&self.plugin_path)?;
+                file::rename(&backup, &self.plugin_path)?;
+                Err(err.wrap_err(format!("failed to refresh plugin {}", self.name)))
+            }
+        }
     }
 
     fn get_repo_url(&self, config: &Config) -> Result<String> {
-        self.repo_url
+        let url = self
+            .untransformed_repo_url(config)?
+            .ok_or_else(|| eyre!("No repository found for plugin {}", self.name))?;
+        transform_url(&url)
+    }
+
+    /// the repo url before plugin_url_transform is applied
+    fn untransformed_repo_url(&self, config: &Config) -> Result<Option<String>> {
+        let url = self
+            .repo_url
             .clone()
-            .or_else(|| config.get_repo_url(&self.name))
-            .ok_or_else(|| eyre!("No repository found for plugin {}", self.name))
+            .or_else(|| config.get_repo_url(&self.name));
+        match url {
+            Some(url) => Ok(Some(expand_git_host_shorthand(&expand_url_env(&url)?))),
+            None => Ok(None),
+        }
+    }
+
+    /// trust is evaluated on the transformed url since that is what gets cloned, but under
+    /// paranoid a transform can't make a plugin more trusted than its untransformed url
+    fn is_trusted(&self, config: &Config, url: &str) -> bool {
+        if !is_trusted_plugin(self.name(), url) {
+            return false;
+        }
+        match self.untransformed_repo_url(config).ok().flatten() {
+            Some(original) if Settings::get().paranoid && original != url => {
+                is_trusted_plugin(self.name(), &original)
+            }
+            _ => true,
+        }
+    }
+
+    /// an installed plugin whose name only differs by case, e.g.: "node" when installing "Node".
+    /// Both are the same directory on case-insensitive filesystems (the macOS default)
+    fn case_collision(&self) -> Result<Option<String>> {
+        let collision = AsdfPlugin::list()?
+            .into_iter()
+            .map(|p| p.name().to_string())
+            .find(|name| name != &self.name && name.eq_ignore_ascii_case(&self.name));
+        Ok(collision)
+    }
+
+    fn ensure_can_prompt(&self, mpr: &MultiProgressReport) -> Result<()> {
+        if mpr.is_silent() {
+            return Err(Report::new(PluginNotInstalled(self.name.clone()))
+                .wrap_err(format!(
+                    "installing plugin {} requires confirmation but prompts are disabled",
+                    self.name
+                ))
+                .suggestion("set MISE_YES=1 to install plugins without confirmation"));
+        }
+        Ok(())
+    }
+
+    /// clones with --reference if there is a local clone of the plugin in plugin_reference_dir
+    fn clone(&self, git: &Git, repo_url: &str) -> Result<()> {
+        match self.clone_method() {
+            CloneMethod::TagFilter(tag_glob) => {
+                return git
+                    .clone_with_tag_filter(repo_url, &tag_glob)
+                    .wrap_err_with(|| format!("plugin_tag_filters.{}", self.name));
+            }
+            CloneMethod::Reference(reference) => {
+                let dissociate = Settings::get().plugin_reference_dissociate;
+                match git.clone_with_reference(repo_url, &reference, dissociate) {
+                    Ok(()) => return Ok(()),
+                    Err(err) => {
+                        warn!(
+                            "failed to clone {repo_url} with reference {}: {err:#}",
+                            display_path(&reference)
+                        );
+                        file::remove_all(&self.plugin_path)?;
+                    }
+                }
+            }
+            CloneMethod::Default => {}
+        }
+        git.clone(repo_url)
+    }
+
+    fn clone_method(&self) -> CloneMethod {
+        let settings = Settings::get();
+        if let Some(tag_glob) = settings.plugin_tag_filters.get(&self.name) {
+            return CloneMethod::TagFilter(tag_glob.clone());
+        }
+        settings
+            .plugin_reference_dir
+            .as_ref()
+            .map(|dir| file::replace_path(dir).join(&self.name))
+            .filter(|dir| dir.join(".git").exists() || dir.join("objects").is_dir())
+            .map_or(CloneMethod::Default, CloneMethod::Reference)
+    }
+
+    fn verify_remote_reachable(&self, config: &Config) -> Result<()> {
+        let (repo_url, repo_ref) = Git::split_url_and_ref(&self.get_repo_url(config)?);
+        let settings = Settings::get();
+        if !settings.plugin_install_preflight
+            || is_release_asset(&repo_url)
+            || (settings.plugin_clone_cache
+                && CloneCache::new(&repo_url, repo_ref.as_deref()).is_valid())
+        {
+            return Ok(());
+        }
+        Git::ls_remote(&repo_url)
+            .wrap_err_with(|| format!("remote unreachable for plugin {}: {repo_url}", self.name))
+            .suggestion(
+                "check the plugin url and your network connection, or set MISE_PLUGIN_INSTALL_PREFLIGHT=0 to skip this check",
+            )
     }
 
+    /// whether `git lfs pull` runs after cloning the plugin to `git`, warns instead if the plugin
+    /// uses git-lfs but it isn't installed
+    fn should_pull_lfs(&self, git: &Git, lfs_available: bool) -> bool {
+        if !Settings::get().plugin_git_lfs || !git.uses_lfs() {
+            return false;
+        }
+        if !lfs_available {
+            warn!(
+                "plugin:{} requires git-lfs, some of its files will only be pointers until it is installed",
+                self.name
+            );
+        }
+        lfs_available
+    }
+
+    /// installs the plugin, recording which phase failed and why as its last_error if it fails
     fn install(&self, pr: &dyn SingleReport) -> Result<()> {
+        disk_space::ensure_free_space(&self.plugin_path, 0)?;
+        let phases = InstallPhases::new(&self.name, self.cancel.clone());
+        let plugin = AsdfPlugin::new(self.name.clone());
+        let result = self
+            .install_phases(pr, &phases)
+            .map_err(|err| disk_space::explain_failure(&self.plugin_path, err));
+        match &result {
+            Ok(()) => plugin.clear_last_error()?,
+            // not a failure to record, only what was installed so far is removed
+            Err(err) if Error::kind(err) == Some(ErrorKind::Cancelled) => {
+                file::remove_all(&self.plugin_path)?;
+                file::remove_all(&self.fa.downloads_path)?;
+            }
+            Err(err) => {
+                // a partial clone or download would only leave less space for anything else
+                if Error::kind(err) == Some(ErrorKind::DiskSpace) {
+                    file::remove_all(&self.plugin_path)?;
+                    file::remove_all(&self.fa.downloads_path)?;
+                }
+                if let Err(e) = plugin.record_last_error(phases.current.get(), err) {
+                    warn!("failed to record install error of {}: {e:#}", self.name);
+                }
+            }
+        }
+        result
+    }
+
+    fn install_phases(&self, pr: &dyn SingleReport, phases: &InstallPhases) -> Result<()> {
         let config = Config::get();
-        let repository = self.get_repo_url(&config)?;
+        let repository = phases.run("resolve url", || self.get_repo_url(&config))?;
         let (repo_url, repo_ref) = Git::split_url_and_ref(&repository);
         debug!("install {} {:?}", self.name, repository);
 
@@ -118,23 +383,345 @@ impl Asdf {
             self.uninstall(pr)?;
         }
 
+        if is_release_asset(&repo_url) {
+            self.install_release_asset(pr, &repo_url, phases)?;
+            disk_space::ensure_free_space(&self.plugin_path, 0)?;
+            self.ensure_supported_platform(&version::OS, &version::ARCH)?;
+            self.ensure_build_requirements(phases)?;
+            phases.run("post-plugin-add", || self.exec_hook(pr, "post-plugin-add"))?;
+            AsdfPlugin::new(self.name.clone()).record_scripts_hash()?;
+            pr.finish_with_message(repo_url);
+            return Ok(());
+        }
+
+        let (repo_ref, pinned_by) = self.resolve_install_ref(pr, phases, &repo_url, repo_ref)?;
+
         let git = Git::new(self.plugin_path.to_path_buf());
-        pr.set_message(format!("cloning {repo_url}"));
-        git.clone(&repo_url)?;
-        if let Some(ref_) = &repo_ref {
-            pr.set_message(format!("checking out {ref_}"));
-            git.update(Some(ref_.to_string()))?;
+        AsdfPlugin::new(self.name.clone()).invalidate_git_head_cache()?;
+        let clone_cache = (Settings::get().plugin_clone_cache && self.dev_ref.is_none())
+            .then(|| CloneCache::new(&repo_url, repo_ref.as_deref()));
+        // held until the clone is copied from or into the cache so a concurrent install of the
+        // same url and ref doesn't replace the cached clone partway through
+        let cache_lock = clone_cache.as_ref().map(|c| c.lock()).transpose()?;
+        match &clone_cache {
+            Some(cache) if cache.is_valid() => {
+                pr.set_message(format!("copying {repo_url} from cache"));
+                phases.run("clone", || git.clone_local(&cache.path, &repo_url))?;
+                // the clone is already at the ref, the hook sees the checkout made when it was
+                // cached
+                if let Some((pre, post)) = cache.checkout() {
+                    self.exec_hook_post_plugin_checkout(pr, pre, post)?;
+                }
+            }
+            _ => {
+                pr.set_message(format!("cloning {repo_url}"));
+                phases.run("clone", || self.clone(&git, &repo_url))?;
+                let mut checkout = None;
+                if let Some(ref_) = &repo_ref {
+                    pr.set_message(format!("checking out {ref_}"));
+                    let update = || git.update(Some(ref_.to_string()));
+                    checkout = Some(phases.run("checkout", update)?);
+                }
+                if let Some(dev_ref) = &self.dev_ref {
+                    pr.set_message(format!("checking out {dev_ref}"));
+                    let fetch = || git.checkout(&git.fetch_refspec(&dev_ref.refspec())?);
+                    checkout = Some(phases.run("checkout", fetch)?);
+                }
+                if let Some(cache) = &clone_cache {
+                    if let Err(err) = cache.populate(&self.plugin_path, checkout.as_ref()) {
+                        warn!("failed to cache clone of {repo_url}: {err:#}");
+                    }
+                }
+                if let Some((pre, post)) = checkout {
+                    self.exec_hook_post_plugin_checkout(pr, pre, post)?;
+                }
+            }
+        }
+        drop(cache_lock);
+        disk_space::ensure_free_space(&self.plugin_path, 0)?;
+        self.ensure_supported_platform(&version::OS, &version::ARCH)?;
+        self.ensure_build_requirements(phases)?;
+        if self.should_pull_lfs(&git, Git::lfs_available()) {
+            pr.set_message("pulling git-lfs files".into());
+            phases.run("git-lfs", || git.lfs_pull())?;
         }
-        self.exec_hook(pr, "post-plugin-add")?;
+        phases.run("post-plugin-add", || self.exec_hook(pr, "post-plugin-add"))?;
+        let plugin = AsdfPlugin::new(self.name.clone());
+        plugin.record_scripts_hash()?;
+        let from_shorthand = self.repo_url.is_none()
+            && config
+                .get_shorthands()
+                .get(&self.name)
+                .is_some_and(|s| Some(s) == config.get_repo_url(&self.name).as_ref());
+        plugin.record_shorthand_url(from_shorthand.then_some(repo_url.as_str()))?;
+        plugin.record_dev_ref(self.dev_ref.as_ref().map(|d| d.refspec()).as_deref())?;
 
         let sha = git.current_sha_short()?;
+        let pinned = match (pinned_by, &repo_ref) {
+            (Some(by), Some(ref_)) => format!(" ({by}: {ref_})"),
+            (Some(_), None) => format!(" (branch: {})", git.current_branch()?),
+            (None, _) => (self.dev_ref.as_ref())
+                .map(|d| format!(" ({d})"))
+                .unwrap_or_default(),
+        };
         pr.finish_with_message(format!(
-            "{repo_url}#{}",
+            "{repo_url}#{}{pinned}",
             style(&sha).bright().yellow().for_stderr(),
         ));
         Ok(())
     }
 
+    /// the commands installing the plugin would run, in order, without running them. Remote
+    /// refs are listed to resolve which one would be checked out, and hooks and `git lfs pull`
+    /// are included whether or not the plugin needs them since it isn't cloned yet. Fallbacks
+    /// that only run if a command fails aren't listed
+    pub fn install_plan(&self) -> Result<Vec<PluginCommand>> {
+        let config = Config::get();
+        let (repo_url, repo_ref) = Git::split_url_and_ref(&self.get_repo_url(&config)?);
+        let hook = |name: &str| {
+            let script = self.script_man.get_script_path(&Script::Hook(name.into()));
+            PluginCommand::new(script.to_string_lossy(), vec![])
+        };
+        // downloaded and extracted by mise itself rather than a command
+        if is_release_asset(&repo_url) {
+            return Ok(vec![hook("post-plugin-add")]);
+        }
+        let phases = InstallPhases::new(&self.name, None);
+        let (repo_ref, _) =
+            self.resolve_install_ref(&QuietReport::new(), &phases, &repo_url, repo_ref)?;
+
+        let git = Git::new(self.plugin_path.to_path_buf());
+        let mut commands = vec![];
+        let clone_cache = (Settings::get().plugin_clone_cache && self.dev_ref.is_none())
+            .then(|| CloneCache::new(&repo_url, repo_ref.as_deref()));
+        match &clone_cache {
+            Some(cache) if cache.is_valid() => {
+                commands.push(PluginCommand::new("git", git.clone_local_args(&cache.path)));
+                if cache.checkout().is_some() {
+                    commands.push(hook("post-plugin-checkout"));
+                }
+            }
+            _ => {
+                let mut shallow_clone = false;
+                match self.clone_method() {
+                    CloneMethod::TagFilter(tag_glob) => {
+                        commands.push(PluginCommand::new("git", git.clone_no_tags_args(&repo_url)));
+                        let tags = filter_tag_refs(Git::available_refs(&repo_url)?, &tag_glob)?
+                            .into_iter()
+                            .filter(|r| r.starts_with("refs/tags/"))
+                            .collect_vec();
+                        if !tags.is_empty() {
+                            commands.push(PluginCommand::new("git", git.fetch_tags_args(&tags)));
+                        }
+                    }
+                    CloneMethod::Reference(reference) => {
+                        let dissociate = Settings::get().plugin_reference_dissociate;
+                        let args = git.clone_reference_args(&repo_url, &reference, dissociate);
+                        commands.push(PluginCommand::new("git", args));
+                    }
+                    CloneMethod::Default => {
+                        let (args, shallow) = git.clone_plan(&repo_url);
+                        commands.push(PluginCommand::new("git", args));
+                        shallow_clone = shallow;
+                    }
+                }
+                let mut checkout = None;
+                if let Some(ref_) = &repo_ref {
+                    let is_branch = shallow_clone
+                        && Git::available_refs(&repo_url)?.contains(&format!("refs/heads/{ref_}"));
+                    let fetch = git.fetch_plan(ref_, shallow_clone, is_branch).remove(0);
+                    commands.push(PluginCommand::new("git", fetch));
+                    checkout = Some(ref_.to_string());
+                }
+                if let Some(dev_ref) = &self.dev_ref {
+                    let refspec = dev_ref.refspec();
+                    commands.push(PluginCommand::new("git", git.fetch_refspec_args(&refspec)));
+                    checkout = Some(parse_refspec(&refspec)?);
+                }
+                if let Some(ref_) = checkout {
+                    commands.push(PluginCommand::new("git", git.checkout_args(&ref_)));
+                    commands.push(hook("post-plugin-checkout"));
+                }
+            }
+        }
+        if Settings::get().plugin_git_lfs && Git::lfs_available() {
+            commands.push(PluginCommand::new("git", git.lfs_pull_args()));
+        }
+        commands.push(hook("post-plugin-add"));
+        Ok(commands)
+    }
+
+    /// the ref to check out along with what chose it when it wasn't in the url, e.g.:
+    /// "latest-tag" or "^1.2"
+    fn resolve_install_ref(
+        &self,
+        pr: &dyn SingleReport,
+        phases: &InstallPhases,
+        repo_url: &str,
+        mut repo_ref: Option<String>,
+    ) -> Result<(Option<String>, Option<String>)> {
+        let mut pinned_by = None;
+        if let Some(dev_ref) = &self.dev_ref {
+            if repo_ref.is_some() || self.ref_constraint.is_some() {
+                bail!(
+                    "{}: {dev_ref} can't be installed with a #ref or a constraint",
+                    self.name
+                );
+            }
+        } else if let (None, Some(constraint)) = (&repo_ref, &self.ref_constraint) {
+            pr.set_message(format!("resolving {constraint} of {repo_url}"));
+            let refs = phases.run("resolve ref", || self.available_refs(repo_url))?;
+            let Some(tag) = resolve_constraint_ref(constraint, &refs)? else {
+                return Err(eyre!("no tags of {repo_url} match {constraint}")).suggestion(format!(
+                    "run `git ls-remote --tags {repo_url}` to list them"
+                ));
+            };
+            repo_ref = Some(tag);
+            pinned_by = Some(constraint.to_string());
+        } else if repo_ref.is_none() {
+            let policy = Settings::get().plugin_pin_policy;
+            if policy != SettingsPluginPinPolicy::Branch {
+                pr.set_message(format!("resolving {policy} of {repo_url}"));
+                let refs = phases.run("resolve ref", || self.available_refs(repo_url))?;
+                repo_ref = resolve_pin_ref(policy, &refs);
+                if repo_ref.is_none() {
+                    warn!(
+                        "no tags found for {repo_url} matching {policy}, using the default branch"
+                    );
+                }
+            }
+            pinned_by = Some(policy.to_string());
+        }
+        Ok((repo_ref, pinned_by))
+    }
+
+    /// with `plugin_auto_gc`, runs `git gc` on the plugin's repo every AUTO_GC_UPDATES updates
+    /// or once it has more than AUTO_GC_LOOSE_OBJECTS loose objects. The plugin's lock is held
+    /// so it doesn't run while another process installs the plugin
+    fn auto_gc(&self, pr: &dyn SingleReport, git: &Git) -> Result<()> {
+        if !Settings::get().plugin_auto_gc {
+            return Ok(());
+        }
+        let plugin = AsdfPlugin::new(self.name.clone());
+        let updates = plugin.record_update_since_gc()?;
+        if updates < AUTO_GC_UPDATES && git.loose_objects()? <= AUTO_GC_LOOSE_OBJECTS {
+            return Ok(());
+        }
+        pr.set_message("running git gc".into());
+        let _lock = self.get_lock(&self.plugin_path, false)?;
+        git.gc()?;
+        plugin.forget_updates_since_gc()
+    }
+
+    /// warns or fails before any of the plugin's hooks run if `platforms` in its mise.plugin.toml
+    /// doesn't list `os`/`arch`, see `plugin_platform_check`
+    fn ensure_supported_platform(&self, os: &str, arch: &str) -> Result<()> {
+        let check = Settings::get().plugin_platform_check;
+        if check == SettingsPluginPlatformCheck::Off {
+            return Ok(());
+        }
+        let toml = MisePluginToml::from_file(&self.plugin_path.join("mise.plugin.toml"))?;
+        if toml.platforms.supports(os, arch) {
+            return Ok(());
+        }
+        let msg = format!("plugin {} does not support {os}-{arch}", self.name);
+        if check == SettingsPluginPlatformCheck::Warn {
+            warn!("⚠️ {msg}, installing anyway");
+            return Ok(());
+        }
+        // so a later install doesn't consider it installed
+        file::remove_all(&self.plugin_path)?;
+        Err(eyre!(msg)).suggestion(
+            "set MISE_PLUGIN_PLATFORM_CHECK=warn to install it anyway if it might still work",
+        )
+    }
+
+    /// fails before any of the plugin's hooks run if a command from `build-requirements` in its
+    /// mise.plugin.toml is missing or too old, see `plugin_build_requirements`
+    fn ensure_build_requirements(&self, phases: &InstallPhases) -> Result<()> {
+        if !Settings::get().plugin_build_requirements {
+            return Ok(());
+        }
+        let toml = MisePluginToml::from_file(&self.plugin_path.join("mise.plugin.toml"))?;
+        if toml.build_requirements.is_empty() {
+            return Ok(());
+        }
+        let result = phases.run("build requirements", || {
+            let unmet = toml
+                .build_requirements
+                .iter()
+                .filter_map(|(bin, min)| unmet_build_requirement(bin, min))
+                .collect_vec();
+            if unmet.is_empty() {
+                return Ok(());
+            }
+            Err(eyre!(
+                "plugin {} has unmet build requirements:\n{}",
+                self.name,
+                unmet.join("\n")
+            ))
+            .suggestion("install them or set MISE_PLUGIN_BUILD_REQUIREMENTS=0 to skip this check")
+        });
+        if result.is_err() {
+            // so it isn't considered installed once the requirements are installed
+            file::remove_all(&self.plugin_path)?;
+        }
+        result
+    }
+
+    /// refs of the remote at `url` without the tags excluded by `plugin_tag_filters`
+    fn available_refs(&self, url: &str) -> Result<Vec<String>> {
+        let refs = Git::available_refs(url)?;
+        match Settings::get().plugin_tag_filters.get(&self.name) {
+            Some(tag_glob) => filter_tag_refs(refs, tag_glob),
+            None => Ok(refs),
+        }
+    }
+
+    /// downloads a plugin published as a tarball/zip and extracts it into the plugin dir
+    fn install_release_asset(
+        &self,
+        pr: &dyn SingleReport,
+        url: &str,
+        phases: &InstallPhases,
+    ) -> Result<()> {
+        let filename = url.rsplit('/').next().unwrap_or_default();
+        let archive = self.fa.downloads_path.join(filename);
+        pr.set_message(format!("downloading {url}"));
+        phases.run("download", || HTTP.download_file(url, &archive, Some(pr)))?;
+
+        pr.set_message(format!("extracting {filename}"));
+        let tmp_extract_path = tempdir_in(&self.fa.downloads_path)?;
+        phases.run("extract", || {
+            if filename.ends_with(".zip") {
+                file::unzip(&archive, tmp_extract_path.path())
+            } else {
+                file::untar(&archive, tmp_extract_path.path())
+            }
+        })?;
+        // release archives generally wrap the plugin in a single top-level directory
+        let mut src = tmp_extract_path.path().to_path_buf();
+        let entries = fs::read_dir(&src)?.collect::<std::io::Result<Vec<_>>>()?;
+        if let [entry] = entries.as_slice() {
+            if entry.path().is_dir() && entry.file_name() != "bin" {
+                src = entry.path();
+            }
+        }
+        file::remove_all(&self.plugin_path)?;
+        file::create_dir_all(self.plugin_path.parent().unwrap())?;
+        file::rename(&src, &self.plugin_path)?;
+        file::write(self.plugin_path.join(RELEASE_ASSET_URL_FILE), url)?;
+        file::remove_all(&self.fa.downloads_path)?;
+        Ok(())
+    }
+
+    /// the url this plugin was downloaded from if it was installed from a release asset
+    fn release_asset_url(&self) -> Option<String> {
+        file::read_to_string(self.plugin_path.join(RELEASE_ASSET_URL_FILE))
+            .ok()
+            .map(|url| url.trim().to_string())
+    }
+
     fn fetch_versions(&self) -> Result<Option<Vec<String>>> {
         if !*env::MISE_USE_VERSIONS_HOST {
             return Ok(None);
@@ -208,10 +795,40 @@ impl Asdf {
             display_stderr();
         }
 
-        Ok(stdout
-            .split_whitespace()
-            .map(|v| regex!(r"^v(\d+)").replace(v, "$1").to_string())
-            .collect())
+        Ok(parse_remote_versions(&stdout))
+    }
+
+    /// the expired cached remote versions followed by the ones the plugin's `list-all-after`
+    /// script prints when given the newest cached version, so only new versions are fetched
+    /// from APIs that can page from a version. None if the plugin has no such script or there
+    /// is nothing cached to continue from
+    fn fetch_remote_versions_after(&self) -> Result<Option<Vec<String>>> {
+        if !self
+            .script_man
+            .script_exists(&Script::ListAllAfter(String::new()))
+        {
+            return Ok(None);
+        }
+        let Some(mut versions) = self.remote_version_cache.get_expired() else {
+            return Ok(None);
+        };
+        let Some(cursor) = versions.last().cloned() else {
+            return Ok(None);
+        };
+        let stdout = self
+            .script_man
+            .read(&Script::ListAllAfter(cursor.clone()))?;
+        let new_versions = parse_remote_versions(&stdout)
+            .into_iter()
+            .filter(|v| !versions.contains(v))
+            .collect_vec();
+        debug!(
+            "{}: {} remote versions after {cursor}",
+            self.name,
+            new_versions.len()
+        );
+        versions.extend(new_versions);
+        Ok(Some(versions))
     }
 
     fn fetch_legacy_filenames(&self) -> Result<Vec<String>> {
@@ -386,6 +1003,34 @@ impl Asdf {
         Ok(sm)
     }
 
+    /// moving the plugin to an older commit is refused unless allowed or confirmed since the
+    /// older scripts may not work with what was installed by the newer ones
+    fn ensure_downgrade_allowed(&self, target: &str, allow_downgrade: bool) -> Result<()> {
+        let plugin = style(&self.name).blue().for_stderr();
+        warn!("plugin:{plugin} {target} is older than the current commit, this is a downgrade");
+        if allow_downgrade
+            || Settings::get().yes
+            || prompt::confirm(format!("Downgrade plugin {} to {target}?", self.name))?
+        {
+            return Ok(());
+        }
+        Err(eyre!("plugin:{plugin} not downgrading to {target}"))
+            .suggestion("run `mise plugins update --allow-downgrade` to update to an older ref")
+    }
+
+    /// compares the plugin's scripts against the ones recorded at install time so users notice
+    /// an update changing what the plugin will run
+    fn warn_if_scripts_changed(&self) -> Result<()> {
+        if AsdfPlugin::new(self.name.clone()).record_scripts_hash()? {
+            warn!(
+                "plugin:{} scripts changed in this update, review them in {}",
+                style(&self.name).blue().for_stderr(),
+                display_path(self.plugin_path.join("bin"))
+            );
+        }
+        Ok(())
+    }
+
     fn exec_hook(&self, pr: &dyn SingleReport, hook: &str) -> Result<()> {
         self.exec_hook_env(pr, hook, Default::default())
     }
@@ -405,6 +1050,19 @@ impl Asdf {
         Ok(())
     }
 
+    /// copies a hook script out of the plugin dir so it can be run after the plugin is removed
+    fn stash_hook(&self, hook: &str) -> Result<Option<tempfile::TempDir>> {
+        let script = Script::Hook(hook.to_string());
+        if !self.script_man.script_exists(&script) {
+            return Ok(None);
+        }
+        let script = self.script_man.get_script_path(&script);
+        let dir = tempfile::tempdir()?;
+        file::create_dir_all(dir.path().join("bin"))?;
+        file::copy(&script, dir.path().join("bin").join(hook))?;
+        Ok(Some(dir))
+    }
+
     fn exec_hook_post_plugin_update(
         &self,
         pr: &dyn SingleReport,
@@ -425,6 +1083,25 @@ impl Asdf {
         }
         Ok(())
     }
+
+    fn exec_hook_post_plugin_checkout(
+        &self,
+        pr: &dyn SingleReport,
+        pre: String,
+        post: String,
+    ) -> Result<()> {
+        if pre != post {
+            let env = [
+                ("MISE_PLUGIN_PREV_SHA", pre),
+                ("MISE_PLUGIN_POST_SHA", post),
+            ]
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect();
+            self.exec_hook_env(pr, "post-plugin-checkout", env)?;
+        }
+        Ok(())
+    }
 }
 
 fn build_script_man(name: &str, plugin_path: &Path) -> ScriptManager {
@@ -457,6 +1134,12 @@ impl Backend for Asdf {
     fn fa(&self) -> &BackendArg {
         &self.fa
     }
+    fn supports(&self, capability: Capability) -> bool {
+        matches!(
+            capability,
+            Capability::RefPinning | Capability::EnvInjection
+        )
+    }
 
     fn get_plugin_type(&self) -> PluginType {
         PluginType::Asdf
@@ -473,7 +1156,22 @@ impl Backend for Asdf {
 
     fn _list_remote_versions(&self) -> Result<Vec<String>> {
         self.remote_version_cache
-            .get_or_try_init(|| self.fetch_remote_versions())
+            .get_or_try_init(|| {
+                let versions = match self.fetch_remote_versions_after() {
+                    Ok(Some(versions)) => versions,
+                    Ok(None) => self.fetch_remote_versions()?,
+                    Err(err) => {
+                        warn!("{}: refetching all remote versions: {err:#}", self.name);
+                        self.fetch_remote_versions()?
+                    }
+                };
+                // an error rather than an empty list so it isn't cached as if there were no
+                // versions and isn't reported as a version not matching the request
+                if versions.is_empty() {
+                    Err(NoRemoteVersions(self.name.clone()))?;
+                }
+                Ok(versions)
+            })
             .wrap_err_with(|| {
                 eyre!(
                     "Failed listing remote versions for plugin {}",
@@ -500,55 +1198,168 @@ impl Backend for Asdf {
 
     fn get_remote_url(&self) -> Option<String> {
         let git = Git::new(self.plugin_path.to_path_buf());
-        git.get_remote_url().or_else(|| self.repo_url.clone())
+        git.get_remote_url()
+            .or_else(|| self.release_asset_url())
+            .or_else(|| self.repo_url.clone())
     }
 
     fn is_installed(&self) -> bool {
         self.plugin_path.exists()
     }
 
+    fn health_probe(&self, timeout: Duration) -> Health {
+        let plugin_path = self.plugin_path.clone();
+        probe_with_timeout(timeout, move || {
+            if let Err(err) = fs::read_dir(&plugin_path) {
+                return Health::Down(format!(
+                    "{} is not accessible: {err}",
+                    display_path(&plugin_path)
+                ));
+            }
+            let list_all = plugin_path.join("bin/list-all");
+            if !file::is_executable(&list_all) {
+                return Health::Down(format!("{} is not executable", display_path(&list_all)));
+            }
+            Health::Ok
+        })
+    }
+
     fn is_installed_err(&self) -> eyre::Result<()> {
         if self.is_installed() {
             return Ok(());
         }
-        Err(eyre!("asdf plugin {} is not installed", self.id())
+        Err(Report::new(PluginNotInstalled(self.name.clone()))
+            .wrap_err(format!("asdf plugin {} is not installed", self.id()))
             .suggestion("run with --yes to install plugin automatically"))
     }
 
-    fn ensure_installed(&self, mpr: &MultiProgressReport, force: bool) -> Result<()> {
+    fn ensure_installed(&self, mpr: &MultiProgressReport, force: bool) -> Result<InstallStatus> {
         let config = Config::get();
         let settings = Settings::try_get()?;
-        if !force {
-            if self.is_installed() {
-                return Ok(());
+        if !force && self.is_installed() {
+            return Ok(InstallStatus::AlreadyInstalled);
+        }
+        // only checked when installing since it lists every plugin. On a case-insensitive
+        // filesystem the other plugin makes this one look installed so it is never reinstalled
+        // over the other one without --force
+        if let Some(other) = self.case_collision()? {
+            if settings.paranoid {
+                return Err(eyre!(
+                    "plugin {} collides with the installed plugin {other}",
+                    self.name
+                ))
+                .suggestion(
+                    "plugin names differing only by case are not allowed in paranoid mode",
+                );
             }
-            if !settings.yes && self.repo_url.is_none() {
-                let url = self.get_repo_url(&config).unwrap_or_default();
-                if !is_trusted_plugin(self.name(), &url) {
+            if !mpr.is_silent() {
+                warn!(
+                    "⚠️ {} differs only by case from the installed plugin {}, they may overwrite each other",
+                    style(&self.name).blue(),
+                    style(&other).blue()
+                );
+            }
+        }
+        ensure_plugins_dir_writable(&self.plugin_path)?;
+        if !force {
+            // an undefined variable in the url fails here rather than prompting for a url that
+            // can't be installed
+            let url = match self.untransformed_repo_url(&config)? {
+                Some(_) => self.get_repo_url(&config)?,
+                None => String::new(),
+            };
+            if is_shadowing_shorthand(self.name(), &url) {
+                if !mpr.is_silent() {
                     warn!(
-                        "⚠️ {} is a community-developed plugin – {}",
+                        "⚠️ {} shadows the official shorthand for {} – {}",
+                        style(url.trim_end_matches(".git")).yellow(),
                         style(&self.name).blue(),
-                        style(url.trim_end_matches(".git")).yellow()
+                        style(DEFAULT_SHORTHANDS[self.name()].trim_end_matches(".git")).yellow(),
                     );
-                    if settings.paranoid {
-                        bail!("Paranoid mode is enabled, refusing to install community-developed plugin");
-                    }
-                    if !prompt::confirm_with_all(format!(
-                        "Would you like to install {}?",
+                }
+                if settings.paranoid && !settings.yes {
+                    self.ensure_can_prompt(mpr)?;
+                    if !prompt::confirm(format!(
+                        "Paranoid mode is enabled, would you like to install {} anyway?",
                         self.name
                     ))? {
                         Err(PluginNotInstalled(self.name.clone()))?
                     }
                 }
             }
+            let untrusted = self.repo_url.is_none() && !self.is_trusted(&config, &url);
+            // an explicit url isn't prompted for but is still up to the callback
+            if let Some(allowed) = (untrusted || self.repo_url.is_some())
+                .then(|| trust_callback(&self.name, &url))
+                .flatten()
+            {
+                // paranoid mode still refuses community plugins unless --yes is passed
+                if !allowed || (settings.paranoid && !settings.yes) {
+                    Err(PluginNotTrusted(self.name.clone()))?
+                }
+            } else if untrusted && !settings.yes {
+                if !mpr.is_silent() {
+                    warn!(
+                        "⚠️ {} is a community-developed plugin – {}",
+                        style(&self.name).blue(),
+                        style(url.trim_end_matches(".git")).yellow()
+                    );
+                }
+                // paranoid refuses even if "All" was answered earlier in this command
+                if settings.paranoid {
+                    Err(PluginNotTrusted(self.name.clone()))?
+                }
+                self.ensure_can_prompt(mpr)?;
+                if !prompt::confirm_with_all(format!("Would you like to install {}?", self.name))? {
+                    Err(PluginNotInstalled(self.name.clone()))?
+                }
+            }
+        }
+        if let Some(dev_ref) = &self.dev_ref {
+            // never trusted, even if the plugin itself is
+            if !mpr.is_silent() {
+                warn!(
+                    "⚠️ {} is installed from {dev_ref}, an untrusted development ref",
+                    style(&self.name).blue()
+                );
+            }
+            if settings.paranoid {
+                Err(PluginNotTrusted(self.name.clone()))?
+            }
+            if !settings.yes {
+                self.ensure_can_prompt(mpr)?;
+                if !prompt::confirm(format!(
+                    "Would you like to install {} from {dev_ref}?",
+                    self.name
+                ))? {
+                    Err(PluginNotInstalled(self.name.clone()))?
+                }
+            }
         }
+        self.verify_remote_reachable(&config)?;
         let prefix = format!("plugin:{}", style(&self.name).blue().for_stderr());
         let pr = mpr.add(&prefix);
         let _lock = self.get_lock(&self.plugin_path, force)?;
-        self.install(pr.as_ref())
+        let subject = format!("plugin:{}", self.name);
+        timings::time(&subject, "total", || self.install(pr.as_ref()))?;
+        let required = AsdfPlugin::new(self.name.clone()).required_settings()?;
+        if !mpr.is_silent() {
+            for setting in plugins::disabled_settings(&required)? {
+                warn!(
+                    "{} requires the {setting} setting, enable it with `mise settings set {setting} true`",
+                    style(&self.name).blue().for_stderr()
+                );
+            }
+        }
+        Ok(InstallStatus::Installed)
     }
 
-    fn update(&self, pr: &dyn SingleReport, gitref: Option<String>) -> Result<()> {
+    fn update(
+        &self,
+        pr: &dyn SingleReport,
+        gitref: Option<String>,
+        allow_downgrade: bool,
+    ) -> Result<()> {
         let plugin_path = self.plugin_path.to_path_buf();
         if plugin_path.is_symlink() {
             warn!(
@@ -557,6 +1368,16 @@ impl Backend for Asdf {
             );
             return Ok(());
         }
+        if let Some(url) = self.release_asset_url() {
+            self.install_release_asset(
+                pr,
+                &url,
+                &InstallPhases::new(&self.name, self.cancel.clone()),
+            )?;
+            self.warn_if_scripts_changed()?;
+            pr.finish_with_message(url);
+            return Ok(());
+        }
         let git = Git::new(plugin_path);
         if !git.is_repo() {
             warn!(
@@ -565,13 +1386,62 @@ impl Backend for Asdf {
             );
             return Ok(());
         }
+        let status = git.status()?;
+        if status.is_dirty() {
+            let changes = status.modified.iter().chain(&status.untracked).join(", ");
+            if Settings::get().plugin_update_refuse_dirty {
+                return Err(eyre!(
+                    "plugin:{} has local changes, not updating: {changes}",
+                    style(&self.name).blue().for_stderr()
+                ))
+                .suggestion(format!(
+                    "commit or discard the changes in {}",
+                    display_path(&self.plugin_path)
+                ));
+            }
+            warn!(
+                "plugin:{} has local changes that may be overwritten by the update: {changes}",
+                style(&self.name).blue().for_stderr()
+            );
+        }
         pr.set_message("updating git repo".into());
-        let (pre, post) = git.update(gitref)?;
-        let sha = git.current_sha_short()?;
+        AsdfPlugin::new(self.name.clone()).invalidate_git_head_cache()?;
+        // fetching the current branch already moves HEAD so this is what it was before the update
+        let before = git.current_sha()?;
+        let plugin = AsdfPlugin::new(self.name.clone());
+        let refspec = Settings::get()
+            .plugin_fetch_refspecs
+            .get(&self.name)
+            .cloned();
+        // updating to another ref stops following the PR or branch installed with --pr/--branch
+        let leaves_dev_ref = gitref.is_some();
+        let target = match (gitref, plugin.dev_ref(), refspec) {
+            (None, Some(dev_ref), _) => git.fetch_refspec(&dev_ref)?,
+            (None, None, Some(refspec)) => git
+                .fetch_refspec(&refspec)
+                .wrap_err_with(|| format!("plugin_fetch_refspecs.{}", self.name))?,
+            (gitref, _, _) => git.fetch(gitref)?,
+        };
+        let downgrade = git.is_behind(&target, &before)?;
+        if downgrade {
+            self.ensure_downgrade_allowed(&target, allow_downgrade)?;
+        }
+        let (_, post) = git.checkout(&target)?;
+        if leaves_dev_ref {
+            plugin.record_dev_ref(None)?;
+        }
+        let sha = git.current_sha_short()?;
         let repo_url = self.get_remote_url().unwrap_or_default();
-        self.exec_hook_post_plugin_update(pr, pre, post)?;
+        let changelog = update_changelog(&git, &before, &post);
+        self.exec_hook_post_plugin_update(pr, before.clone(), post.clone())?;
+        self.exec_hook_post_plugin_checkout(pr, before, post)?;
+        self.warn_if_scripts_changed()?;
+        if let Err(err) = self.auto_gc(pr, &git) {
+            warn!("failed to gc plugin:{}: {err:#}", self.name);
+        }
         pr.finish_with_message(format!(
-            "{repo_url}#{}",
+            "{}{repo_url}#{}{changelog}",
+            if downgrade { "downgraded to " } else { "" },
             style(&sha).bright().yellow().for_stderr(),
         ));
         Ok(())
@@ -581,8 +1451,19 @@ impl Backend for Asdf {
         if !self.is_installed() {
             return Ok(());
         }
+        ensure_plugins_dir_writable(&self.plugin_path)?;
         self.exec_hook(pr, "pre-plugin-remove")?;
         pr.set_message("uninstalling".into());
+        // the plugin's own scripts are removed with it so these need to be read beforehand
+        let post_remove_hook = self.stash_hook("post-plugin-remove")?;
+        let plugin_shims_dir = self.plugin_path.join("shims");
+        let plugin_shims = match plugin_shims_dir.exists() {
+            true => file::ls(&plugin_shims_dir)?
+                .into_iter()
+                .filter_map(|p| p.file_name().map(|f| f.to_string_lossy().to_string()))
+                .collect(),
+            false => vec![],
+        };
 
         let rmdir = |dir: &Path| {
             if !dir.exists() {
@@ -597,9 +1478,25 @@ impl Backend for Asdf {
             })
         };
 
-        rmdir(&self.plugin_path)?;
-
-        Ok(())
+        // cleanup still runs if only part of the plugin dir could be removed
+        let removed = rmdir(&self.plugin_path);
+        // worktrees can't be used without the plugin's repo
+        if self.plugin_path == dirs::plugin_path(&self.name) {
+            rmdir(&worktrees_dir(&self.name))?;
+        }
+        shims::remove_plugin_shims(&plugin_shims_dir, &plugin_shims)?;
+        if let Some(hook_dir) = post_remove_hook {
+            let mut sm = self.script_man.clone();
+            sm.plugin_path = hook_dir.path().to_path_buf();
+            pr.set_message("executing post-plugin-remove hook".into());
+            sm.run_by_line(&Script::Hook("post-plugin-remove".into()), pr)?;
+        }
+        let plugin = AsdfPlugin::new(self.name.clone());
+        plugin.forget_scripts_hash()?;
+        plugin.record_shorthand_url(None)?;
+        plugin.record_dev_ref(None)?;
+        plugin.forget_updates_since_gc()?;
+        removed
     }
 
     fn get_aliases(&self) -> Result<BTreeMap<String, String>> {
@@ -721,14 +1618,28 @@ impl Backend for Asdf {
             sm.prepend_path(p);
         }
 
-        let run_script = |script| sm.run_by_line(script, ctx.pr.as_ref());
+        let install_path = ctx.tv.install_path();
+        disk_space::ensure_free_space(&install_path, 0)?;
+        let subject = ctx.tv.to_string();
+        let run_script = |script: &Script, phase| {
+            timings::time(&subject, phase, || sm.run_by_line(script, ctx.pr.as_ref()))
+                .map_err(|err| disk_space::explain_failure(&install_path, err))
+        };
 
         if sm.script_exists(&Download) {
             ctx.pr.set_message("downloading".into());
-            run_script(&Download)?;
+            run_script(&Download, "download")?;
+            disk_space::ensure_free_space(&install_path, 0)?;
         }
         ctx.pr.set_message("installing".into());
-        run_script(&Install)?;
+        run_script(&Install, "install")?;
+        let plugin = AsdfPlugin::new(self.name.clone());
+        if let Err(err) = plugin.record_provenance(&ctx.tv) {
+            warn!(
+                "failed to record where {} was installed from: {err:#}",
+                ctx.tv
+            );
+        }
         file::remove_dir(&self.fa.downloads_path)?;
 
         Ok(())
@@ -847,6 +1758,14 @@ impl ExternalPluginCache {
     }
 }
 
+/// versions printed by `list-all` or `list-all-after`, without the "v" prefix
+fn parse_remote_versions(stdout: &str) -> Vec<String> {
+    stdout
+        .split_whitespace()
+        .map(|v| regex!(r"^v(\d+)").replace(v, "$1").to_string())
+        .collect()
+}
+
 fn render_cache_key(config: &Config, tv: &ToolVersion, cache_key: &[String]) -> String {
     let elements = cache_key
         .iter()
@@ -877,28 +1796,391 @@ fn parse_template(config: &Config, tv: &ToolVersion, tmpl: &str) -> eyre::Result
     .wrap_err_with(|| eyre!("failed to parse template: {tmpl}"))
 }
 
-fn normalize_remote(remote: &str) -> eyre::Result<String> {
-    let url = Url::parse(remote)?;
-    let host = url.host_str().unwrap();
+/// written to the plugin dir to remember where a release asset plugin came from
+pub const RELEASE_ASSET_URL_FILE: &str = ".mise-release-asset-url";
+
+fn is_release_asset(url: &str) -> bool {
+    [".tar.gz", ".tgz", ".zip"]
+        .iter()
+        .any(|ext| url.ends_with(ext))
+}
+
+pub fn normalize_remote(remote: &str) -> eyre::Result<String> {
+    let url = Url::parse(&expand_git_host_shorthand(remote))?;
+    let host = url.host_str().unwrap_or_default();
     let path = url.path().trim_end_matches(".git");
     Ok(format!("{host}{path}"))
 }
 
-fn is_trusted_plugin(name: &str, remote: &str) -> bool {
-    let normalized_url = normalize_remote(remote).unwrap_or("INVALID_URL".into());
-    let is_shorthand = DEFAULT_SHORTHANDS
-        .get(name)
-        .is_some_and(|s| normalize_remote(s).unwrap_or_default() == normalized_url);
-    let is_mise_url = normalized_url.starts_with("github.com/mise-plugins/");
+/// decides whether a community plugin, or one with an explicit url, may be installed from its name
+/// and normalized url (see [`normalize_remote`]), true to install it
+pub type TrustCallback = Box<dyn Fn(&str, &str) -> bool + Send + Sync>;
+
+/// set by applications embedding mise to decide which community plugins are installed, e.g.:
+/// by asking a policy service, instead of prompting
+static TRUST_CALLBACK: Mutex<Option<TrustCallback>> = Mutex::new(None);
+
+/// replaces the prompt to install community plugins with `callback`, which is asked even with
+/// --yes and for plugins installed from an explicit url. None restores the prompt
+pub fn set_trust_callback(callback: Option<TrustCallback>) {
+    *TRUST_CALLBACK.lock().unwrap() = callback;
+}
+
+/// None if no callback is set
+fn trust_callback(name: &str, url: &str) -> Option<bool> {
+    let callback = TRUST_CALLBACK.lock().unwrap();
+    let url = normalize_remote(url).unwrap_or_else(|_| url.to_string());
+    callback.as_ref().map(|cb| cb(name, &url))
+}
+
+/// how much a plugin named `name` installed from `remote` can be trusted, see docs/paranoid.md
+pub fn trust_status(name: &str, remote: &str) -> TrustStatus {
+    let Ok(normalized_url) = normalize_remote(remote) else {
+        return TrustStatus::Unknown(format!("invalid remote url {remote}"));
+    };
+    match DEFAULT_SHORTHANDS.get(name) {
+        Some(s) if normalize_remote(s).unwrap_or_default() != normalized_url => {
+            TrustStatus::Shadowing(format!(
+                "shadows the official shorthand {}",
+                s.trim_end_matches(".git")
+            ))
+        }
+        _ if normalized_url.starts_with("github.com/mise-plugins/") => {
+            TrustStatus::Trusted("maintained by the mise team".into())
+        }
+        None => TrustStatus::Unknown("not a default shorthand".into()),
+        Some(_) if TRUSTED_SHORTHANDS.contains(name) => {
+            TrustStatus::Trusted("first-party plugin".into())
+        }
+        Some(_) => TrustStatus::Community("community-developed plugin".into()),
+    }
+}
+
+/// why `bin` doesn't meet the `min` version ("*" for any) from `build-requirements`, None if it
+/// does. The version is the first one in the output of `<bin> --version`
+fn unmet_build_requirement(bin: &str, min: &str) -> Option<String> {
+    let Some(path) = file::which(bin) else {
+        return Some(format!("{bin} is not on PATH"));
+    };
+    if min == "*" {
+        return None;
+    }
+    let output = cmd!(path, "--version")
+        .stderr_to_stdout()
+        .unchecked()
+        .read()
+        .unwrap_or_default();
+    match regex!(r"\d+(\.\d+)*").find(&output) {
+        Some(found) if Versioning::new(found.as_str()) < Versioning::new(min) => Some(format!(
+            "{bin} {} is older than the required {min}",
+            found.as_str()
+        )),
+        Some(_) => None,
+        None => Some(format!(
+            "the version of {bin} is unknown, {min} or newer is required"
+        )),
+    }
+}
+
+/// compares `remote` to the default shorthand named `name`, see [`crate::plugins::Plugin::compare_to_shorthand`]
+pub fn compare_to_shorthand(name: &str, remote: &str) -> eyre::Result<Option<ShorthandComparison>> {
+    let Some(shorthand) = DEFAULT_SHORTHANDS.get(name) else {
+        return Ok(None);
+    };
+    let remote = normalize_remote(remote)?;
+    let shorthand = normalize_remote(shorthand)?;
+    let host = |r: &str| r.split('/').next().unwrap_or_default().to_string();
+    Ok(Some(if remote == shorthand {
+        ShorthandComparison::Match
+    } else if host(&remote) != host(&shorthand) {
+        ShorthandComparison::DifferentHost { remote, shorthand }
+    } else {
+        ShorthandComparison::DifferentPath { remote, shorthand }
+    }))
+}
+
+/// the url installing `name` from its shorthand would clone now, None if it has no shorthand
+pub fn shorthand_remote(name: &str) -> Result<Option<String>> {
+    let Some(url) = get_shorthands(&Settings::get()).remove(name) else {
+        return Ok(None);
+    };
+    let url = transform_url(&expand_git_host_shorthand(&expand_url_env(&url)?))?;
+    Ok(Some(Git::split_url_and_ref(&url).0))
+}
+
+/// whether both point at the same repository, local paths are compared as they are
+pub fn same_remote(a: &str, b: &str) -> bool {
+    match (normalize_remote(a), normalize_remote(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// expands `${VAR}` in a plugin url from the environment, e.g.: to parameterize the host of an
+/// internal git server. Undefined variables are an error rather than expanding to nothing
+fn expand_url_env(url: &str) -> Result<String> {
+    let mut undefined = vec![];
+    let expanded =
+        regex!(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").replace_all(url, |caps: &regex::Captures| {
+            env::var(&caps[1]).unwrap_or_else(|_| {
+                undefined.push(caps[1].to_string());
+                String::new()
+            })
+        });
+    if !undefined.is_empty() {
+        return Err(eyre!(
+            "plugin url {url} uses undefined environment variables: {}",
+            undefined.join(", ")
+        ))
+        .suggestion("set them or replace them in the plugin url");
+    }
+    Ok(expanded.to_string())
+}
+
+/// runs the plugin_url_transform command on `url`. The result is remembered so the url that
+/// trust is evaluated on is the one that gets cloned even if the command is not deterministic
+fn transform_url(url: &str) -> Result<String> {
+    static TRANSFORMED: Lazy<Mutex<HashMap<(String, String), String>>> =
+        Lazy::new(Default::default);
+    let Some(transform) = Settings::get().plugin_url_transform.clone() else {
+        return Ok(url.to_string());
+    };
+    let mut transformed = TRANSFORMED.lock().unwrap();
+    let key = (transform, url.to_string());
+    if let Some(url) = transformed.get(&key) {
+        return Ok(url.clone());
+    }
+    let out = cmd!("sh", "-c", &key.0, "mise", url)
+        .read()
+        .wrap_err_with(|| format!("plugin_url_transform failed for {url}"))?;
+    let new_url = match out.trim() {
+        "" => url.to_string(),
+        out => out.to_string(),
+    };
+    debug!("plugin_url_transform: {url} -> {new_url}");
+    transformed.insert(key, new_url.clone());
+    Ok(new_url)
+}
+
+pub fn is_trusted_plugin(name: &str, remote: &str) -> bool {
+    !matches!(trust_status(name, remote), TrustStatus::Community(_))
+}
+
+/// picks the tag to check out for `policy` from `refs` which are in version order
+fn resolve_pin_ref(policy: SettingsPluginPinPolicy, refs: &[String]) -> Option<String> {
+    let mut tags = refs
+        .iter()
+        .rev()
+        .filter_map(|r| r.strip_prefix("refs/tags/"));
+    match policy {
+        SettingsPluginPinPolicy::Branch => None,
+        SettingsPluginPinPolicy::LatestTag => tags.next(),
+        SettingsPluginPinPolicy::LatestSemverTag => {
+            tags.find(|t| regex!(r"^v?\d+\.\d+\.\d+$").is_match(t))
+        }
+    }
+    .map(String::from)
+}
+
+/// the highest tag in `refs` matching the semver `constraint` (e.g.: "^1.2"), ignoring a "v"
+/// prefix on the tags. If both "v1.2.0" and "1.2.0" exist, the last one in `refs` is used
+fn resolve_constraint_ref(constraint: &str, refs: &[String]) -> Result<Option<String>> {
+    let mut tags: HashMap<String, Vec<&str>> = HashMap::new();
+    for tag in refs.iter().filter_map(|r| r.strip_prefix("refs/tags/")) {
+        let version = tag.strip_prefix('v').unwrap_or(tag).to_string();
+        tags.entry(version).or_default().push(tag);
+    }
+    let matching = super::range_match_filter(tags.keys().cloned().collect(), constraint)?;
+    let Some(version) = matching
+        .into_iter()
+        .filter_map(|v| Versioning::new(&v).map(|version| (version, v)))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, v)| v)
+    else {
+        return Ok(None);
+    };
+    let candidates = &tags[&version];
+    let tag = candidates.last().unwrap();
+    if candidates.len() > 1 {
+        warn!(
+            "tags {} are all version {version}, using {tag}",
+            candidates.join(" and ")
+        );
+    }
+    Ok(Some(tag.to_string()))
+}
+
+/// how many commit subjects `update` lists after updating a plugin
+const UPDATE_CHANGELOG_LIMIT: usize = 10;
+
+/// `plugin_auto_gc` thresholds, the updates since the last gc or the loose objects in the repo
+const AUTO_GC_UPDATES: u32 = 20;
+const AUTO_GC_LOOSE_OBJECTS: u32 = 1000;
+
+/// the subjects of the commits an update brought in, one per line after the finish message.
+/// Empty if it didn't move the plugin forward
+fn update_changelog(git: &Git, pre: &str, post: &str) -> String {
+    if pre == post {
+        return String::new();
+    }
+    let (subjects, total) = match git.log_subjects(pre, post, UPDATE_CHANGELOG_LIMIT) {
+        Ok(log) => log,
+        Err(err) => {
+            // e.g.: a shallow clone missing the previous commit
+            debug!("failed to list the commits in {pre}..{post}: {err:#}");
+            return String::new();
+        }
+    };
+    let mut changelog = subjects.iter().map(|s| format!("\n  {s}")).join("");
+    if total > subjects.len() {
+        changelog.push_str(&format!("\n  …and {} more", total - subjects.len()));
+    }
+    changelog
+}
+
+/// true if `name` is a default shorthand but `remote` points somewhere else
+fn is_shadowing_shorthand(name: &str, remote: &str) -> bool {
+    matches!(trust_status(name, remote), TrustStatus::Shadowing(_))
+}
+
+/// the phases of installing a plugin, each one is timed for `--timings` and the one in progress
+/// is recorded in the plugin's last_error if the install fails. No more phases run once `cancel`
+/// is set
+struct InstallPhases {
+    subject: String,
+    current: Cell<&'static str>,
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+impl InstallPhases {
+    fn new(name: &str, cancel: Option<Arc<AtomicBool>>) -> Self {
+        Self {
+            subject: format!("plugin:{name}"),
+            current: Cell::new("install"),
+            cancel,
+        }
+    }
+
+    fn run<T>(&self, phase: &'static str, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        if self
+            .cancel
+            .as_ref()
+            .is_some_and(|c| c.load(Ordering::Relaxed))
+        {
+            Err(Cancelled(self.subject.clone()))?;
+        }
+        self.current.set(phase);
+        timings::time(&self.subject, phase, f)
+    }
+}
+
+/// how a plugin's repo is cloned, see `plugin_tag_filters` and `plugin_reference_dir`
+enum CloneMethod {
+    /// without tags, then fetching only the tags matching the glob
+    TagFilter(String),
+    /// borrowing objects from a local clone, falling back to the default if that fails
+    Reference(PathBuf),
+    Default,
+}
+
+/// a clone of a plugin repo at a given ref kept under ~/.cache/mise/plugin-clones so installing
+/// the same plugin again (e.g.: in ephemeral CI with a persisted cache) doesn't hit the network
+struct CloneCache {
+    path: PathBuf,
+    /// url#ref recorded alongside the clone, checked before reuse in case of hash collisions
+    /// or an entry left behind by an interrupted populate
+    key: String,
+}
+
+impl CloneCache {
+    fn new(url: &str, gitref: Option<&str>) -> Self {
+        let key = match gitref {
+            Some(gitref) => format!("{url}#{gitref}"),
+            None => url.to_string(),
+        };
+        let path = dirs::CACHE.join("plugin-clones").join(hash_to_str(&key));
+        Self { path, key }
+    }
+
+    fn key_file(&self) -> PathBuf {
+        self.path.join(".git").join("mise-clone-cache")
+    }
+
+    /// shas before and after checking out the ref when the clone was cached, e.g.: "abc123 def456"
+    fn checkout_file(&self) -> PathBuf {
+        self.path.join(".git").join("mise-clone-cache-checkout")
+    }
+
+    /// the checkout made before the clone was cached, to pass on to post-plugin-checkout
+    fn checkout(&self) -> Option<(String, String)> {
+        let checkout = file::read_to_string(self.checkout_file()).ok()?;
+        let (pre, post) = checkout.trim().split_once(' ')?;
+        Some((pre.to_string(), post.to_string()))
+    }
+
+    fn is_valid(&self) -> bool {
+        let (url, _) = Git::split_url_and_ref(&self.key);
+        file::read_to_string(self.key_file()).is_ok_and(|key| key == self.key)
+            && Git::new(self.path.clone()).get_remote_url() == Some(url)
+    }
+
+    /// locks the entry for the caller to copy from or [`CloneCache::populate`] it
+    fn lock(&self) -> Result<fslock::LockFile> {
+        LockFile::new(&self.path)
+            .with_callback(|l| {
+                debug!("waiting for lock on {}", display_path(l));
+            })
+            .lock()
+    }
+
+    /// replaces the entry with a clone of `from`, while holding [`CloneCache::lock`]
+    fn populate(&self, from: &Path, checkout: Option<&(String, String)>) -> Result<()> {
+        let (url, _) = Git::split_url_and_ref(&self.key);
+        file::remove_all(&self.path)?;
+        Git::new(self.path.clone()).clone_local(from, &url)?;
+        if let Some((pre, post)) = checkout {
+            file::write(self.checkout_file(), format!("{pre} {post}"))?;
+        }
+        file::write(self.key_file(), &self.key)?;
+        Ok(())
+    }
+}
 
-    !is_shorthand || is_mise_url || TRUSTED_SHORTHANDS.contains(name)
+/// fails before touching anything if plugins can't be written to (e.g.: a read-only mount)
+/// rather than erroring out deep inside a clone/remove and leaving a partial plugin behind
+fn ensure_plugins_dir_writable(plugin_path: &Path) -> Result<()> {
+    let plugins_dir = plugin_path.parent().unwrap();
+    // the plugins dir may not have been created yet, check the dir it would be created in
+    let Some(dir) = plugins_dir.ancestors().find(|p| p.exists()) else {
+        return Ok(());
+    };
+    let writable = match dir.metadata() {
+        Ok(md) if md.permissions().readonly() => false,
+        Ok(_) => tempfile::tempfile_in(dir).is_ok(),
+        Err(_) => true,
+    };
+    if !writable {
+        return Err(eyre!(
+            "plugins directory {} is read-only",
+            display_path(plugins_dir)
+        ))
+        .suggestion("set MISE_PLUGINS_DIR to a writable directory to manage plugins");
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::{Instant, SystemTime};
+
+    use filetime::FileTime;
+
     use test_log::test;
 
-    use crate::test::reset;
+    use crate::output::tests::STDERR;
+    use crate::test;
+    use crate::test::{reset, serve_responses, Response};
+    use crate::ui::progress_report::{LogFileReport, QuietReport};
 
     use super::*;
 
@@ -908,4 +2190,1682 @@ mod tests {
         let plugin = Asdf::new(String::from("dummy"));
         assert!(format!("{:?}", plugin).starts_with("ExternalPlugin { name: \"dummy\""));
     }
+
+    #[test]
+    fn test_post_plugin_checkout_hook() {
+        reset();
+        let remote = tempfile::tempdir().unwrap();
+        let hook = remote.path().join("bin/post-plugin-checkout");
+        file::create_dir_all(hook.parent().unwrap()).unwrap();
+        file::write(
+            &hook,
+            "#!/usr/bin/env bash\necho \"$MISE_PLUGIN_PREV_SHA $MISE_PLUGIN_POST_SHA\" > \"$MISE_PLUGIN_PATH/checkout.txt\"\n",
+        )
+        .unwrap();
+        file::make_executable(&hook).unwrap();
+        let first = test::git_init(remote.path(), "first");
+        cmd!("git", "-C", remote.path(), "checkout", "-q", "-b", "v2")
+            .run()
+            .unwrap();
+        let second = test::git_commit(remote.path(), "second");
+        cmd!("git", "-C", remote.path(), "checkout", "-q", "main")
+            .run()
+            .unwrap();
+
+        let mut plugin = Asdf::new(String::from("checkout-hook"));
+        plugin.repo_url = Some(format!("{}#v2", remote.path().display()));
+        let pr = QuietReport::new();
+        plugin.install(&pr).unwrap();
+        let checkout = plugin.plugin_path.join("checkout.txt");
+        assert_eq!(
+            file::read_to_string(&checkout).unwrap().trim(),
+            format!("{first} {second}")
+        );
+
+        plugin.update(&pr, Some("main".into()), false).unwrap();
+        assert_eq!(
+            file::read_to_string(&checkout).unwrap().trim(),
+            format!("{second} {first}")
+        );
+        plugin.uninstall(&pr).unwrap();
+    }
+
+    #[test]
+    fn test_update_tracked_branch_hooks() {
+        reset();
+        let hook = "echo \"$MISE_PLUGIN_PREV_SHA $MISE_PLUGIN_POST_SHA\" > \"$MISE_PLUGIN_PATH/checkout.txt\"";
+        let update_hook =
+            "echo \"$MISE_PLUGIN_PREV_REF $MISE_PLUGIN_POST_REF\" > \"$MISE_PLUGIN_PATH/update.txt\"";
+        let origin = test::plugin_origin(&[
+            ("list-all", "echo 1.0.0"),
+            ("post-plugin-checkout", hook),
+            ("post-plugin-update", update_hook),
+        ]);
+        let first = cmd!("git", "-C", origin.path(), "rev-parse", "HEAD")
+            .read()
+            .unwrap();
+        let mut plugin = Asdf::new(String::from("tracked-branch"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let pr = QuietReport::new();
+        plugin.install(&pr).unwrap();
+        file::write(origin.path().join("version"), "2").unwrap();
+        let second = test::git_commit(origin.path(), "second");
+
+        let update = plugin.update(&pr, None, false);
+        let checkout = file::read_to_string(plugin.plugin_path.join("checkout.txt"));
+        let updated = file::read_to_string(plugin.plugin_path.join("update.txt"));
+        plugin.uninstall(&pr).unwrap();
+
+        update.unwrap();
+        assert_eq!(checkout.unwrap().trim(), format!("{first} {second}"));
+        assert_eq!(updated.unwrap().trim(), format!("{first} {second}"));
+    }
+
+    #[test]
+    fn test_install_from_release_asset() {
+        reset();
+        let tmp = tempfile::tempdir().unwrap();
+        let list_all = tmp.path().join("asdf-asset-1.0.0/bin/list-all");
+        file::create_dir_all(list_all.parent().unwrap()).unwrap();
+        file::write(&list_all, "#!/usr/bin/env bash\necho 1.0.0 1.1.0\n").unwrap();
+        file::make_executable(&list_all).unwrap();
+        let archive = tmp.path().join("asdf-asset.tar.gz");
+        cmd!(
+            "tar",
+            "-czf",
+            &archive,
+            "-C",
+            tmp.path(),
+            "asdf-asset-1.0.0"
+        )
+        .run()
+        .unwrap();
+        let url = test::serve(fs::read(&archive).unwrap(), 2);
+        let url = format!("{url}/releases/latest/download/asdf-asset.tar.gz");
+
+        let mut plugin = Asdf::new(String::from("release-asset"));
+        plugin.repo_url = Some(url.clone());
+        let pr = QuietReport::new();
+        plugin.install(&pr).unwrap();
+        assert!(file::is_executable(
+            &plugin.plugin_path.join("bin/list-all")
+        ));
+        assert!(!plugin.plugin_path.join(".git").exists());
+        assert_eq!(plugin.get_remote_url(), Some(url));
+
+        file::remove_file(plugin.plugin_path.join("bin/list-all")).unwrap();
+        plugin.update(&pr, None, false).unwrap();
+        assert!(file::is_executable(
+            &plugin.plugin_path.join("bin/list-all")
+        ));
+        plugin.uninstall(&pr).unwrap();
+    }
+
+    #[test]
+    fn test_install_low_disk_space() {
+        reset();
+        let mib = 1024 * 1024;
+        let url = test::serve(vec![0; 16 * mib], 1);
+        let url = format!("{url}/releases/latest/download/asdf-asset.tar.gz");
+        let mut plugin = Asdf::new(String::from("low-disk-space"));
+        plugin.repo_url = Some(url);
+        // enough free space to start but not to download the 16MiB archive
+        let free = disk_space::free_space(&plugin.plugin_path).unwrap() / mib as u64;
+        env::set_var(
+            "MISE_MIN_FREE_DISK_SPACE",
+            free.saturating_sub(8).max(1).to_string(),
+        );
+        Settings::reset(None);
+
+        let err = plugin.install(&QuietReport::new()).unwrap_err();
+        env::remove_var("MISE_MIN_FREE_DISK_SPACE");
+        Settings::reset(None);
+
+        assert_eq!(Error::kind(&err), Some(ErrorKind::DiskSpace));
+        assert!(
+            err.to_string().starts_with("insufficient disk space on "),
+            "{err}"
+        );
+        assert!(!plugin.plugin_path.exists());
+        assert!(!plugin.fa.downloads_path.exists());
+    }
+
+    #[test]
+    fn test_install_cancel() {
+        reset();
+        let tmp = tempfile::tempdir().unwrap();
+        let list_all = tmp.path().join("asdf-cancel/bin/list-all");
+        file::create_dir_all(list_all.parent().unwrap()).unwrap();
+        file::write(&list_all, "#!/usr/bin/env bash\necho 1.0.0\n").unwrap();
+        let archive = tmp.path().join("asdf-cancel.tar.gz");
+        cmd!("tar", "-czf", &archive, "-C", tmp.path(), "asdf-cancel")
+            .run()
+            .unwrap();
+        let body = fs::read(&archive).unwrap();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let server_cancel = cancel.clone();
+        let (url, server) = serve_responses(vec![Response::ok(body).with_on_request(move || {
+            // cancelled while the download phase is in progress
+            server_cancel.store(true, Ordering::Relaxed);
+        })]);
+        let url = format!("{url}/asdf-cancel.tar.gz");
+
+        let mut plugin = Asdf::new(String::from("cancel"));
+        plugin.repo_url = Some(url);
+        plugin.cancel = Some(cancel);
+        let err = plugin.install(&QuietReport::new()).unwrap_err();
+        server.join().unwrap();
+
+        assert_eq!(Error::kind(&err), Some(ErrorKind::Cancelled));
+        assert_eq!(err.to_string(), "[plugin:cancel] install cancelled");
+        assert!(!plugin.plugin_path.exists());
+        assert!(!plugin.fa.downloads_path.exists());
+        let last_error = AsdfPlugin::new("cancel".into()).last_error().unwrap();
+        assert_eq!(last_error, None);
+    }
+
+    #[test]
+    fn test_is_release_asset() {
+        assert!(is_release_asset("https://example.com/plugin.tar.gz"));
+        assert!(is_release_asset("https://example.com/plugin.tgz"));
+        assert!(is_release_asset("https://example.com/plugin.zip"));
+        assert!(!is_release_asset(
+            "https://github.com/mise-plugins/mise-tiny.git"
+        ));
+        assert!(!is_release_asset(
+            "https://github.com/mise-plugins/mise-tiny"
+        ));
+    }
+
+    #[test]
+    fn test_git_host_shorthand_trust() {
+        assert_eq!(
+            normalize_remote("gitea:codeberg.org/someone/asdf-act").unwrap(),
+            "codeberg.org/someone/asdf-act"
+        );
+        assert_eq!(
+            normalize_remote("forgejo:codeberg.org/someone/asdf-act.git").unwrap(),
+            normalize_remote("https://codeberg.org/someone/asdf-act.git").unwrap()
+        );
+        // still flagged like any other url that isn't the official shorthand
+        assert!(is_shadowing_shorthand(
+            "act",
+            "gitea:codeberg.org/someone/asdf-act"
+        ));
+        assert!(!is_shadowing_shorthand(
+            "act",
+            "forgejo:github.com/gr1m0h/asdf-act"
+        ));
+        assert!(!is_trusted_plugin(
+            "act",
+            "gitea:github.com/gr1m0h/asdf-act"
+        ));
+    }
+
+    #[test]
+    fn test_is_shadowing_shorthand() {
+        assert!(is_shadowing_shorthand(
+            "act",
+            "https://github.com/someone-else/asdf-act.git"
+        ));
+        assert!(is_shadowing_shorthand(
+            "act",
+            "https://gitlab.com/gr1m0h/asdf-act"
+        ));
+        assert!(!is_shadowing_shorthand(
+            "act",
+            "https://github.com/gr1m0h/asdf-act.git"
+        ));
+        assert!(!is_shadowing_shorthand(
+            "act",
+            "https://github.com/gr1m0h/asdf-act"
+        ));
+        assert!(!is_shadowing_shorthand(
+            "not-a-shorthand",
+            "https://github.com/someone/asdf-not-a-shorthand.git"
+        ));
+    }
+
+    #[test]
+    fn test_trust_status() {
+        assert_eq!(
+            trust_status(
+                "cargo-make",
+                "https://github.com/mise-plugins/asdf-cargo-make"
+            ),
+            TrustStatus::Trusted("maintained by the mise team".into())
+        );
+        assert_eq!(
+            trust_status(
+                "consul",
+                "https://github.com/asdf-community/asdf-hashicorp.git"
+            ),
+            TrustStatus::Trusted("first-party plugin".into())
+        );
+        assert_eq!(
+            trust_status("act", "https://github.com/gr1m0h/asdf-act.git"),
+            TrustStatus::Community("community-developed plugin".into())
+        );
+        assert_eq!(
+            trust_status("act", "https://github.com/someone-else/asdf-act.git"),
+            TrustStatus::Shadowing(
+                "shadows the official shorthand https://github.com/gr1m0h/asdf-act".into()
+            )
+        );
+        assert_eq!(
+            trust_status("not-a-shorthand", "https://github.com/someone/asdf-foo"),
+            TrustStatus::Unknown("not a default shorthand".into())
+        );
+        assert_eq!(
+            trust_status("act", "not a url"),
+            TrustStatus::Unknown("invalid remote url not a url".into())
+        );
+        assert!(is_trusted_plugin(
+            "act",
+            "https://github.com/someone-else/asdf-act.git"
+        ));
+        assert!(!is_trusted_plugin(
+            "act",
+            "https://github.com/gr1m0h/asdf-act.git"
+        ));
+    }
+
+    #[test]
+    fn test_plugin_url_env() {
+        reset();
+        let config = Config::get();
+        let mut plugin = Asdf::new("act".into());
+        plugin.repo_url = Some("https://${MISE_TEST_GIT_HOST}/gr1m0h/asdf-act.git".into());
+        env::set_var("MISE_TEST_GIT_HOST", "github.com");
+        let url = plugin.get_repo_url(&config);
+        env::remove_var("MISE_TEST_GIT_HOST");
+        let undefined = plugin.get_repo_url(&config);
+
+        let url = url.unwrap();
+        assert_eq!(url, "https://github.com/gr1m0h/asdf-act.git");
+        // trust sees the same url as if it had been written out
+        assert_eq!(
+            trust_status("act", &url),
+            TrustStatus::Community("community-developed plugin".into())
+        );
+        assert_eq!(
+            format!("{}", undefined.unwrap_err()),
+            "plugin url https://${MISE_TEST_GIT_HOST}/gr1m0h/asdf-act.git uses undefined environment variables: MISE_TEST_GIT_HOST"
+        );
+    }
+
+    #[test]
+    fn test_plugin_url_transform() {
+        reset();
+        let config = Config::get();
+        let calls = tempfile::NamedTempFile::new().unwrap();
+        let transform = format!(
+            r#"echo >> {}; echo "$1" | sed s/github.com/mirror.example.com/"#,
+            calls.path().display()
+        );
+        env::set_var("MISE_PLUGIN_URL_TRANSFORM", transform);
+        Settings::reset(None);
+        let plugin = Asdf::new("act".into());
+
+        let url = plugin.get_repo_url(&config).unwrap();
+        let again = plugin.get_repo_url(&config).unwrap();
+        let trusted = plugin.is_trusted(&config, &url);
+        env::set_var("MISE_PARANOID", "1");
+        Settings::reset(None);
+        let paranoid_trusted = plugin.is_trusted(&config, &url);
+        env::remove_var("MISE_PARANOID");
+        env::remove_var("MISE_PLUGIN_URL_TRANSFORM");
+        Settings::reset(None);
+
+        assert_eq!(url, "https://mirror.example.com/gr1m0h/asdf-act.git");
+        // the transform only runs once so trust and the clone see the same url
+        assert_eq!(again, url);
+        assert_eq!(
+            file::read_to_string(calls.path()).unwrap().lines().count(),
+            1
+        );
+        assert_eq!(
+            trust_status("act", &url),
+            TrustStatus::Shadowing(
+                "shadows the official shorthand https://github.com/gr1m0h/asdf-act".into()
+            )
+        );
+        assert!(trusted);
+        assert!(!paranoid_trusted);
+        assert_eq!(
+            plugin.get_repo_url(&config).unwrap(),
+            "https://github.com/gr1m0h/asdf-act.git"
+        );
+    }
+
+    #[test]
+    fn test_plugin_trust_status() {
+        reset();
+        let mut plugin = AsdfPlugin::new("act".into());
+        assert_eq!(
+            plugin.trust_status(),
+            TrustStatus::Unknown("no remote url".into())
+        );
+        plugin.repo_url = Some("https://github.com/someone-else/asdf-act".into());
+        assert_eq!(plugin.trust_status().to_string(), "shadowing");
+        assert_eq!(
+            plugin.trust_status().reason(),
+            "shadows the official shorthand https://github.com/gr1m0h/asdf-act"
+        );
+    }
+
+    #[test]
+    fn test_compare_to_shorthand() {
+        reset();
+        let mut plugin = AsdfPlugin::new("act".into());
+        assert_eq!(plugin.compare_to_shorthand().unwrap(), None);
+        plugin.repo_url = Some("https://github.com/gr1m0h/asdf-act".into());
+        assert_eq!(
+            plugin.compare_to_shorthand().unwrap(),
+            Some(ShorthandComparison::Match)
+        );
+        plugin.repo_url = Some("https://github.com/someone-else/asdf-act.git".into());
+        assert_eq!(
+            plugin.compare_to_shorthand().unwrap().unwrap().to_string(),
+            "different-path: github.com/someone-else/asdf-act instead of github.com/gr1m0h/asdf-act"
+        );
+        assert_eq!(
+            compare_to_shorthand("act", "gitea:codeberg.org/gr1m0h/asdf-act").unwrap(),
+            Some(ShorthandComparison::DifferentHost {
+                remote: "codeberg.org/gr1m0h/asdf-act".into(),
+                shorthand: "github.com/gr1m0h/asdf-act".into(),
+            })
+        );
+        assert_eq!(
+            compare_to_shorthand("not-a-shorthand", "https://github.com/someone/asdf-foo").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_project_plugins() {
+        reset();
+        let project = tempfile::tempdir().unwrap();
+        let project_plugins = project.path().join(".mise/plugins");
+        file::create_dir_all(project_plugins.join("dummy/bin")).unwrap();
+        let cwd = env::current_dir().unwrap();
+        env::set_current_dir(project.path()).unwrap();
+
+        assert!(dirs::PLUGINS.join("dummy").exists());
+        assert_eq!(
+            Asdf::new("dummy".into()).plugin_path,
+            project_plugins.join("dummy")
+        );
+        assert_eq!(
+            dirs::plugin_path("new-plugin"),
+            project_plugins.join("new-plugin")
+        );
+        let names = Asdf::list()
+            .unwrap()
+            .iter()
+            .map(|p| p.id().to_string())
+            .collect_vec();
+        assert_eq!(names.iter().filter(|n| *n == "dummy").count(), 1);
+        let names = AsdfPlugin::list()
+            .unwrap()
+            .iter()
+            .map(|p| p.name().to_string())
+            .collect_vec();
+        assert_eq!(names.iter().filter(|n| *n == "dummy").count(), 1);
+
+        env::set_current_dir(cwd).unwrap();
+        assert_eq!(dirs::plugin_path("dummy"), dirs::PLUGINS.join("dummy"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_only_plugins_dir() {
+        use std::os::unix::fs::PermissionsExt;
+
+        reset();
+        let mpr = MultiProgressReport::get();
+        let pr = QuietReport::new();
+        let tiny = Asdf::new(String::from("tiny"));
+        let missing = Asdf::new(String::from("not-a-real-plugin"));
+        let perms = fs::metadata(*dirs::PLUGINS).unwrap().permissions();
+        fs::set_permissions(*dirs::PLUGINS, fs::Permissions::from_mode(0o555)).unwrap();
+        let install_err = missing.ensure_installed(&mpr, false).unwrap_err();
+        let uninstall_err = tiny.uninstall(&pr).unwrap_err();
+        fs::set_permissions(*dirs::PLUGINS, perms).unwrap();
+
+        assert_eq!(
+            install_err.to_string(),
+            "plugins directory ~/data/plugins is read-only"
+        );
+        assert_eq!(
+            uninstall_err.to_string(),
+            "plugins directory ~/data/plugins is read-only"
+        );
+        assert!(tiny.is_installed());
+        assert!(!missing.plugin_path.exists());
+    }
+
+    #[test]
+    fn test_trust_callback() {
+        reset();
+        let asked = Arc::new(Mutex::new(vec![]));
+        let asked_ = asked.clone();
+        set_trust_callback(Some(Box::new(move |name, url| {
+            asked_.lock().unwrap().push(format!("{name} {url}"));
+            name != "act"
+        })));
+        let mpr = MultiProgressReport::get();
+
+        // asked even though MISE_YES is set for tests
+        let denied = Asdf::new("act".into()).ensure_installed(&mpr, false);
+        // and for an explicit url, which isn't prompted for
+        let mut explicit = Asdf::new("act".into());
+        explicit.repo_url = Some("https://github.com/someone-else/asdf-act.git".into());
+        let explicit_denied = explicit.ensure_installed(&mpr, false);
+        set_trust_callback(None);
+
+        for denied in [denied, explicit_denied] {
+            assert_eq!(
+                Error::kind(&denied.unwrap_err()),
+                Some(ErrorKind::TrustRefused)
+            );
+        }
+        assert_eq!(
+            *asked.lock().unwrap(),
+            vec![
+                "act github.com/gr1m0h/asdf-act",
+                "act github.com/someone-else/asdf-act"
+            ]
+        );
+        assert!(!dirs::PLUGINS.join("act").exists());
+    }
+
+    #[test]
+    fn test_confirm_all_batch_install() {
+        reset();
+        // community plugins so they prompt, cloned from a local fixture through the clone cache
+        let origin = tempfile::tempdir().unwrap();
+        test::git_init(origin.path(), "init");
+        let config = Config::get();
+        let plugins = ["act", "shfmt", "helm", "yq"].map(|name| Asdf::new(name.to_string()));
+        let caches = plugins
+            .iter()
+            .map(|p| {
+                let cache = CloneCache::new(&p.get_repo_url(&config).unwrap(), None);
+                cache.populate(origin.path(), None).unwrap();
+                cache
+            })
+            .collect_vec();
+        env::set_var("MISE_PLUGIN_CLONE_CACHE", "1");
+        env::set_var("MISE_YES", "0");
+        Settings::reset(None);
+        let mpr = MultiProgressReport::get();
+        let prompted = |result: Result<InstallStatus>| {
+            result.is_err_and(|err| Error::kind(&err) == Some(ErrorKind::NotInstalled))
+        };
+
+        // not attended so the prompt is declined
+        let declined = prompted(plugins[0].ensure_installed(&mpr, false));
+        // answering "All" on the first prompt, the rest are not prompted and go on to
+        // clone the plugin
+        prompt::set_confirmed_all(true);
+        let skipped = plugins[..3]
+            .iter()
+            .map(|p| !prompted(p.ensure_installed(&mpr, false)))
+            .collect_vec();
+        env::set_var("MISE_PARANOID", "1");
+        Settings::reset(None);
+        let paranoid = plugins[3].ensure_installed(&mpr, false).unwrap_err();
+
+        prompt::set_confirmed_all(false);
+        env::remove_var("MISE_PARANOID");
+        env::remove_var("MISE_PLUGIN_CLONE_CACHE");
+        env::set_var("MISE_YES", "1");
+        Settings::reset(None);
+        let installed = plugins.iter().map(|p| p.is_installed()).collect_vec();
+        for plugin in &plugins {
+            file::remove_all(&plugin.plugin_path).unwrap();
+        }
+        for cache in &caches {
+            file::remove_all(&cache.path).unwrap();
+        }
+
+        assert!(declined);
+        assert_eq!(skipped, vec![true, true, true]);
+        assert_eq!(installed, vec![true, true, true, false]);
+        assert_eq!(Error::kind(&paranoid), Some(ErrorKind::TrustRefused));
+    }
+
+    #[test]
+    fn test_update_dirty_plugin() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        let mut plugin = Asdf::new(String::from("dirty"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let pr = QuietReport::new();
+        plugin.install(&pr).unwrap();
+        let edited = "#!/usr/bin/env bash\necho 2.0.0\n";
+        let list_all = plugin.plugin_path.join("bin/list-all");
+        file::write(&list_all, edited).unwrap();
+        file::write(plugin.plugin_path.join("notes.txt"), "").unwrap();
+
+        let status = AsdfPlugin::new(plugin.name.clone())
+            .git_status()
+            .unwrap()
+            .unwrap();
+        let warned = plugin.update(&pr, None, false);
+        let overwritten = file::read_to_string(&list_all).unwrap();
+        file::write(&list_all, edited).unwrap();
+        env::set_var("MISE_PLUGIN_UPDATE_REFUSE_DIRTY", "1");
+        Settings::reset(None);
+        let refused = plugin.update(&pr, None, false).unwrap_err();
+        env::remove_var("MISE_PLUGIN_UPDATE_REFUSE_DIRTY");
+        Settings::reset(None);
+        let kept = file::read_to_string(&list_all).unwrap();
+        plugin.uninstall(&pr).unwrap();
+
+        assert_eq!(status.modified, vec!["bin/list-all"]);
+        assert_eq!(status.untracked, vec!["notes.txt"]);
+        assert!(warned.is_ok());
+        assert_ne!(overwritten, edited);
+        assert_eq!(
+            refused.to_string(),
+            "plugin:dirty has local changes, not updating: bin/list-all, notes.txt"
+        );
+        assert_eq!(kept, edited);
+    }
+
+    #[test]
+    fn test_install_with_reference() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        let references = tempfile::tempdir().unwrap();
+        let mut plugin = Asdf::new(String::from("reference"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let mut other = Asdf::new(String::from("no-reference"));
+        other.repo_url = plugin.repo_url.clone();
+        cmd!(
+            "git",
+            "clone",
+            "-q",
+            origin.path(),
+            references.path().join("reference")
+        )
+        .run()
+        .unwrap();
+        let alternates = |p: &Asdf| p.plugin_path.join(".git/objects/info/alternates").exists();
+        let pr = QuietReport::new();
+        env::set_var("MISE_PLUGIN_REFERENCE_DIR", references.path());
+        Settings::reset(None);
+
+        plugin.install(&pr).unwrap();
+        let referenced = alternates(&plugin);
+        plugin.uninstall(&pr).unwrap();
+        other.install(&pr).unwrap();
+        let other_referenced = alternates(&other);
+        other.uninstall(&pr).unwrap();
+        env::set_var("MISE_PLUGIN_REFERENCE_DISSOCIATE", "1");
+        Settings::reset(None);
+        plugin.install(&pr).unwrap();
+        let dissociated = !alternates(&plugin) && plugin.plugin_path.join("bin/list-all").exists();
+        plugin.uninstall(&pr).unwrap();
+
+        env::remove_var("MISE_PLUGIN_REFERENCE_DIR");
+        env::remove_var("MISE_PLUGIN_REFERENCE_DISSOCIATE");
+        Settings::reset(None);
+        assert!(referenced);
+        assert!(!other_referenced);
+        assert!(dissociated);
+    }
+
+    #[test]
+    fn test_install_from_clone_cache() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        let url = origin.path().to_string_lossy().to_string();
+        let mut plugin = Asdf::new(String::from("clone-cache"));
+        plugin.repo_url = Some(url.clone());
+        let cache = CloneCache::new(&url, None);
+        let pr = QuietReport::new();
+        env::set_var("MISE_PLUGIN_CLONE_CACHE", "1");
+        Settings::reset(None);
+
+        // waits for another install copying from or populating the same entry
+        let lock = cache.lock().unwrap();
+        let release = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(300));
+            drop(lock);
+        });
+        let start = Instant::now();
+        plugin.install(&pr).unwrap();
+        let waited = start.elapsed();
+        release.join().unwrap();
+        let populated = cache.is_valid();
+        plugin.uninstall(&pr).unwrap();
+        // the origin is gone so this can only succeed by using the cache
+        drop(origin);
+        let cached_install = plugin.install(&pr);
+        let remote_url = plugin.get_remote_url();
+        let list_all_cached = file::is_executable(&plugin.plugin_path.join("bin/list-all"));
+        plugin.uninstall(&pr).unwrap();
+        // an entry recorded for another url+ref is not reused
+        file::write(cache.key_file(), "https://example.com/other.git").unwrap();
+        let invalid = cache.is_valid();
+        let uncached_install = plugin.install(&pr);
+
+        env::remove_var("MISE_PLUGIN_CLONE_CACHE");
+        Settings::reset(None);
+        file::remove_all(&plugin.plugin_path).unwrap();
+        file::remove_all(&cache.path).unwrap();
+
+        assert!(waited >= Duration::from_millis(300), "{waited:?}");
+        assert!(populated);
+        assert!(cached_install.is_ok(), "{cached_install:?}");
+        assert_eq!(remote_url, Some(url));
+        assert!(list_all_cached);
+        assert!(!invalid);
+        assert!(uncached_install.is_err());
+    }
+
+    #[test]
+    fn test_clone_cache_post_plugin_checkout() {
+        reset();
+        let origin = tempfile::tempdir().unwrap();
+        let hook = origin.path().join("bin/post-plugin-checkout");
+        file::create_dir_all(hook.parent().unwrap()).unwrap();
+        file::write(
+            &hook,
+            "#!/usr/bin/env bash\necho \"$MISE_PLUGIN_PREV_SHA $MISE_PLUGIN_POST_SHA\" > \"$MISE_PLUGIN_PATH/checkout.txt\"\n",
+        )
+        .unwrap();
+        file::make_executable(&hook).unwrap();
+        let first = test::git_init(origin.path(), "first");
+        cmd!("git", "-C", origin.path(), "tag", "v1").run().unwrap();
+        let second = test::git_commit(origin.path(), "second");
+        let url = origin.path().to_string_lossy().to_string();
+        let mut plugin = Asdf::new(String::from("clone-cache-checkout"));
+        plugin.repo_url = Some(format!("{url}#v1"));
+        let cache = CloneCache::new(&url, Some("v1"));
+        let checkout = plugin.plugin_path.join("checkout.txt");
+        let pr = QuietReport::new();
+        env::set_var("MISE_PLUGIN_CLONE_CACHE", "1");
+        Settings::reset(None);
+
+        plugin.install(&pr).unwrap();
+        let uncached = file::read_to_string(&checkout).unwrap();
+        plugin.uninstall(&pr).unwrap();
+        drop(origin);
+        let cached_install = plugin.install(&pr);
+        let cached = file::read_to_string(&checkout);
+
+        env::remove_var("MISE_PLUGIN_CLONE_CACHE");
+        Settings::reset(None);
+        file::remove_all(&plugin.plugin_path).unwrap();
+        file::remove_all(&cache.path).unwrap();
+
+        assert_eq!(uncached.trim(), format!("{second} {first}"));
+        assert!(cached_install.is_ok(), "{cached_install:?}");
+        assert_eq!(cached.unwrap(), uncached);
+    }
+
+    #[test]
+    fn test_silent_ensure_installed() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        let mut plugin = Asdf::new(String::from("silent"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let community = Asdf::new(String::from("shfmt"));
+        let mpr = MultiProgressReport::silent();
+        STDERR.lock().unwrap().clear();
+
+        let installed = plugin.ensure_installed(&mpr, false);
+        env::set_var("MISE_YES", "0");
+        Settings::reset(None);
+        let refused = community.ensure_installed(&mpr, false).unwrap_err();
+        env::set_var("MISE_YES", "1");
+        Settings::reset(None);
+        let stderr = STDERR.lock().unwrap().clone();
+        let list_all = file::is_executable(&plugin.plugin_path.join("bin/list-all"));
+        plugin.uninstall(&QuietReport::new()).unwrap();
+
+        installed.unwrap();
+        assert!(list_all);
+        assert_eq!(Error::kind(&refused), Some(ErrorKind::NotInstalled));
+        assert!(!community.plugin_path.exists());
+        assert_eq!(stderr, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_case_collision() {
+        reset();
+        // a plugin dir only differing by case from the plugin being installed, on a
+        // case-insensitive filesystem this would be the same directory
+        let existing = dirs::PLUGINS.join("Case-Collision");
+        file::create_dir_all(existing.join("bin")).unwrap();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        let mut plugin = Asdf::new(String::from("case-collision"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let mpr = MultiProgressReport::get();
+        STDERR.lock().unwrap().clear();
+
+        let installed = plugin.ensure_installed(&mpr, false);
+        let stderr = STDERR.lock().unwrap().clone();
+        env::set_var("MISE_PARANOID", "1");
+        Settings::reset(None);
+        let already_installed = plugin.ensure_installed(&mpr, false);
+        plugin.uninstall(&QuietReport::new()).unwrap();
+        let paranoid = plugin.ensure_installed(&mpr, false);
+        env::remove_var("MISE_PARANOID");
+        Settings::reset(None);
+        let collision = plugin.case_collision().unwrap();
+        file::remove_all(&existing).unwrap();
+
+        installed.unwrap();
+        assert!(
+            stderr.iter().any(|l| console::strip_ansi_codes(l).contains(
+                "case-collision differs only by case from the installed plugin Case-Collision"
+            )),
+            "{stderr:?}"
+        );
+        assert_eq!(already_installed.unwrap(), InstallStatus::AlreadyInstalled);
+        assert_eq!(
+            paranoid.unwrap_err().to_string(),
+            "plugin case-collision collides with the installed plugin Case-Collision"
+        );
+        assert!(!plugin.plugin_path.exists());
+        assert_eq!(collision, Some("Case-Collision".to_string()));
+        assert_eq!(plugin.case_collision().unwrap(), None);
+    }
+
+    #[test]
+    fn test_unreachable_remote() {
+        reset();
+        let origin = tempfile::tempdir().unwrap();
+        let mut plugin = Asdf::new(String::from("unreachable"));
+        plugin.repo_url = Some(origin.path().join("missing").to_string_lossy().to_string());
+        let mpr = MultiProgressReport::get();
+
+        let err = plugin.ensure_installed(&mpr, false).unwrap_err();
+
+        assert!(
+            err.to_string()
+                .starts_with("remote unreachable for plugin unreachable"),
+            "{err}"
+        );
+        assert!(!plugin.plugin_path.exists());
+    }
+
+    #[test]
+    fn test_resolve_pin_ref() {
+        let refs = [
+            "refs/heads/main",
+            "refs/tags/nightly",
+            "refs/tags/v1.0.0",
+            "refs/tags/v1.10.0",
+            "refs/tags/v2.0.0-rc.1",
+        ]
+        .map(String::from);
+        let resolve = |policy| resolve_pin_ref(policy, &refs);
+        assert_eq!(resolve(SettingsPluginPinPolicy::Branch), None);
+        assert_eq!(
+            resolve(SettingsPluginPinPolicy::LatestTag).as_deref(),
+            Some("v2.0.0-rc.1")
+        );
+        assert_eq!(
+            resolve(SettingsPluginPinPolicy::LatestSemverTag).as_deref(),
+            Some("v1.10.0")
+        );
+        let branches = ["refs/heads/main".to_string()];
+        assert_eq!(
+            resolve_pin_ref(SettingsPluginPinPolicy::LatestTag, &branches),
+            None
+        );
+    }
+
+    #[test]
+    fn test_plugin_pin_policy() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        for version in ["1.0.0", "1.10.0", "1.9.0", "2.0.0-rc.1", "main"] {
+            file::write(origin.path().join("version"), version).unwrap();
+            test::git_commit(origin.path(), version);
+            if version != "main" {
+                let tag = format!("v{version}");
+                cmd!("git", "-C", origin.path(), "tag", tag).run().unwrap();
+            }
+        }
+        cmd!("git", "-C", origin.path(), "branch", "zzz", "v1.0.0")
+            .run()
+            .unwrap();
+        let mut plugin = Asdf::new(String::from("pin-policy"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let pr = QuietReport::new();
+        let installed_version = |policy: &str| {
+            env::set_var("MISE_PLUGIN_PIN_POLICY", policy);
+            Settings::reset(None);
+            let result = plugin.install(&pr);
+            let version = file::read_to_string(plugin.plugin_path.join("version"));
+            plugin.uninstall(&pr).unwrap();
+            result.and(version).unwrap()
+        };
+
+        let branch = installed_version("branch");
+        let latest_tag = installed_version("latest-tag");
+        let latest_semver_tag = installed_version("latest-semver-tag");
+        env::remove_var("MISE_PLUGIN_PIN_POLICY");
+        Settings::reset(None);
+
+        assert_eq!(branch, "main");
+        assert_eq!(latest_tag, "2.0.0-rc.1");
+        assert_eq!(latest_semver_tag, "1.10.0");
+    }
+
+    #[test]
+    fn test_resolve_constraint_ref() {
+        let refs = [
+            "refs/heads/main",
+            "refs/tags/v1.1.0",
+            "refs/tags/v1.2.0",
+            "refs/tags/v1.2.5",
+            "refs/tags/1.10.0",
+            "refs/tags/v2.0.0-rc.1",
+            "refs/tags/v2.0.0",
+        ]
+        .map(String::from);
+        let resolve = |c| resolve_constraint_ref(c, &refs).unwrap();
+        assert_eq!(resolve("^1.2"), Some("1.10.0".into()));
+        assert_eq!(resolve("~1.2"), Some("v1.2.5".into()));
+        assert_eq!(resolve(">=1.1 <1.2"), Some("v1.1.0".into()));
+        assert_eq!(resolve("^3"), None);
+
+        // tags that only differ by the "v" prefix aren't dropped silently
+        let refs = ["refs/tags/v1.2.0", "refs/tags/1.2.0", "refs/tags/v1.1.0"].map(String::from);
+        STDERR.lock().unwrap().clear();
+        let tag = resolve_constraint_ref("^1", &refs).unwrap();
+        let stderr = STDERR.lock().unwrap().clone();
+        assert_eq!(tag, Some("1.2.0".into()));
+        assert_eq!(
+            stderr,
+            vec!["mise tags v1.2.0 and 1.2.0 are all version 1.2.0, using 1.2.0"]
+        );
+    }
+
+    #[test]
+    fn test_install_plan() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        for tag in ["v1.0.0", "v1.1.0"] {
+            cmd!("git", "-C", origin.path(), "tag", tag).run().unwrap();
+        }
+        let url = origin.path().to_string_lossy().to_string();
+        let mut plugin = Asdf::new(String::from("install-plan"));
+        plugin.repo_url = Some(url.clone());
+        plugin.ref_constraint = Some("^1".into());
+
+        let commands = plugin.install_plan().unwrap();
+        // the cli is used to clone instead of libgit2 and makes a shallow clone
+        env::set_var("MISE_GIT_CREDENTIAL_HELPER", "store");
+        Settings::reset(None);
+        let cli_commands = plugin.install_plan().unwrap();
+        env::remove_var("MISE_GIT_CREDENTIAL_HELPER");
+        Settings::reset(None);
+
+        let path = plugin.plugin_path.display().to_string();
+        let git =
+            |args: String| PluginCommand::new("git", args.split(' ').map(String::from).collect());
+        let git_c = format!("-C {path} -c safe.directory={path}");
+        let helper = "-c credential.helper= -c credential.helper=store";
+        let checkout = git(format!(
+            "{git_c} -c advice.detachedHead=false -c advice.objectNameWarning=false checkout --force v1.1.0"
+        ));
+        let mut hooks = vec![
+            PluginCommand::new(format!("{path}/bin/post-plugin-checkout"), vec![]),
+            PluginCommand::new(format!("{path}/bin/post-plugin-add"), vec![]),
+        ];
+        if Git::lfs_available() {
+            hooks.insert(1, git(format!("{git_c} lfs pull")));
+        }
+        let mut expected = vec![
+            git(format!("clone -q {url} {path}")),
+            git(format!(
+                "{git_c} fetch --prune --update-head-ok origin v1.1.0:v1.1.0"
+            )),
+            checkout.clone(),
+        ];
+        expected.extend(hooks.clone());
+        assert_eq!(commands, expected);
+        let mut expected = vec![
+            git(format!("{helper} clone -q --depth 1 {url} {path}")),
+            git(format!(
+                "{git_c} {helper} fetch --depth 1 --no-tags origin +refs/tags/v1.1.0:refs/tags/v1.1.0"
+            )),
+            checkout,
+        ];
+        expected.extend(hooks);
+        assert_eq!(cli_commands, expected);
+        assert_eq!(
+            commands[0].to_string(),
+            format!("git clone -q {url} {path}")
+        );
+        assert!(!plugin.plugin_path.exists());
+    }
+
+    #[test]
+    fn test_install_ref_constraint() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        for version in ["1.1.0", "1.2.0", "1.2.5", "2.0.0"] {
+            file::write(origin.path().join("version"), version).unwrap();
+            test::git_commit(origin.path(), version);
+            let tag = format!("v{version}");
+            cmd!("git", "-C", origin.path(), "tag", tag).run().unwrap();
+        }
+        let mut plugin = Asdf::new(String::from("ref-constraint"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let pr = QuietReport::new();
+
+        plugin.ref_constraint = Some("^1.1".into());
+        plugin.install(&pr).unwrap();
+        let version = file::read_to_string(plugin.plugin_path.join("version")).unwrap();
+        plugin.uninstall(&pr).unwrap();
+        assert_eq!(version, "1.2.5");
+
+        plugin.ref_constraint = Some("^3".into());
+        let err = plugin.install(&pr).unwrap_err();
+        assert!(format!("{err}").starts_with("no tags of "), "{err}");
+    }
+
+    #[test]
+    fn test_post_plugin_remove_hook() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        let marker = origin.path().join("post-plugin-remove-ran");
+        let hook = origin.path().join("bin/post-plugin-remove");
+        let hook_script = format!(
+            "#!/usr/bin/env bash\necho \"$MISE_PLUGIN_NAME\" > {}\n",
+            marker.display()
+        );
+        file::write(&hook, hook_script).unwrap();
+        file::make_executable(&hook).unwrap();
+        file::create_dir_all(origin.path().join("shims")).unwrap();
+        file::write(origin.path().join("shims/post-remove-bin"), "").unwrap();
+        test::git_commit(origin.path(), "hooks");
+        let mut plugin = Asdf::new(String::from("post-remove"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let pr = QuietReport::new();
+        plugin.install(&pr).unwrap();
+        let shim = dirs::SHIMS.join("post-remove-bin");
+        let other_shim = dirs::SHIMS.join("post-remove-other");
+        file::create_dir_all(*dirs::SHIMS).unwrap();
+        let plugin_bin = plugin.plugin_path.join("shims/post-remove-bin");
+        shims::make_shim(&plugin_bin, &shim).unwrap();
+        shims::make_shim(Path::new("/bin/true"), &other_shim).unwrap();
+
+        plugin.uninstall(&pr).unwrap();
+        let hook_output = file::read_to_string(&marker);
+        let shim_exists = shim.exists();
+        let other_shim_exists = other_shim.exists();
+        file::remove_file(&other_shim).unwrap();
+
+        assert!(!plugin.plugin_path.exists());
+        assert_eq!(hook_output.unwrap(), "post-remove\n");
+        assert!(!shim_exists);
+        assert!(other_shim_exists);
+    }
+
+    #[test]
+    fn test_update_scripts_changed() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        let mut plugin = Asdf::new(String::from("scripts-changed"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let pr = QuietReport::new();
+        plugin.install(&pr).unwrap();
+        file::write(origin.path().join("README.md"), "docs only").unwrap();
+        test::git_commit(origin.path(), "docs");
+        STDERR.lock().unwrap().clear();
+        plugin.update(&pr, None, false).unwrap();
+        let unchanged = STDERR.lock().unwrap().join("\n");
+        file::write(
+            origin.path().join("bin/list-all"),
+            "#!/usr/bin/env bash\necho 2.0.0\n",
+        )
+        .unwrap();
+        test::git_commit(origin.path(), "list-all");
+        STDERR.lock().unwrap().clear();
+        plugin.update(&pr, None, false).unwrap();
+        let changed = STDERR.lock().unwrap().join("\n");
+        plugin.uninstall(&pr).unwrap();
+
+        assert!(!unchanged.contains("scripts changed"), "{unchanged}");
+        assert!(
+            changed.contains("scripts changed in this update"),
+            "{changed}"
+        );
+    }
+
+    #[test]
+    fn test_auto_gc() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        let mut plugin = Asdf::new(String::from("auto-gc"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let pr = QuietReport::new();
+        plugin.install(&pr).unwrap();
+        let git = Git::new(plugin.plugin_path.clone());
+        let asdf_plugin = AsdfPlugin::new("auto-gc".into());
+        let update = |n: u32| {
+            file::write(origin.path().join("version"), n.to_string()).unwrap();
+            test::git_commit(origin.path(), &n.to_string());
+            plugin.update(&pr, None, false).unwrap();
+            git.loose_objects().unwrap()
+        };
+        env::set_var("MISE_PLUGIN_AUTO_GC", "1");
+        Settings::reset(None);
+
+        let below_threshold = update(1);
+        for _ in 1..AUTO_GC_UPDATES - 1 {
+            asdf_plugin.record_update_since_gc().unwrap();
+        }
+        let at_threshold = update(2);
+        let updates_after_gc = asdf_plugin.record_update_since_gc().unwrap();
+        env::remove_var("MISE_PLUGIN_AUTO_GC");
+        Settings::reset(None);
+        plugin.uninstall(&pr).unwrap();
+        let updates_after_uninstall = asdf_plugin.record_update_since_gc().unwrap();
+        asdf_plugin.forget_updates_since_gc().unwrap();
+
+        assert!(below_threshold > 0);
+        assert_eq!(at_threshold, 0);
+        assert_eq!(updates_after_gc, 1);
+        assert_eq!(updates_after_uninstall, 1);
+    }
+
+    #[test]
+    fn test_update_downgrade() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        let older = cmd!("git", "-C", origin.path(), "rev-parse", "HEAD")
+            .read()
+            .unwrap();
+        file::write(origin.path().join("version"), "2").unwrap();
+        test::git_commit(origin.path(), "newer");
+        let mut plugin = Asdf::new(String::from("downgrade"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        plugin.install(&QuietReport::new()).unwrap();
+        let git = Git::new(plugin.plugin_path.clone());
+        let newer = git.current_sha().unwrap();
+        let log = tempfile::NamedTempFile::new().unwrap();
+        let pr = LogFileReport::new(
+            Box::new(QuietReport::new()),
+            "downgrade",
+            Arc::new(Mutex::new(log.reopen().unwrap())),
+        );
+
+        env::set_var("MISE_YES", "0");
+        Settings::reset(None);
+        let refused = plugin.update(&pr, Some(older.clone()), false);
+        env::set_var("MISE_YES", "1");
+        Settings::reset(None);
+        let refused_sha = git.current_sha().unwrap();
+        let allowed = plugin.update(&pr, Some(older.clone()), true);
+        let allowed_sha = git.current_sha().unwrap();
+        let finished = file::read_to_string(log.path()).unwrap();
+        plugin.uninstall(&QuietReport::new()).unwrap();
+
+        assert_eq!(
+            format!("{:#}", refused.unwrap_err()),
+            format!("plugin:downgrade not downgrading to {older}")
+        );
+        assert_eq!(refused_sha, newer);
+        allowed.unwrap();
+        assert_eq!(allowed_sha, older);
+        assert!(finished.contains("downgraded to "), "{finished}");
+    }
+
+    #[test]
+    fn test_empty_remote_versions() {
+        reset();
+        let list_all = dirs::PLUGINS.join("empty-versions/bin/list-all");
+        file::create_dir_all(list_all.parent().unwrap()).unwrap();
+        file::write(&list_all, "#!/usr/bin/env bash\n").unwrap();
+        file::make_executable(&list_all).unwrap();
+        let plugin = Asdf::new("empty-versions".into());
+
+        let err = plugin.list_remote_versions().unwrap_err();
+        let cached = file::ls(&plugin.fa().cache_path).unwrap_or_default();
+        file::remove_all(dirs::PLUGINS.join("empty-versions")).unwrap();
+        file::remove_all(&plugin.fa().cache_path).unwrap();
+
+        assert_eq!(
+            format!("{err:#}"),
+            "Failed listing remote versions for plugin empty-versions: plugin empty-versions returned no versions, it may be misconfigured or have no releases upstream"
+        );
+        assert!(matches!(
+            err.downcast_ref::<crate::errors::Error>(),
+            Some(NoRemoteVersions(_))
+        ));
+        assert_eq!(cached, Vec::<PathBuf>::new());
+    }
+
+    #[test]
+    fn test_list_remote_versions_after() {
+        reset();
+        let calls = tempfile::NamedTempFile::new().unwrap();
+        let plugin_path = dirs::PLUGINS.join("versions-after");
+        for (script, versions) in [
+            ("list-all", "1.0.0 v1.1.0"),
+            ("list-all-after", "1.1.0 1.2.0"),
+        ] {
+            let path = plugin_path.join("bin").join(script);
+            file::create_dir_all(path.parent().unwrap()).unwrap();
+            let calls = calls.path().display();
+            file::write(
+                &path,
+                format!("#!/usr/bin/env bash\necho {script} \"$@\" >> {calls}\necho {versions}\n"),
+            )
+            .unwrap();
+            file::make_executable(&path).unwrap();
+        }
+        let age = |days: u64| {
+            FileTime::from_system_time(SystemTime::now() - Duration::from_secs(days * 86400))
+        };
+        let versions = || {
+            Asdf::new("versions-after".into())
+                .list_remote_versions()
+                .unwrap()
+        };
+        let cache_path = Asdf::new("versions-after".into()).fa().cache_path.clone();
+        file::remove_all(&cache_path).unwrap();
+
+        let first = versions();
+        for path in [&plugin_path, &plugin_path.join("bin/list-all")] {
+            filetime::set_file_mtime(path, age(10)).unwrap();
+        }
+        for path in file::ls(&cache_path).unwrap() {
+            filetime::set_file_mtime(path, age(2)).unwrap();
+        }
+        let second = versions();
+        let calls = file::read_to_string(calls.path()).unwrap();
+        file::remove_all(&plugin_path).unwrap();
+        file::remove_all(&cache_path).unwrap();
+
+        assert_eq!(first, vec!["1.0.0", "1.1.0"]);
+        assert_eq!(second, vec!["1.0.0", "1.1.0", "1.2.0"]);
+        assert_eq!(calls, "list-all\nlist-all-after 1.1.0\n");
+    }
+
+    #[test]
+    fn test_update_changelog() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        let mut plugin = Asdf::new(String::from("changelog"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        plugin.install(&QuietReport::new()).unwrap();
+        let git = Git::new(plugin.plugin_path.clone());
+        let pre = git.current_sha().unwrap();
+        for subject in ["fix list-all", "add latest-stable", "update docs"] {
+            file::write(origin.path().join("README.md"), subject).unwrap();
+            test::git_commit(origin.path(), subject);
+        }
+        let log = tempfile::NamedTempFile::new().unwrap();
+        let pr = LogFileReport::new(
+            Box::new(QuietReport::new()),
+            "changelog",
+            Arc::new(Mutex::new(log.reopen().unwrap())),
+        );
+        plugin.update(&pr, None, false).unwrap();
+        let post = git.current_sha().unwrap();
+        plugin.update(&pr, None, false).unwrap();
+        let capped = git.log_subjects(&pre, &post, 2);
+        let finished = file::read_to_string(log.path()).unwrap();
+        plugin.uninstall(&QuietReport::new()).unwrap();
+
+        // each line is prefixed with "changelog " by the report
+        let finished = finished.lines().collect::<Vec<_>>();
+        assert_eq!(
+            finished[2..5],
+            [
+                "changelog   update docs",
+                "changelog   add latest-stable",
+                "changelog   fix list-all"
+            ],
+            "{finished:?}"
+        );
+        // the second update was a no-op so it has no changelog
+        assert_eq!(finished.len(), 7, "{finished:?}");
+        let (subjects, total) = capped.unwrap();
+        assert_eq!(subjects, vec!["update docs", "add latest-stable"]);
+        assert_eq!(total, 3);
+        assert_eq!(update_changelog(&git, &post, &post), "");
+    }
+
+    #[test]
+    fn test_install_tag_filter() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        for tag in ["v1.0.0", "v1.1.0", "v2.0.0", "v2.1.0"] {
+            file::write(origin.path().join("version"), tag).unwrap();
+            test::git_commit(origin.path(), tag);
+            cmd!("git", "-C", origin.path(), "tag", tag).run().unwrap();
+        }
+        let mut plugin = Asdf::new(String::from("tag-filter"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        env::set_var("MISE_PLUGIN_TAG_FILTERS", "tag-filter=v*.0.0");
+        env::set_var("MISE_PLUGIN_PIN_POLICY", "latest-tag");
+        Settings::reset(None);
+        let installed = plugin.install(&QuietReport::new());
+        env::remove_var("MISE_PLUGIN_TAG_FILTERS");
+        env::remove_var("MISE_PLUGIN_PIN_POLICY");
+        Settings::reset(None);
+        let tags = cmd!("git", "-C", &plugin.plugin_path, "tag", "--list")
+            .read()
+            .unwrap_or_default();
+        let version = file::read_to_string(plugin.plugin_path.join("version")).unwrap_or_default();
+        plugin.uninstall(&QuietReport::new()).unwrap();
+
+        installed.unwrap();
+        assert_eq!(tags, "v1.0.0\nv2.0.0");
+        assert_eq!(version, "v2.0.0");
+    }
+
+    #[test]
+    fn test_refresh_plugin() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        let mut plugin = Asdf::new(String::from("refresh"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let pr = QuietReport::new();
+        plugin.install(&pr).unwrap();
+        let installed_version = dirs::INSTALLS.join("refresh/1.0.0/bin/tool");
+        file::create_dir_all(installed_version.parent().unwrap()).unwrap();
+        file::write(&installed_version, "installed").unwrap();
+        let list_all = plugin.plugin_path.join("bin/list-all");
+        file::write(&list_all, "broken").unwrap();
+
+        // the url is taken from the installed plugin
+        let refreshed = Asdf::new(String::from("refresh")).refresh_plugin(&pr);
+        let script = file::read_to_string(&list_all);
+        let version = file::read_to_string(&installed_version);
+        plugin.uninstall(&pr).unwrap();
+        file::remove_all(dirs::INSTALLS.join("refresh")).unwrap();
+
+        refreshed.unwrap();
+        assert_eq!(script.unwrap(), "#!/usr/bin/env bash\necho 1.0.0\n");
+        assert_eq!(version.unwrap(), "installed");
+    }
+
+    #[test]
+    fn test_install_last_error() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        let missing = origin.path().join("missing");
+        let mut plugin = Asdf::new(String::from("last-error"));
+        plugin.repo_url = Some(missing.to_string_lossy().to_string());
+        let pr = QuietReport::new();
+        let failed = plugin.install(&pr);
+        let recorded = AsdfPlugin::new("last-error".into()).last_error().unwrap();
+        file::remove_all(&plugin.plugin_path).unwrap();
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let installed = plugin.install(&pr);
+        let cleared = AsdfPlugin::new("last-error".into()).last_error().unwrap();
+        plugin.uninstall(&pr).unwrap();
+
+        let err = failed.unwrap_err();
+        let recorded = recorded.unwrap();
+        assert_eq!(recorded.phase, "clone");
+        assert_eq!(recorded.message, format!("{err:#}"));
+        assert!(chrono::DateTime::parse_from_rfc3339(&recorded.timestamp).is_ok());
+        installed.unwrap();
+        assert_eq!(cleared, None);
+    }
+
+    #[test]
+    fn test_update_fetch_refspec() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        let mut plugin = Asdf::new(String::from("fetch-refspec"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let pr = QuietReport::new();
+        plugin.install(&pr).unwrap();
+        cmd!("git", "-C", origin.path(), "checkout", "-qb", "feature-x")
+            .run()
+            .unwrap();
+        file::write(origin.path().join("version"), "feature-x").unwrap();
+        test::git_commit(origin.path(), "feature-x");
+        let update_with = |refspec: &str| {
+            let refspecs = format!("fetch-refspec={refspec}");
+            env::set_var("MISE_PLUGIN_FETCH_REFSPECS", refspecs);
+            Settings::reset(None);
+            let result = plugin.update(&pr, None, false);
+            env::remove_var("MISE_PLUGIN_FETCH_REFSPECS");
+            Settings::reset(None);
+            result
+        };
+
+        let invalid = update_with("refs/heads/feature-x:bad..ref");
+        let fetched = update_with("+refs/heads/feature-x:refs/remotes/origin/feature-x");
+        let version = file::read_to_string(plugin.plugin_path.join("version"));
+        plugin.uninstall(&pr).unwrap();
+
+        assert_eq!(
+            format!("{:#}", invalid.unwrap_err()),
+            "plugin_fetch_refspecs.fetch-refspec: invalid fetch refspec: refs/heads/feature-x:bad..ref"
+        );
+        fetched.unwrap();
+        assert_eq!(version.unwrap(), "feature-x");
+    }
+
+    #[test]
+    fn test_install_git_lfs() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        let pointer = "version https://git-lfs.github.com/spec/v1\noid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\nsize 12345\n";
+        file::write(
+            origin.path().join(".gitattributes"),
+            "*.bin filter=lfs diff=lfs merge=lfs -text\n",
+        )
+        .unwrap();
+        file::write(origin.path().join("asset.bin"), pointer).unwrap();
+        test::git_commit(origin.path(), "lfs");
+        let mut plugin = Asdf::new(String::from("git-lfs"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let git = Git::new(plugin.plugin_path.clone());
+        let pr = QuietReport::new();
+        let should_pull = |lfs_available| {
+            STDERR.lock().unwrap().clear();
+            let pull = plugin.should_pull_lfs(&git, lfs_available);
+            let warned = STDERR
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|l| l.contains("plugin:git-lfs requires git-lfs"));
+            (pull, warned)
+        };
+
+        env::set_var("MISE_PLUGIN_GIT_LFS", "0");
+        Settings::reset(None);
+        let install = plugin.install(&pr);
+        let asset = file::read_to_string(plugin.plugin_path.join("asset.bin"));
+        let disabled = should_pull(true);
+        env::remove_var("MISE_PLUGIN_GIT_LFS");
+        Settings::reset(None);
+        let available = should_pull(true);
+        let unavailable = should_pull(false);
+        file::remove_file(plugin.plugin_path.join(".gitattributes")).unwrap();
+        let no_lfs = should_pull(true);
+        plugin.uninstall(&pr).unwrap();
+
+        install.unwrap();
+        assert_eq!(asset.unwrap(), pointer);
+        assert_eq!(disabled, (false, false));
+        assert_eq!(available, (true, false));
+        // without git-lfs the install only warns and keeps the pointer files
+        assert_eq!(unavailable, (false, true));
+        assert_eq!(no_lfs, (false, false));
+    }
+
+    #[test]
+    fn test_plugin_worktrees() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        let tag = |tag: &str| {
+            cmd!("git", "-C", origin.path(), "tag", tag).run().unwrap();
+            cmd!("git", "-C", origin.path(), "rev-parse", "--short", tag)
+                .read()
+                .unwrap()
+        };
+        let v1 = tag("v1");
+        let list_all = origin.path().join("bin/list-all");
+        file::write(&list_all, "#!/usr/bin/env bash\necho 2.0.0\n").unwrap();
+        test::git_commit(origin.path(), "v2");
+        let v2 = tag("v2");
+        let mut plugin = Asdf::new(String::from("worktree"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let pr = QuietReport::new();
+        plugin.install(&pr).unwrap();
+
+        assert_cli!("plugins", "install", "worktree@v1", "--worktree");
+        let v2_worktree = AsdfPlugin::new("worktree".into()).add_worktree("v2");
+        let duplicate = AsdfPlugin::new("worktree".into()).add_worktree("v1");
+        let worktrees = AsdfPlugin::new("worktree".into()).worktrees();
+        let sha =
+            |gitref: &str| AsdfPlugin::worktree("worktree".into(), gitref).current_sha_short();
+        let (sha1, sha2) = (sha("v1"), sha("v2"));
+        let versions =
+            |gitref: &str| Asdf::worktree("worktree".into(), gitref).list_remote_versions();
+        let (versions1, versions2) = (versions("v1"), versions("v2"));
+        plugin.uninstall(&pr).unwrap();
+        let worktrees_removed = !worktrees_dir("worktree").exists();
+
+        assert_eq!(
+            v2_worktree.unwrap().repo.dir,
+            worktree_path("worktree", "v2")
+        );
+        assert_eq!(
+            duplicate.unwrap_err().to_string(),
+            "a worktree of worktree at v1 already exists"
+        );
+        assert_eq!(worktrees.unwrap().len(), 2);
+        assert_eq!(sha1.unwrap(), Some(v1));
+        assert_eq!(sha2.unwrap(), Some(v2));
+        assert_eq!(versions1.unwrap(), vec!["1.0.0"]);
+        assert_eq!(versions2.unwrap(), vec!["2.0.0"]);
+        assert!(worktrees_removed);
+    }
+
+    #[test]
+    fn test_upgrade_shorthand() {
+        reset();
+        let (old, new) = (
+            test::plugin_origin(&[("list-all", "echo 1.0.0")]),
+            test::plugin_origin(&[("list-all", "echo 1.0.0")]),
+        );
+        let url = |origin: &tempfile::TempDir| origin.path().to_string_lossy().to_string();
+        let shorthands = tempfile::NamedTempFile::new().unwrap();
+        let set_shorthand = |url: String| {
+            file::write(shorthands.path(), format!("upgrade-shorthand = \"{url}\"")).unwrap();
+        };
+        set_shorthand(url(&old));
+        env::set_var("MISE_SHORTHANDS_FILE", shorthands.path());
+        Settings::reset(None);
+        Config::reset();
+        let plugin = Asdf::new(String::from("upgrade-shorthand"));
+        let pr = QuietReport::new();
+        plugin.install(&pr).unwrap();
+        let asdf_plugin = AsdfPlugin::new("upgrade-shorthand".into());
+        let unchanged = asdf_plugin.upgrade_shorthand();
+        set_shorthand(url(&new));
+        let upgraded = asdf_plugin.upgrade_shorthand();
+        let remote = asdf_plugin.get_remote_url();
+        let again = asdf_plugin.upgrade_shorthand();
+        // a remote changed by hand is kept even if the shorthand moves again
+        asdf_plugin.repo.set_remote_url("/tmp/elsewhere").unwrap();
+        set_shorthand(url(&old));
+        let edited = asdf_plugin.upgrade_shorthand();
+        plugin.uninstall(&pr).unwrap();
+        env::remove_var("MISE_SHORTHANDS_FILE");
+        Settings::reset(None);
+        Config::reset();
+
+        assert_eq!(unchanged.unwrap(), None);
+        assert_eq!(upgraded.unwrap(), Some(url(&new)));
+        assert_eq!(remote.unwrap(), Some(url(&new)));
+        assert_eq!(again.unwrap(), None);
+        assert_eq!(edited.unwrap(), None);
+    }
+
+    #[test]
+    fn test_required_settings() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        file::write(
+            origin.path().join("mise.plugin.toml"),
+            "[hints]\nrequired-settings = [\"experimental\", \"paranoid\"]\n",
+        )
+        .unwrap();
+        test::git_commit(origin.path(), "manifest");
+        let mut plugin = Asdf::new(String::from("required-settings"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let mpr = MultiProgressReport::get();
+        STDERR.lock().unwrap().clear();
+
+        plugin.ensure_installed(&mpr, false).unwrap();
+        let stderr = STDERR.lock().unwrap().clone();
+        let required = AsdfPlugin::new("required-settings".into()).required_settings();
+        let paranoid = "required-settings requires the paranoid setting";
+        let experimental = "required-settings requires the experimental setting";
+        plugin.uninstall(&QuietReport::new()).unwrap();
+        let stderr = stderr
+            .iter()
+            .map(|l| console::strip_ansi_codes(l).to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(required.unwrap(), vec!["experimental", "paranoid"]);
+        assert!(stderr.iter().any(|l| l.contains(paranoid)), "{stderr:?}");
+        assert!(!stderr.iter().any(|l| l.contains(experimental)));
+        assert!(!Settings::get().paranoid);
+    }
+
+    #[test]
+    fn test_ensure_installed_status() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        let mut plugin = Asdf::new(String::from("install-status"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let mpr = MultiProgressReport::silent();
+
+        let installed = plugin.ensure_installed(&mpr, false);
+        let already = plugin.ensure_installed(&mpr, false);
+        let forced = plugin.ensure_installed(&mpr, true);
+        plugin.uninstall(&QuietReport::new()).unwrap();
+
+        assert_eq!(installed.unwrap(), InstallStatus::Installed);
+        assert_eq!(already.unwrap(), InstallStatus::AlreadyInstalled);
+        assert_eq!(forced.unwrap(), InstallStatus::Installed);
+        assert_eq!(
+            InstallStatus::AlreadyInstalled.to_string(),
+            "already installed"
+        );
+    }
+
+    #[test]
+    fn test_build_requirements() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        file::write(
+            origin.path().join("mise.plugin.toml"),
+            "[build-requirements]\ngit = \"1\"\nmise-missing-cc = \"*\"\nsh = \"999\"\n",
+        )
+        .unwrap();
+        test::git_commit(origin.path(), "manifest");
+        let mut plugin = Asdf::new(String::from("build-requirements"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        let mpr = MultiProgressReport::silent();
+
+        let err = plugin.ensure_installed(&mpr, false).unwrap_err();
+        let removed = !plugin.plugin_path.exists();
+        env::set_var("MISE_PLUGIN_BUILD_REQUIREMENTS", "0");
+        Settings::reset(None);
+        let skipped = plugin.ensure_installed(&mpr, false);
+        env::remove_var("MISE_PLUGIN_BUILD_REQUIREMENTS");
+        Settings::reset(None);
+        plugin.uninstall(&QuietReport::new()).unwrap();
+
+        let err = format!("{err:#}");
+        assert!(err.contains("plugin build-requirements has unmet build requirements"));
+        assert!(err.contains("mise-missing-cc is not on PATH"), "{err}");
+        assert!(!err.contains("git"), "{err}");
+        assert!(removed);
+        assert_eq!(skipped.unwrap(), InstallStatus::Installed);
+        assert_eq!(unmet_build_requirement("sh", "*"), None);
+    }
+
+    #[test]
+    fn test_supported_platform() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        file::write(
+            origin.path().join("mise.plugin.toml"),
+            "[platforms]\nos = [\"linux\"]\n",
+        )
+        .unwrap();
+        test::git_commit(origin.path(), "manifest");
+        let mut plugin = Asdf::new(String::from("linux-only"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        plugin
+            .ensure_installed(&MultiProgressReport::silent(), false)
+            .unwrap();
+
+        let warned = plugin.ensure_supported_platform("macos", "arm64");
+        let linux = plugin.ensure_supported_platform("linux", "x64");
+        env::set_var("MISE_PLUGIN_PLATFORM_CHECK", "error");
+        Settings::reset(None);
+        let err = plugin
+            .ensure_supported_platform("macos", "arm64")
+            .unwrap_err();
+        let removed = !plugin.plugin_path.exists();
+        env::remove_var("MISE_PLUGIN_PLATFORM_CHECK");
+        Settings::reset(None);
+
+        assert!(warned.is_ok());
+        assert!(linux.is_ok());
+        assert_eq!(
+            err.to_string(),
+            "plugin linux-only does not support macos-arm64"
+        );
+        assert!(removed);
+    }
+
+    #[test]
+    fn test_install_dev_ref() {
+        reset();
+        let origin = test::plugin_origin(&[("list-all", "echo 1.0.0")]);
+        let list_all = origin.path().join("bin/list-all");
+        let dir = origin.path().to_string_lossy().to_string();
+        let git = |args: &[&str]| {
+            duct::cmd("git", [&["-C", dir.as_str()], args].concat())
+                .stdout_null()
+                .stderr_null()
+                .run()
+                .unwrap();
+        };
+        git(&["checkout", "-qb", "wip"]);
+        file::write(&list_all, "#!/usr/bin/env bash\necho 2.0.0\n").unwrap();
+        test::git_commit(origin.path(), "wip");
+        git(&["update-ref", "refs/pull/7/head", "wip"]);
+        git(&["checkout", "-q", "-"]);
+        let mut plugin = Asdf::new(String::from("dev-ref"));
+        plugin.repo_url = Some(origin.path().to_string_lossy().to_string());
+        plugin.dev_ref = Some(DevRef::Branch("wip".into()));
+        let mpr = MultiProgressReport::silent();
+        let pr = QuietReport::new();
+
+        plugin.ensure_installed(&mpr, false).unwrap();
+        let branch_versions = plugin.list_remote_versions();
+        let trust = AsdfPlugin::new("dev-ref".into()).trust_status();
+        git(&["checkout", "-q", "wip"]);
+        file::write(&list_all, "#!/usr/bin/env bash\necho 2.1.0\n").unwrap();
+        test::git_commit(origin.path(), "wip 2");
+        git(&["checkout", "-q", "-"]);
+        plugin.update(&pr, None, false).unwrap();
+        let updated_versions = Asdf::new("dev-ref".into()).list_remote_versions();
+        plugin.dev_ref = Some(DevRef::Pr(7));
+        plugin.ensure_installed(&mpr, true).unwrap();
+        let pr_versions = Asdf::new("dev-ref".into()).list_remote_versions();
+        plugin.uninstall(&pr).unwrap();
+        let forgotten = AsdfPlugin::new("dev-ref".into()).dev_ref();
+
+        assert_eq!(branch_versions.unwrap(), vec!["2.0.0"]);
+        assert_eq!(
+            trust,
+            TrustStatus::Development(
+                "installed from +refs/heads/wip:refs/remotes/origin/wip".into()
+            )
+        );
+        assert_eq!(updated_versions.unwrap(), vec!["2.1.0"]);
+        assert_eq!(pr_versions.unwrap(), vec!["2.0.0"]);
+        assert_eq!(forgotten, None);
+        assert_eq!(DevRef::Pr(7).to_string(), "PR #7");
+    }
 }
@@ -3,7 +3,7 @@ use std::fmt::Debug;
 use serde_json::Deserializer;
 use url::Url;
 
-use crate::backend::{Backend, BackendType};
+use crate::backend::{Backend, BackendType, REMOTE_VERSIONS_SCHEMA_VERSION};
 use crate::cache::CacheManager;
 use crate::cli::args::BackendArg;
 use crate::cmd::CmdLineRunner;
@@ -82,7 +82,8 @@ impl CargoBackend {
         Self {
             remote_version_cache: CacheManager::new(
                 fa.cache_path.join("remote_versions-$KEY.msgpack.z"),
-            ),
+            )
+            .with_schema_version(REMOTE_VERSIONS_SCHEMA_VERSION),
             fa,
         }
     }
@@ -1,6 +1,6 @@
 use std::fmt::Debug;
 
-use crate::backend::{Backend, BackendType};
+use crate::backend::{Backend, BackendType, REMOTE_VERSIONS_SCHEMA_VERSION};
 use crate::cache::CacheManager;
 use crate::cli::args::BackendArg;
 use crate::cmd::CmdLineRunner;
@@ -88,7 +88,8 @@ impl GoBackend {
         Self {
             remote_version_cache: CacheManager::new(
                 fa.cache_path.join("remote_versions-$KEY.msgpack.z"),
-            ),
+            )
+            .with_schema_version(REMOTE_VERSIONS_SCHEMA_VERSION),
             fa,
         }
     }
@@ -3,32 +3,39 @@ use std::fmt::{Debug, Display, Formatter};
 use std::fs::File;
 use std::hash::Hash;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 
 use clap::Command;
+use color_eyre::Section;
 use console::style;
 use contracts::requires;
-use eyre::{bail, eyre, WrapErr};
+use eyre::{bail, eyre, Report, WrapErr};
 use itertools::Itertools;
 use rayon::prelude::*;
 use regex::Regex;
 use strum::IntoEnumIterator;
-use versions::Versioning;
+use versions::{Requirement, Versioning};
 
 use crate::backend::asdf::Asdf;
 use crate::backend::cargo::CargoBackend;
 use crate::cli::args::BackendArg;
+use crate::config::settings::SettingsInstallSymlinkPolicy;
 use crate::config::{Config, Settings};
+use crate::env_diff::EnvDiff;
+use crate::errors::Error::PluginNotInstalled;
 use crate::file::{display_path, remove_all, remove_all_with_warning};
 use crate::install_context::InstallContext;
 use crate::lock_file::LockFile;
 use crate::plugins::core::CORE_PLUGINS;
 use crate::plugins::{PluginType, VERSION_REGEX};
 use crate::runtime_symlinks::is_runtime_symlink;
+use crate::shell::Shell;
 use crate::toolset::{ToolRequest, ToolVersion, Toolset};
 use crate::ui::multi_progress_report::MultiProgressReport;
 use crate::ui::progress_report::SingleReport;
-use crate::{dirs, file};
+use crate::{dirs, file, timings};
 
 use self::backend_meta::BackendMeta;
 
@@ -45,6 +52,9 @@ pub type ABackend = Arc<dyn Backend>;
 pub type BackendMap = BTreeMap<BackendArg, ABackend>;
 pub type BackendList = Vec<ABackend>;
 
+/// bump when the format of the remote versions lists changes so caches written by older versions of mise are refetched
+pub const REMOTE_VERSIONS_SCHEMA_VERSION: u32 = 1;
+
 #[derive(
     Debug,
     PartialEq,
@@ -76,6 +86,54 @@ impl Display for BackendType {
     }
 }
 
+/// optional features a backend may implement so generic code doesn't need to check backend types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumIter)]
+pub enum Capability {
+    /// can install a tool from a git ref, e.g.: `zig@ref:master`
+    RefPinning,
+    /// verifies downloads against published checksums
+    ChecksumVerification,
+    /// sets env vars for the tool with `exec_env`
+    EnvInjection,
+}
+
+/// whether a backend can be used, see [`Backend::health_probe`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Health {
+    Ok,
+    /// usable but slow or partially broken, with the reason
+    Degraded(String),
+    /// not usable, with the reason
+    Down(String),
+}
+
+impl Display for Health {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Health::Ok => write!(f, "ok"),
+            Health::Degraded(reason) => write!(f, "degraded: {reason}"),
+            Health::Down(reason) => write!(f, "down: {reason}"),
+        }
+    }
+}
+
+/// what [`Backend::ensure_installed`] did, e.g.: for "3 installed, 2 already installed"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallStatus {
+    Installed,
+    /// nothing was done since it was installed before
+    AlreadyInstalled,
+}
+
+impl Display for InstallStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstallStatus::Installed => write!(f, "installed"),
+            InstallStatus::AlreadyInstalled => write!(f, "already installed"),
+        }
+    }
+}
+
 static FORGES: Mutex<Option<BackendMap>> = Mutex::new(None);
 
 fn load_backends() -> BackendMap {
@@ -103,7 +161,7 @@ fn list_installed_backends() -> eyre::Result<BackendList> {
             let id = BackendMeta::read(&dir).id;
             let fa: BackendArg = id.as_str().into();
             match fa.backend_type {
-                BackendType::Asdf => Arc::new(Asdf::new(fa.name)) as ABackend,
+                BackendType::Asdf => Arc::new(Asdf::from_arg(fa)) as ABackend,
                 BackendType::Cargo => Arc::new(CargoBackend::new(fa.name)) as ABackend,
                 BackendType::Core => Arc::new(Asdf::new(fa.name)) as ABackend,
                 BackendType::Npm => Arc::new(npm::NPMBackend::new(fa.name)) as ABackend,
@@ -113,7 +171,9 @@ fn list_installed_backends() -> eyre::Result<BackendList> {
                 BackendType::Ubi => Arc::new(ubi::UbiBackend::new(fa.name)) as ABackend,
             }
         })
-        .filter(|f| f.fa().backend_type != BackendType::Asdf)
+        // asdf plugins are listed from the plugins dir, except for those named after a core
+        // plugin which are installed under their own id, e.g.: "asdf:node"
+        .filter(|f| f.fa().backend_type != BackendType::Asdf || f.id() != f.name())
         .collect())
 }
 
@@ -135,7 +195,7 @@ pub fn get(fa: &BackendArg) -> ABackend {
         backends
             .entry(fa.clone())
             .or_insert_with(|| match fa.backend_type {
-                BackendType::Asdf => Arc::new(Asdf::new(name)),
+                BackendType::Asdf => Arc::new(Asdf::from_arg(fa.clone())),
                 BackendType::Cargo => Arc::new(CargoBackend::new(name)),
                 BackendType::Core => Arc::new(Asdf::new(name)),
                 BackendType::Npm => Arc::new(npm::NPMBackend::new(name)),
@@ -148,6 +208,18 @@ pub fn get(fa: &BackendArg) -> ABackend {
     }
 }
 
+/// the type of backend that handles the tool `name` (e.g.: "node", "tiny" or "cargo:eza") with the
+/// same precedence as `get`: an installed asdf plugin wins over a core plugin of the same name.
+/// Only what's installed is looked at, nothing is installed or fetched
+pub fn resolve_backend_type(name: &str) -> BackendType {
+    let backend = get(&BackendArg::from(unalias_backend(name)));
+    match backend.get_type() {
+        // core plugins are asdf-like backends
+        BackendType::Asdf if backend.get_plugin_type() == PluginType::Core => BackendType::Core,
+        backend_type => backend_type,
+    }
+}
+
 impl From<BackendArg> for ABackend {
     fn from(fa: BackendArg) -> Self {
         get(&fa)
@@ -174,6 +246,9 @@ pub trait Backend: Debug + Send + Sync {
     fn get_plugin_type(&self) -> PluginType {
         PluginType::Core
     }
+    fn supports(&self, _capability: Capability) -> bool {
+        false
+    }
     /// If any of these tools are installing in parallel, we should wait for them to finish
     /// before installing this tool.
     fn get_dependencies(&self, _tvr: &ToolRequest) -> eyre::Result<Vec<BackendArg>> {
@@ -194,9 +269,26 @@ pub trait Backend: Debug + Send + Sync {
         self._list_remote_versions()
     }
     fn _list_remote_versions(&self) -> eyre::Result<Vec<String>>;
+    /// fills the caches used to resolve versions (the remote versions by default) ahead of time so
+    /// later commands don't need the network, see `mise cache warm`
+    fn prewarm(&self) -> eyre::Result<()> {
+        self._list_remote_versions()?;
+        Ok(())
+    }
     fn latest_stable_version(&self) -> eyre::Result<Option<String>> {
         self.latest_version(Some("latest".into()))
     }
+    /// the newest remote version by version order rather than the order they were listed in,
+    /// prereleases (e.g.: "2.0.0-rc1") are skipped unless `include_prerelease`. Backends that
+    /// can fetch the latest version without listing them all may override this
+    fn resolve_latest(&self, include_prerelease: bool) -> eyre::Result<Option<String>> {
+        Ok(self
+            .list_remote_versions()?
+            .into_iter()
+            .filter(|v| include_prerelease || !VERSION_REGEX.is_match(v))
+            .sorted_by_cached_key(|v| (Versioning::new(v), v.to_string()))
+            .last())
+    }
     fn list_installed_versions(&self) -> eyre::Result<Vec<String>> {
         let installs_path = &self.fa().installs_path;
         Ok(match installs_path.exists() {
@@ -234,6 +326,11 @@ pub trait Backend: Debug + Send + Sync {
         };
         !self.is_version_installed(tv) || tv.version != latest
     }
+    /// where `version` is (or would be) installed, same as [`ToolVersion::install_path`]
+    /// for a plain version request
+    fn install_path_for(&self, version: &str) -> PathBuf {
+        self.fa().installs_path.join(ToolVersion::pathname(version))
+    }
     fn symlink_path(&self, tv: &ToolVersion) -> Option<PathBuf> {
         match tv.install_path() {
             path if path.is_symlink() => Some(path),
@@ -253,6 +350,11 @@ pub trait Backend: Debug + Send + Sync {
         let versions = self.list_remote_versions()?;
         fuzzy_match_filter(versions, query)
     }
+    /// remote versions satisfying a range like `>=18 <20`, every requirement must match
+    fn list_remote_versions_matching(&self, range: &str) -> eyre::Result<Vec<String>> {
+        let versions = self.list_remote_versions()?;
+        range_match_filter(versions, range)
+    }
     fn latest_version(&self, query: Option<String>) -> eyre::Result<Option<String>> {
         match query {
             Some(query) => {
@@ -299,10 +401,24 @@ pub trait Backend: Debug + Send + Sync {
         if self.is_installed() {
             return Ok(());
         }
-        bail!("{} is not installed", self.id())
+        Err(Report::new(PluginNotInstalled(self.id().to_string()))
+            .wrap_err(format!("{} is not installed", self.id())))
     }
-    fn ensure_installed(&self, _mpr: &MultiProgressReport, _force: bool) -> eyre::Result<()> {
-        Ok(())
+    fn ensure_installed(
+        &self,
+        _mpr: &MultiProgressReport,
+        _force: bool,
+    ) -> eyre::Result<InstallStatus> {
+        Ok(InstallStatus::AlreadyInstalled)
+    }
+    /// cheap check for status dashboards that the backend can be used, without changing anything
+    /// or hitting the network. Backends that need to touch the filesystem bound it by `timeout`
+    /// so a hung mount reports as degraded rather than blocking the caller
+    fn health_probe(&self, _timeout: Duration) -> Health {
+        match self.is_installed() {
+            true => Health::Ok,
+            false => Health::Down(format!("{} is not installed", self.id())),
+        }
     }
     fn ensure_dependencies_installed(&self) -> eyre::Result<()> {
         let deps = self
@@ -320,7 +436,13 @@ pub trait Backend: Debug + Send + Sync {
         }
         Ok(())
     }
-    fn update(&self, _pr: &dyn SingleReport, _git_ref: Option<String>) -> eyre::Result<()> {
+    /// `allow_downgrade` permits moving to a ref older than the current one without confirming
+    fn update(
+        &self,
+        _pr: &dyn SingleReport,
+        _git_ref: Option<String>,
+        _allow_downgrade: bool,
+    ) -> eyre::Result<()> {
         Ok(())
     }
     fn uninstall(&self, _pr: &dyn SingleReport) -> eyre::Result<()> {
@@ -352,8 +474,40 @@ pub trait Backend: Debug + Send + Sync {
     #[requires(ctx.tv.backend.backend_type == self.get_type())]
     fn install_version(&self, ctx: InstallContext) -> eyre::Result<()> {
         self.is_installed_err()?;
+        if let ToolRequest::Ref { .. } = ctx.tv.request {
+            if !self.supports(Capability::RefPinning) {
+                bail!("{} does not support installing from a ref", self.id());
+            }
+        }
         let config = Config::get();
         let settings = Settings::try_get()?;
+        if let Some(link) = self.symlink_path(&ctx.tv) {
+            if !is_runtime_symlink(&link) {
+                let target = link.read_link()?;
+                match settings.install_symlink_policy {
+                    SettingsInstallSymlinkPolicy::Skip => {
+                        debug!(
+                            "{} is linked to {}, skipping",
+                            ctx.tv,
+                            display_path(&target)
+                        );
+                        ctx.pr
+                            .finish_with_message(format!("linked to {}", display_path(&target)));
+                        return Ok(());
+                    }
+                    // only the symlink is removed, never what it points to
+                    SettingsInstallSymlinkPolicy::Replace => file::remove_file(&link)?,
+                    SettingsInstallSymlinkPolicy::Error => {
+                        return Err(eyre!(
+                            "{} is a symlink to {}",
+                            display_path(&link),
+                            display_path(&target)
+                        ))
+                        .suggestion("remove it or set install_symlink_policy to skip or replace");
+                    }
+                }
+            }
+        }
         if self.is_version_installed(&ctx.tv) {
             if ctx.force {
                 self.uninstall_version(&ctx.tv, ctx.pr.as_ref(), false)?;
@@ -365,7 +519,9 @@ pub trait Backend: Debug + Send + Sync {
         let _lock = self.get_lock(&ctx.tv.install_path(), ctx.force)?;
         self.create_install_dirs(&ctx.tv)?;
 
-        if let Err(e) = self.install_version_impl(&ctx) {
+        let subject = ctx.tv.to_string();
+        let installed = timings::time(&subject, "total", || self.install_version_impl(&ctx));
+        if let Err(e) = installed {
             self.cleanup_install_dirs_on_error(&settings, &ctx.tv);
             return Err(e);
         }
@@ -439,6 +595,52 @@ pub trait Backend: Debug + Send + Sync {
         Ok(BTreeMap::new())
     }
 
+    /// `exec_env` of the highest installed version (compared as semver), for callers that don't
+    /// have a version in mind. None if no version is installed
+    fn latest_installed_exec_env(
+        &self,
+        config: &Config,
+        ts: &Toolset,
+    ) -> eyre::Result<Option<BTreeMap<String, String>>> {
+        let Some(version) = self.list_installed_versions()?.pop() else {
+            return Ok(None);
+        };
+        debug!(
+            "using the newest installed version of {}: {version}",
+            self.id()
+        );
+        let tv = ToolVersion {
+            request: ToolRequest::new(self.fa().clone(), &version)?,
+            backend: self.fa().clone(),
+            version,
+        };
+        Ok(Some(self.exec_env(config, ts, &tv)?))
+    }
+
+    /// `exec_env` as a script to source in `shell`, e.g.: "export JAVA_HOME='/path/to/java'"
+    fn export_env_script(
+        &self,
+        config: &Config,
+        ts: &Toolset,
+        tv: &ToolVersion,
+        shell: &dyn Shell,
+    ) -> eyre::Result<String> {
+        Ok(shell.export_env(&self.exec_env(config, ts, tv)?))
+    }
+
+    /// what changes in `exec_env` when switching from one version to another
+    fn exec_env_diff(
+        &self,
+        config: &Config,
+        ts: &Toolset,
+        from: &ToolVersion,
+        to: &ToolVersion,
+    ) -> eyre::Result<EnvDiff> {
+        let from = self.exec_env(config, ts, from)?.into_iter().collect();
+        let to = self.exec_env(config, ts, to)?.into_iter().collect();
+        Ok(EnvDiff::between(&from, &to))
+    }
+
     fn which(&self, tv: &ToolVersion, bin_name: &str) -> eyre::Result<Option<PathBuf>> {
         let bin_paths = self.list_bin_paths(tv)?;
         for bin_path in bin_paths {
@@ -504,6 +706,20 @@ pub trait Backend: Debug + Send + Sync {
     }
 }
 
+/// runs `probe` on another thread, degraded if it doesn't finish within `timeout`. The thread
+/// is left running in that case since it may be stuck on something that can't be interrupted
+fn probe_with_timeout(
+    timeout: Duration,
+    probe: impl FnOnce() -> Health + Send + 'static,
+) -> Health {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(probe());
+    });
+    rx.recv_timeout(timeout)
+        .unwrap_or_else(|_| Health::Degraded(format!("probe timed out after {timeout:?}")))
+}
+
 fn fuzzy_match_filter(versions: Vec<String>, query: &str) -> eyre::Result<Vec<String>> {
     let mut query = query;
     if query == "latest" {
@@ -525,6 +741,40 @@ fn fuzzy_match_filter(versions: Vec<String>, query: &str) -> eyre::Result<Vec<St
     Ok(versions)
 }
 
+fn range_match_filter(versions: Vec<String>, range: &str) -> eyre::Result<Vec<String>> {
+    // allow a space between the operator and the version, e.g.: ">= 18"
+    let range = Regex::new(r"([<>=~^])\s+")?.replace_all(range, "$1");
+    let requirements = range
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|r| !r.is_empty())
+        .map(|r| {
+            Requirement::new(&pad_semver(r)).ok_or_else(|| eyre!("invalid version range: {range}"))
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+    let versions = versions
+        .into_iter()
+        .filter(|v| match Versioning::new(pad_semver(v)) {
+            // versions that aren't semver-ish (e.g.: "lts-hydrogen") can't be compared
+            Some(Versioning::Complex(_)) | None => false,
+            Some(version) => requirements.iter().all(|r| r.matches(&version)),
+        })
+        .collect();
+    Ok(versions)
+}
+
+/// pads partial versions like npm does (^20 == ^20.0.0) so they're compared as semver
+fn pad_semver(v: &str) -> String {
+    let version = v.trim_start_matches(['<', '>', '=', '~', '^']);
+    if !version.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return v.to_string();
+    }
+    match version.split('.').count() {
+        1 => format!("{v}.0.0"),
+        2 => format!("{v}.0"),
+        _ => v.to_string(),
+    }
+}
+
 fn find_match_in_list(list: &[String], query: &str) -> Option<String> {
     match list.contains(&query.to_string()) {
         true => Some(query.to_string()),
@@ -589,3 +839,251 @@ impl Ord for dyn Backend {
 pub fn reset() {
     *FORGES.lock().unwrap() = None;
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use strum::IntoEnumIterator;
+
+    use crate::plugins::asdf_plugin::AsdfPlugin;
+    use crate::plugins::Plugin;
+    use crate::test::reset;
+
+    use super::*;
+
+    #[test]
+    fn test_supports() {
+        reset();
+        let supported = |id: &str| {
+            let backend = get(&id.into());
+            Capability::iter()
+                .filter(|c| backend.supports(*c))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            supported("tiny"),
+            vec![Capability::RefPinning, Capability::EnvInjection]
+        );
+        assert_eq!(
+            supported("go"),
+            vec![Capability::ChecksumVerification, Capability::EnvInjection]
+        );
+        assert_eq!(supported("node"), vec![Capability::ChecksumVerification]);
+        assert_eq!(supported("zig"), vec![Capability::RefPinning]);
+        assert_eq!(supported("bun"), vec![]);
+        assert_eq!(supported("cargo:eza"), vec![]);
+        assert_eq!(supported("npm:prettier"), vec![]);
+    }
+
+    #[test]
+    fn test_exec_env_diff() {
+        reset();
+        let config = Config::get();
+        let ts = Toolset::default();
+        let backend = get(&"tiny".into());
+        let tv = |v: &str| {
+            let request = ToolRequest::new(backend.fa().clone(), v).unwrap();
+            ToolVersion::new(backend.as_ref(), request, v.to_string())
+        };
+
+        let diff = backend
+            .exec_env_diff(&config, &ts, &tv("2.1.0"), &tv("3.1.0"))
+            .unwrap();
+
+        assert_eq!(
+            diff.old,
+            [("JDXCODE_TINY".to_string(), "2.1.0".to_string())].into()
+        );
+        assert_eq!(
+            diff.new,
+            [("JDXCODE_TINY".to_string(), "3.1.0".to_string())].into()
+        );
+    }
+
+    #[test]
+    fn test_latest_installed_exec_env() {
+        reset();
+        let config = Config::get();
+        let backend = get(&"tiny".into());
+        let installs = &backend.fa().installs_path;
+        for v in ["3.9.0", "3.10.0"] {
+            file::create_dir_all(installs.join(v)).unwrap();
+            file::write(installs.join(v).join("VERSION"), v).unwrap();
+        }
+
+        let env = backend.latest_installed_exec_env(&config, &Toolset::default());
+        for v in ["3.9.0", "3.10.0"] {
+            file::remove_all(installs.join(v)).unwrap();
+        }
+        let env = env.unwrap().unwrap();
+        assert_eq!(env["JDXCODE_TINY"], "3.10.0");
+        let empty = get(&"no-installs".into());
+        let env = empty.latest_installed_exec_env(&config, &Toolset::default());
+        assert_eq!(env.unwrap(), None);
+    }
+
+    #[test]
+    fn test_resolve_backend_type() {
+        reset();
+        assert_eq!(resolve_backend_type("tiny"), BackendType::Asdf);
+        assert_eq!(resolve_backend_type("node"), BackendType::Core);
+        assert_eq!(resolve_backend_type("nodejs"), BackendType::Core);
+        assert_eq!(resolve_backend_type("cargo:eza"), BackendType::Cargo);
+        assert_eq!(resolve_backend_type("ubi"), BackendType::Cargo);
+        // only available through its shorthand
+        assert_eq!(resolve_backend_type("shfmt"), BackendType::Asdf);
+        assert!(!dirs::PLUGINS.join("shfmt").exists());
+
+        file::create_dir_all(dirs::PLUGINS.join("bun")).unwrap();
+        crate::backend::reset();
+        let shadowed = resolve_backend_type("bun");
+        file::remove_all(dirs::PLUGINS.join("bun")).unwrap();
+        crate::backend::reset();
+        assert_eq!(shadowed, BackendType::Asdf);
+        assert_eq!(resolve_backend_type("bun"), BackendType::Core);
+    }
+
+    #[test]
+    fn test_install_path_for() {
+        reset();
+        let backend = get(&"tiny".into());
+        let request = ToolRequest::new(backend.fa().clone(), "3.1.0").unwrap();
+        let tv = ToolVersion::new(backend.as_ref(), request, "3.1.0".into());
+        assert_eq!(backend.install_path_for("3.1.0"), tv.install_path());
+        let plugin = AsdfPlugin::new("tiny".into());
+        assert_eq!(plugin.install_path_for("3.1.0"), tv.install_path());
+        assert_eq!(
+            plugin.install_path_for("ref:feat/x"),
+            backend.fa().installs_path.join("ref-feat-x")
+        );
+    }
+
+    #[test]
+    fn test_install_symlink_policy() {
+        reset();
+        let backend = get(&"tiny".into());
+        let link = backend.install_path_for("1.0.1");
+        let system = tempfile::tempdir().unwrap();
+        file::write(system.path().join("VERSION"), "system").unwrap();
+        let install_with = |policy: &str| {
+            file::remove_all(&link).unwrap();
+            backend.create_symlink("1.0.1", system.path()).unwrap();
+            std::env::set_var("MISE_INSTALL_SYMLINK_POLICY", policy);
+            Settings::reset(None);
+            let result = crate::test::cli_run(&vec![
+                "mise".into(),
+                "install".into(),
+                "-f".into(),
+                "tiny@1.0.1".into(),
+            ]);
+            std::env::remove_var("MISE_INSTALL_SYMLINK_POLICY");
+            Settings::reset(None);
+            result
+        };
+
+        install_with("skip").unwrap();
+        let skipped = link.is_symlink();
+        let err = install_with("error").unwrap_err();
+        install_with("replace").unwrap();
+        let replaced = !link.is_symlink();
+        let version = file::read_to_string(link.join("VERSION")).unwrap();
+        file::remove_all(&link).unwrap();
+
+        assert!(skipped);
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "{} is a symlink to {}",
+                display_path(&link),
+                display_path(system.path())
+            )
+        );
+        assert!(replaced);
+        assert_eq!(version.trim(), "1.0.1");
+        let system_version = file::read_to_string(system.path().join("VERSION")).unwrap();
+        assert_eq!(system_version, "system");
+    }
+
+    #[test]
+    fn test_health_probe() {
+        reset();
+        let timeout = Duration::from_secs(5);
+        assert_eq!(get(&"tiny".into()).health_probe(timeout), Health::Ok);
+        assert_eq!(get(&"node".into()).health_probe(timeout), Health::Ok);
+        let missing = get(&"health-missing".into()).health_probe(timeout);
+        assert!(matches!(missing, Health::Down(_)), "{missing}");
+
+        let hung = probe_with_timeout(Duration::from_millis(10), || {
+            thread::sleep(Duration::from_secs(1));
+            Health::Ok
+        });
+        assert_eq!(hung.to_string(), "degraded: probe timed out after 10ms");
+    }
+
+    #[test]
+    fn test_install_ref_unsupported() {
+        reset();
+        let err = assert_cli_err!("install", "bun@ref:main");
+        assert_eq!(
+            err.to_string(),
+            "bun does not support installing from a ref"
+        );
+    }
+
+    #[test]
+    fn test_resolve_latest() {
+        reset();
+        let list_all = dirs::PLUGINS.join("resolve-latest/bin/list-all");
+        file::create_dir_all(list_all.parent().unwrap()).unwrap();
+        file::write(
+            &list_all,
+            "#!/usr/bin/env bash\necho 1.9.0 2.0.0-rc1 1.10.0 1.2.0\n",
+        )
+        .unwrap();
+        file::make_executable(&list_all).unwrap();
+        let backend = asdf::Asdf::new("resolve-latest".into());
+
+        let stable = backend.resolve_latest(false).unwrap();
+        let prerelease = backend.resolve_latest(true).unwrap();
+        file::remove_all(dirs::PLUGINS.join("resolve-latest")).unwrap();
+        file::remove_all(&backend.fa().cache_path).unwrap();
+
+        assert_eq!(stable.as_deref(), Some("1.10.0"));
+        assert_eq!(prerelease.as_deref(), Some("2.0.0-rc1"));
+    }
+
+    #[test]
+    fn test_range_match_filter() {
+        let versions = [
+            "16.20.2",
+            "18",
+            "18.0.0",
+            "18.19.1",
+            "19.9.0",
+            "20.0.0",
+            "20.11.1",
+            "lts-hydrogen",
+            "ref:main",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(
+            range_match_filter(versions.clone(), ">=18 <20").unwrap(),
+            vec!["18", "18.0.0", "18.19.1", "19.9.0"]
+        );
+        assert_eq!(
+            range_match_filter(versions.clone(), ">= 18.1, < 20").unwrap(),
+            vec!["18.19.1", "19.9.0"]
+        );
+        assert_eq!(
+            range_match_filter(versions.clone(), "^20").unwrap(),
+            vec!["20.0.0", "20.11.1"]
+        );
+        assert_eq!(
+            range_match_filter(versions, "nope")
+                .unwrap_err()
+                .to_string(),
+            "invalid version range: nope"
+        );
+    }
+}
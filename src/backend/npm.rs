@@ -2,7 +2,7 @@ use std::fmt::Debug;
 
 use serde_json::Value;
 
-use crate::backend::{Backend, BackendType};
+use crate::backend::{Backend, BackendType, REMOTE_VERSIONS_SCHEMA_VERSION};
 use crate::cache::CacheManager;
 use crate::cli::args::BackendArg;
 use crate::cmd::CmdLineRunner;
@@ -82,7 +82,8 @@ impl NPMBackend {
         Self {
             remote_version_cache: CacheManager::new(
                 fa.cache_path.join("remote_versions-$KEY.msgpack.z"),
-            ),
+            )
+            .with_schema_version(REMOTE_VERSIONS_SCHEMA_VERSION),
             latest_version_cache: CacheManager::new(
                 fa.cache_path.join("latest_version-$KEY.msgpack.z"),
             ),
@@ -4,7 +4,7 @@ use std::fmt::Debug;
 use std::str::FromStr;
 use versions::Versioning;
 
-use crate::backend::{Backend, BackendType};
+use crate::backend::{Backend, BackendType, REMOTE_VERSIONS_SCHEMA_VERSION};
 use crate::cache::CacheManager;
 use crate::cli::args::BackendArg;
 use crate::cmd::CmdLineRunner;
@@ -106,7 +106,8 @@ impl PIPXBackend {
         Self {
             remote_version_cache: CacheManager::new(
                 fa.cache_path.join("remote_versions-$KEY.msgpack.z"),
-            ),
+            )
+            .with_schema_version(REMOTE_VERSIONS_SCHEMA_VERSION),
             latest_version_cache: CacheManager::new(
                 fa.cache_path.join("latest_version-$KEY.msgpack.z"),
             ),
@@ -9,7 +9,7 @@ use serde_derive::Deserialize;
 use url::Url;
 use walkdir::WalkDir;
 
-use crate::backend::{Backend, BackendType};
+use crate::backend::{Backend, BackendType, REMOTE_VERSIONS_SCHEMA_VERSION};
 use crate::cache::CacheManager;
 use crate::cli::args::BackendArg;
 use crate::cmd::CmdLineRunner;
@@ -90,7 +90,8 @@ impl SPMBackend {
         Self {
             remote_version_cache: CacheManager::new(
                 fa.cache_path.join("remote_versions-$KEY.msgpack.z"),
-            ),
+            )
+            .with_schema_version(REMOTE_VERSIONS_SCHEMA_VERSION),
             fa,
         }
     }
@@ -9,7 +9,8 @@ use flate2::read::ZlibDecoder;
 use flate2::write::ZlibEncoder;
 use flate2::Compression;
 use once_cell::sync::{Lazy, OnceCell};
-use serde::de::DeserializeOwned;
+use rand::Rng;
+use serde::de::{DeserializeOwned, IgnoredAny};
 use serde::Serialize;
 
 use crate::build_time::built_info;
@@ -26,6 +27,8 @@ where
     cache_file_path: PathBuf,
     fresh_duration: Option<Duration>,
     fresh_files: Vec<PathBuf>,
+    stale_window: Option<Duration>,
+    schema_version: Option<u32>,
     cache: Box<OnceCell<T>>,
     no_cache: bool,
 }
@@ -45,6 +48,8 @@ where
             cache: Box::new(OnceCell::new()),
             fresh_files: Vec::new(),
             fresh_duration: None,
+            stale_window: None,
+            schema_version: None,
             no_cache: false,
         }
     }
@@ -59,36 +64,134 @@ where
         self
     }
 
+    /// once the cache expires, only one process refetches while the others keep serving the
+    /// expired value for up to a randomized portion of `window` instead of all refetching at once
+    pub fn with_stampede_protection(mut self, window: Duration) -> Self {
+        self.stale_window = Some(window);
+        self
+    }
+
+    /// embeds `version` in the cache file, a cache file written with another version (or none)
+    /// is discarded and refetched instead of being deserialized. Bump it when `T` changes in a
+    /// way that old cache files would deserialize incorrectly
+    pub fn with_schema_version(mut self, version: u32) -> Self {
+        self.schema_version = Some(version);
+        self
+    }
+
     pub fn get_or_try_init<F>(&self, fetch: F) -> Result<&T>
     where
         F: FnOnce() -> Result<T>,
     {
         let val = self.cache.get_or_try_init(|| {
-            let path = &self.cache_file_path;
             if !self.no_cache && self.is_fresh() {
-                match self.parse() {
-                    Ok(val) => return Ok::<_, color_eyre::Report>(val),
-                    Err(err) => {
-                        warn!("failed to parse cache file: {} {:#}", path.display(), err);
-                    }
+                if let Some(val) = self.try_parse() {
+                    return Ok::<_, color_eyre::Report>(val);
                 }
             }
-            let val = (fetch)()?;
-            if let Err(err) = self.write(&val) {
-                warn!("failed to write cache file: {} {:#}", path.display(), err);
+            if !self.no_cache && self.stale_window.is_some() && self.cache_file_path.exists() {
+                return self.fetch_single_flight(fetch);
             }
-            Ok(val)
+            self.fetch_and_write(fetch)
         })?;
         Ok(val)
     }
 
-    fn parse(&self) -> Result<T> {
+    /// the value in the cache file even if it is no longer fresh, e.g.: to only fetch what
+    /// changed since it was written. None if there is no cache file or one of the fresh files
+    /// changed after it was written since the value may not be valid anymore
+    pub fn get_expired(&self) -> Option<T> {
+        if self.no_cache {
+            return None;
+        }
+        let age = modified_duration(&self.cache_file_path).ok()?;
+        if self
+            .fresh_files
+            .iter()
+            .any(|path| modified_duration(path).is_ok_and(|d| d < age))
+        {
+            return None;
+        }
+        self.try_parse()
+    }
+
+    fn fetch_and_write<F>(&self, fetch: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<T>,
+    {
+        let val = (fetch)()?;
+        if let Err(err) = self.write(&val) {
+            let path = &self.cache_file_path;
+            warn!("failed to write cache file: {} {:#}", path.display(), err);
+        }
+        Ok(val)
+    }
+
+    fn fetch_single_flight<F>(&self, fetch: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<T>,
+    {
+        let lock_path = self.cache_file_path.with_extension("lock");
+        let mut lock = match fslock::LockFile::open(&lock_path) {
+            Ok(lock) => lock,
+            Err(err) => {
+                debug!("failed to open lock {}: {err:#}", display_path(&lock_path));
+                return self.fetch_and_write(fetch);
+            }
+        };
+        if !lock.try_lock()? {
+            if self.is_within_stale_window() {
+                if let Some(val) = self.try_parse() {
+                    trace!("serving stale {}", display_path(&self.cache_file_path));
+                    return Ok(val);
+                }
+            }
+            lock.lock()?;
+        }
+        // another process may have refreshed the cache while we were waiting on the lock
+        if self.is_fresh() {
+            if let Some(val) = self.try_parse() {
+                return Ok(val);
+            }
+        }
+        self.fetch_and_write(fetch)
+    }
+
+    fn try_parse(&self) -> Option<T> {
+        match self.parse() {
+            Ok(Some(val)) => Some(val),
+            Ok(None) => {
+                let path = display_path(&self.cache_file_path);
+                debug!("discarding cache file with another schema version: {path}");
+                None
+            }
+            Err(err) => {
+                let path = &self.cache_file_path;
+                warn!("failed to parse cache file: {} {:#}", path.display(), err);
+                None
+            }
+        }
+    }
+
+    /// None if the cache file has another schema version
+    fn parse(&self) -> Result<Option<T>> {
         let path = &self.cache_file_path;
         trace!("reading {}", display_path(path));
         let mut zlib = ZlibDecoder::new(File::open(path)?);
         let mut bytes = Vec::new();
         zlib.read_to_end(&mut bytes)?;
-        Ok(rmp_serde::from_slice(&bytes)?)
+        let Some(version) = self.schema_version else {
+            return Ok(Some(rmp_serde::from_slice(&bytes)?));
+        };
+        // the version is checked before deserializing the value since an old one may not
+        // deserialize as `T`, or worse, deserialize as the wrong thing
+        match rmp_serde::from_slice::<(u32, IgnoredAny)>(&bytes) {
+            Ok((v, _)) if v == version => {
+                let (_, val): (u32, T) = rmp_serde::from_slice(&bytes)?;
+                Ok(Some(val))
+            }
+            _ => Ok(None),
+        }
     }
 
     pub fn write(&self, val: &T) -> Result<()> {
@@ -100,13 +203,16 @@ where
             .cache_file_path
             .with_extension(format!("part-{}", random_string(8)));
         let mut zlib = ZlibEncoder::new(File::create(&partial_path)?, Compression::fast());
-        zlib.write_all(&rmp_serde::to_vec_named(&val)?[..])?;
+        let bytes = match self.schema_version {
+            Some(version) => rmp_serde::to_vec_named(&(version, val))?,
+            None => rmp_serde::to_vec_named(&val)?,
+        };
+        zlib.write_all(&bytes[..])?;
         file::rename(&partial_path, &self.cache_file_path)?;
 
         Ok(())
     }
 
-    #[cfg(test)]
     pub fn clear(&self) -> Result<()> {
         let path = &self.cache_file_path;
         trace!("clearing cache {}", path.display());
@@ -130,6 +236,18 @@ where
         true
     }
 
+    fn is_within_stale_window(&self) -> bool {
+        let Some(window) = self.stale_window else {
+            return false;
+        };
+        let Ok(age) = modified_duration(&self.cache_file_path) else {
+            return false;
+        };
+        // jitter so processes that expired at the same moment don't all give up at once either
+        let window = window.mul_f64(rand::thread_rng().gen_range(0.5..=1.0));
+        age < self.freshest_duration().unwrap_or_default() + window
+    }
+
     fn freshest_duration(&self) -> Option<Duration> {
         let mut freshest = self.fresh_duration;
         for path in &self.fresh_files {
@@ -158,6 +276,11 @@ static KEY: Lazy<String> = Lazy::new(|| {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::SystemTime;
+
+    use filetime::FileTime;
     use pretty_assertions::assert_eq;
 
     use super::*;
@@ -172,4 +295,104 @@ mod tests {
         let val = cache.get_or_try_init(|| Ok(2)).unwrap();
         assert_eq!(val, &1);
     }
+
+    #[test]
+    fn test_schema_version() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let path = tmpdir.path().join("remote_versions.msgpack.z");
+        let cache = |version| CacheManager::<Vec<String>>::new(&path).with_schema_version(version);
+        let untagged = CacheManager::<Vec<String>>::new(&path);
+        fn fetch(v: &'static str) -> impl FnOnce() -> Result<Vec<String>> {
+            move || Ok(vec![v.to_string()])
+        }
+
+        untagged.write(&vec!["untagged".into()]).unwrap();
+        assert_eq!(cache(1).get_or_try_init(fetch("v1")).unwrap(), &["v1"]);
+        // written by the first fetch with the current version so it is reused
+        assert_eq!(
+            cache(1).get_or_try_init(fetch("refetched")).unwrap(),
+            &["v1"]
+        );
+        // an old version tag is ignored
+        assert_eq!(cache(2).get_or_try_init(fetch("v2")).unwrap(), &["v2"]);
+        assert_eq!(
+            cache(1).get_or_try_init(fetch("v1 again")).unwrap(),
+            &["v1 again"]
+        );
+    }
+
+    #[test]
+    fn test_get_expired() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let path = tmpdir.path().join("remote_versions.msgpack.z");
+        let fresh_file = tmpdir.path().join("list-all");
+        let cache = || {
+            CacheManager::<u32>::new(&path)
+                .with_fresh_duration(Some(Duration::ZERO))
+                .with_fresh_file(fresh_file.clone())
+        };
+        file::write(&fresh_file, "").unwrap();
+        let mtime = FileTime::from_system_time(SystemTime::now() - Duration::from_secs(10));
+        filetime::set_file_mtime(&fresh_file, mtime).unwrap();
+
+        assert_eq!(cache().get_expired(), None);
+        cache().write(&1).unwrap();
+        assert_eq!(cache().get_expired(), Some(1));
+        filetime::set_file_mtime(&fresh_file, FileTime::now()).unwrap();
+        assert_eq!(cache().get_expired(), None);
+    }
+
+    #[test]
+    fn test_stampede_protection() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let path = tmpdir.path().join("remote_versions.msgpack.z");
+        let expire = || {
+            let mtime = FileTime::from_system_time(SystemTime::now() - Duration::from_secs(10));
+            filetime::set_file_mtime(&path, mtime).unwrap();
+        };
+        let new_cache = |window| {
+            CacheManager::<u32>::new(&path)
+                .with_fresh_duration(Some(Duration::from_secs(5)))
+                .with_stampede_protection(window)
+        };
+        new_cache(Duration::ZERO).write(&1).unwrap();
+
+        // waiters serve the stale value while one refetches
+        expire();
+        let fetches = AtomicUsize::new(0);
+        let vals = run_concurrently(|| new_cache(Duration::from_secs(60)), &fetches);
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert!(vals.iter().all(|v| *v == 1 || *v == 2));
+        assert!(vals.contains(&2));
+
+        // outside of the stale window waiters block and reuse the refreshed value
+        expire();
+        let fetches = AtomicUsize::new(0);
+        let vals = run_concurrently(|| new_cache(Duration::ZERO), &fetches);
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert_eq!(vals, vec![2; 8]);
+    }
+
+    fn run_concurrently(
+        new_cache: impl Fn() -> CacheManager<u32> + Sync,
+        fetches: &AtomicUsize,
+    ) -> Vec<u32> {
+        thread::scope(|s| {
+            let handles = (0..8)
+                .map(|_| {
+                    s.spawn(|| {
+                        let cache = new_cache();
+                        *cache
+                            .get_or_try_init(|| {
+                                fetches.fetch_add(1, Ordering::SeqCst);
+                                thread::sleep(Duration::from_millis(200));
+                                Ok(2)
+                            })
+                            .unwrap()
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        })
+    }
 }
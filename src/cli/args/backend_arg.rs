@@ -8,6 +8,7 @@ use once_cell::sync::Lazy;
 
 use crate::backend::{unalias_backend, BackendType};
 use crate::dirs;
+use crate::plugins::core::CORE_PLUGINS;
 use crate::registry::REGISTRY;
 
 #[derive(Clone, PartialOrd, Ord)]
@@ -36,7 +37,15 @@ impl<A: AsRef<str>> From<A> for BackendArg {
         }
         if let Some((backend_type, name)) = s.split_once(':') {
             if let Ok(backend_type) = backend_type.parse() {
-                return Self::new(backend_type, name);
+                let fa = Self::new(backend_type, name);
+                // the asdf plugin gets its own id and dirs so it doesn't share the core plugin's
+                if backend_type == BackendType::Asdf
+                    && CORE_PLUGINS.iter().any(|p| p.name() == fa.name)
+                {
+                    let id = format!("{backend_type}:{}", fa.name);
+                    return Self::with_id(backend_type, fa.name, id);
+                }
+                return fa;
             }
         }
         Self::new(BackendType::Asdf, s)
@@ -50,6 +59,10 @@ impl BackendArg {
             BackendType::Asdf | BackendType::Core => name.clone(),
             backend_type => format!("{backend_type}:{name}"),
         };
+        Self::with_id(backend_type, name, id)
+    }
+
+    fn with_id(backend_type: BackendType, name: String, id: String) -> Self {
         let pathname = id.to_kebab_case();
         Self {
             name,
@@ -120,6 +133,7 @@ mod tests {
         let npm = |s, id, name| t(s, id, name, BackendType::Npm);
 
         asdf("asdf:poetry", "poetry", "poetry");
+        asdf("asdf:node", "asdf:node", "node");
         asdf("poetry", "poetry", "poetry");
         asdf("", "", "");
         cargo("cargo:eza", "cargo:eza", "eza");
@@ -136,7 +150,7 @@ mod tests {
             let expected = dirs::INSTALLS.join(expected);
             assert_str_eq!(actual, expected.to_string_lossy());
         };
-        t("asdf:node", "node");
+        t("asdf:node", "asdf-node");
         t("node", "node");
         t("", "");
         t("cargo:eza", "cargo-eza");
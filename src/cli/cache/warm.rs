@@ -0,0 +1,131 @@
+use eyre::{bail, Result};
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+
+use crate::backend;
+use crate::backend::ABackend;
+use crate::cli::args::BackendArg;
+use crate::config::{Config, Settings};
+use crate::toolset::ToolsetBuilder;
+use crate::ui::multi_progress_report::MultiProgressReport;
+use crate::ui::style;
+
+/// Fetches the remote versions of tools ahead of time
+///
+/// Later commands resolve versions from the cache instead of the network,
+/// e.g.: to warm the cache in CI before going offline.
+/// Without arguments the tools in the current config are warmed.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct CacheWarm {
+    /// Tool(s) to warm the cache for
+    /// e.g.: node, python
+    #[clap(verbatim_doc_comment)]
+    tool: Vec<BackendArg>,
+
+    /// Warm the cache for every installed plugin and backend
+    #[clap(long, short, conflicts_with = "tool")]
+    all: bool,
+}
+
+impl CacheWarm {
+    pub fn run(self) -> Result<()> {
+        let backends = self.get_backends()?;
+        let mpr = MultiProgressReport::get();
+        let failed = ThreadPoolBuilder::new()
+            .num_threads(Settings::get().jobs)
+            .build()?
+            .install(|| {
+                backends
+                    .par_iter()
+                    .filter(|backend| {
+                        let pr = mpr.add(&style::eblue(backend.id()).to_string());
+                        pr.set_message("fetching remote versions".into());
+                        match backend.prewarm() {
+                            Ok(()) => {
+                                pr.finish_with_message("warmed".into());
+                                false
+                            }
+                            Err(err) => {
+                                pr.finish_with_message("failed".into());
+                                warn!("failed to warm the cache for {}: {err:#}", backend.id());
+                                true
+                            }
+                        }
+                    })
+                    .count()
+            });
+        if failed > 0 {
+            bail!("failed to warm the cache for {failed} tool(s)");
+        }
+        Ok(())
+    }
+
+    fn get_backends(&self) -> Result<Vec<ABackend>> {
+        if self.all {
+            return Ok(backend::list()
+                .into_iter()
+                .filter(|b| b.is_installed())
+                .collect());
+        }
+        if !self.tool.is_empty() {
+            return Ok(self.tool.iter().map(backend::get).collect());
+        }
+        let config = Config::try_get()?;
+        Ok(ToolsetBuilder::new().build(&config)?.list_plugins())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise cache warm</bold>
+    $ <bold>mise cache warm node python</bold>
+    $ <bold>mise cache warm --all</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use test_log::test;
+
+    use crate::backend::asdf::Asdf;
+    use crate::backend::Backend;
+    use crate::dirs;
+    use crate::file;
+    use crate::test::reset;
+
+    #[test]
+    fn test_cache_warm() {
+        reset();
+        let versions = tempfile::NamedTempFile::new().unwrap();
+        file::write(versions.path(), "1.0.0 1.1.0").unwrap();
+        let plugin_path = dirs::PLUGINS.join("prewarm");
+        let list_all = plugin_path.join("bin/list-all");
+        file::create_dir_all(list_all.parent().unwrap()).unwrap();
+        let script = format!("#!/usr/bin/env bash\ncat {}\n", versions.path().display());
+        file::write(&list_all, script).unwrap();
+        file::make_executable(&list_all).unwrap();
+        let cache_path = dirs::CACHE.join("prewarm");
+        let _ = file::remove_all(&cache_path);
+
+        assert_cli!("cache", "warm", "prewarm");
+        let cached = file::ls(&cache_path).unwrap();
+        // the plugin can't list its versions anymore, e.g.: when offline
+        file::remove_file(versions.path()).unwrap();
+        let offline = Asdf::new("prewarm".into()).list_remote_versions();
+        file::remove_all(&plugin_path).unwrap();
+        file::remove_all(&cache_path).unwrap();
+
+        assert!(
+            cached.iter().any(|p| p
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("remote_versions-")),
+            "{cached:?}"
+        );
+        assert_eq!(offline.unwrap(), vec!["1.0.0", "1.1.0"]);
+    }
+}
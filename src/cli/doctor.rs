@@ -14,17 +14,29 @@ use crate::cli::version::VERSION;
 use crate::config::{Config, Settings};
 use crate::file::display_path;
 use crate::git::Git;
+use crate::plugins::asdf_plugin::AsdfPlugin;
 use crate::plugins::core::CORE_PLUGINS;
-use crate::plugins::PluginType;
+use crate::plugins::{Plugin, PluginMap, PluginType, RemoteUrlCheck};
 use crate::shell::ShellType;
 use crate::toolset::{Toolset, ToolsetBuilder};
 use crate::ui::style;
-use crate::{backend, cmd, dirs, duration, env, file, shims};
+use crate::{backend, cmd, dirs, duration, env, file, plugins, shims};
 
 /// Check mise installation for possible problems
+///
+/// Plugins whose remote url is unreachable or has moved are flagged
+/// With GITHUB_API_TOKEN set, plugins from GitHub repos that have been archived are flagged too
+/// These checks need the network, use --offline to skip them
 #[derive(Debug, clap::Args)]
 #[clap(visible_alias = "dr", verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
 pub struct Doctor {
+    /// Move installs left in the layout of older versions of mise to where they are looked for now
+    /// and repair symlinks in installs that broke when the mise data dir was moved
+    #[clap(long, verbatim_doc_comment)]
+    fix: bool,
+    /// Skip the checks that need the network, e.g.: whether plugin remotes are reachable
+    #[clap(long)]
+    offline: bool,
     #[clap(skip)]
     errors: Vec<String>,
     #[clap(skip)]
@@ -33,6 +45,10 @@ pub struct Doctor {
 
 impl Doctor {
     pub fn run(mut self) -> eyre::Result<()> {
+        let plugins = plugins::list2().unwrap_or_default();
+        if self.fix {
+            self.fix_installed_layouts(&plugins);
+        }
         inline_section("version", &*VERSION)?;
         inline_section("activated", yn(env::is_activated()))?;
         inline_section("shims_on_path", yn(shims_on_path()))?;
@@ -46,7 +62,11 @@ impl Doctor {
             Err(err) => self.errors.push(format!("failed to load config: {err}")),
         }
 
-        self.analyze_plugins();
+        self.analyze_plugins(&plugins);
+        match self.offline {
+            true => inline_section("remote_checks", "skipped (--offline)")?,
+            false => self.analyze_plugin_remotes(plugins),
+        }
 
         section("env_vars", mise_env_vars())?;
         self.analyze_settings()?;
@@ -87,6 +107,23 @@ impl Doctor {
         Ok(())
     }
 
+    fn fix_installed_layouts(&mut self, plugins: &PluginMap) {
+        for plugin in plugins.values() {
+            if let Err(err) = plugin.normalize_installed_layout() {
+                self.errors.push(format!(
+                    "failed to fix the installs of plugin {}: {err:#}",
+                    plugin.name()
+                ));
+            }
+            if let Err(err) = plugin.relink() {
+                self.errors.push(format!(
+                    "failed to relink the installs of plugin {}: {err:#}",
+                    plugin.name()
+                ));
+            }
+        }
+    }
+
     fn analyze_settings(&mut self) -> eyre::Result<()> {
         match Settings::try_get() {
             Ok(settings) => {
@@ -127,6 +164,9 @@ impl Doctor {
             Ok(ts) => {
                 self.analyze_shims(&ts);
                 self.analyze_toolset(&ts)?;
+                for collision in ts.env_collisions(config) {
+                    self.warnings.push(collision.to_string());
+                }
             }
             Err(err) => self.errors.push(format!("failed to load toolset: {}", err)),
         }
@@ -185,7 +225,7 @@ impl Doctor {
         trace!("Shim analysis took {:?}", start_ms.elapsed());
     }
 
-    fn analyze_plugins(&mut self) {
+    fn analyze_plugins(&mut self, plugins: &PluginMap) {
         for plugin in backend::list() {
             let is_core = CORE_PLUGINS.iter().any(|fg| fg.id() == plugin.id());
             let plugin_type = plugin.get_plugin_type();
@@ -195,6 +235,69 @@ impl Doctor {
                     .push(format!("plugin {} overrides a core plugin", &plugin.id()));
             }
         }
+        for plugin in plugins.values() {
+            match plugin.git_status() {
+                Ok(Some(status)) if status.is_dirty() => self.warnings.push(format!(
+                    "plugin {} has local changes in {}",
+                    plugin.name(),
+                    display_path(dirs::plugin_path(plugin.name()))
+                )),
+                Ok(_) => {}
+                Err(err) => warn!("plugin {}: {err:#}", plugin.name()),
+            }
+        }
+        // plugins that failed to install generally aren't in the plugins dir, their errors are
+        // kept in their cache dir
+        for name in file::dir_subdirs(&dirs::CACHE).unwrap_or_default() {
+            if let Ok(Some(err)) = AsdfPlugin::new(name.clone()).last_error() {
+                self.warnings.push(format!(
+                    "plugin {name} failed to install at {} ({}): {}",
+                    err.timestamp, err.phase, err.message
+                ));
+            }
+        }
+    }
+
+    /// flags plugins whose upstream is archived or whose remote url is unreachable or has moved
+    fn analyze_plugin_remotes(&mut self, plugins: PluginMap) {
+        for plugin in plugins.values() {
+            if plugin.upstream_archived() == Some(true) {
+                let url = plugin.get_remote_url().ok().flatten().unwrap_or_default();
+                self.warnings.push(format!(
+                    "plugin {} upstream {url} is archived and won't receive updates",
+                    plugin.name(),
+                ));
+            }
+        }
+        let asdf_plugins = plugins
+            .into_iter()
+            .filter(|(_, p)| p.get_plugin_type() == PluginType::Asdf)
+            .collect();
+        match plugins::remote_url_redirect_check_all(asdf_plugins) {
+            Ok(checks) => {
+                for (name, check) in checks {
+                    match check {
+                        Ok(Some(RemoteUrlCheck {
+                            url,
+                            reachable: false,
+                            ..
+                        })) => self
+                            .warnings
+                            .push(format!("plugin {name} remote {url} is unreachable")),
+                        Ok(Some(RemoteUrlCheck {
+                            url,
+                            redirect: Some(moved),
+                            ..
+                        })) => self
+                            .warnings
+                            .push(format!("plugin {name} remote {url} has moved to {moved}")),
+                        Ok(_) => {}
+                        Err(err) => warn!("plugin {name}: {err:#}"),
+                    }
+                }
+            }
+            Err(err) => warn!("failed to check plugin remotes: {err:#}"),
+        }
     }
 }
 
@@ -270,7 +373,7 @@ fn render_plugins() -> String {
             let padded_name = pad_str(p.id(), max_plugin_name_len, Alignment::Left, None);
             let extra = match p.get_plugin_type() {
                 PluginType::Asdf => {
-                    let git = Git::new(dirs::PLUGINS.join(p.id()));
+                    let git = Git::new(dirs::plugin_path(p.id()));
                     match git.get_remote_url() {
                         Some(url) => {
                             let sha = git
@@ -327,6 +430,12 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 
     $ <bold>mise doctor</bold>
     [WARN] plugin node is not installed
+
+    # move installs left by older versions of mise to where they are looked for now
+    $ <bold>mise doctor --fix</bold>
+
+    # without checking whether plugin remotes have moved or been archived
+    $ <bold>mise doctor --offline</bold>
 "#
 );
 
@@ -1,8 +1,9 @@
-use eyre::Result;
+use eyre::{bail, Result};
 
+use crate::backend;
 use crate::cli::args::ToolArg;
 use crate::config::Config;
-use crate::shell::{get_shell, ShellType};
+use crate::shell::{get_shell, Shell, ShellType};
 use crate::toolset::{InstallOptions, Toolset, ToolsetBuilder};
 
 /// Exports env vars to activate mise a single time
@@ -23,11 +24,20 @@ pub struct Env {
     /// Shell type to generate environment variables for
     #[clap(long, short, overrides_with = "json")]
     shell: Option<ShellType>,
+
+    /// Only export the env vars the plugin sets for this tool
+    /// e.g.: JAVA_HOME for java, without PATH or env vars from config files
+    /// Without a version, the active one is used or else the newest installed one
+    #[clap(long = "tool", value_name = "TOOL@VERSION", conflicts_with_all = ["tool", "json"], verbatim_doc_comment)]
+    tool_env: Option<ToolArg>,
 }
 
 impl Env {
     pub fn run(self) -> Result<()> {
         let config = Config::try_get()?;
+        if let Some(tool) = &self.tool_env {
+            return self.output_tool_env(&config, tool);
+        }
         let mut ts = ToolsetBuilder::new().with_args(&self.tool).build(&config)?;
         ts.install_arg_versions(&config, &InstallOptions::new())?;
         ts.notify_if_versions_missing();
@@ -46,8 +56,7 @@ impl Env {
     }
 
     fn output_shell(&self, config: &Config, ts: Toolset) -> Result<()> {
-        let default_shell = get_shell(Some(ShellType::Bash)).unwrap();
-        let shell = get_shell(self.shell).unwrap_or(default_shell);
+        let shell = self.get_shell();
         for (k, v) in ts.env_with_path(config)? {
             let k = k.to_string();
             let v = v.to_string();
@@ -55,6 +64,37 @@ impl Env {
         }
         Ok(())
     }
+
+    fn output_tool_env(&self, config: &Config, tool: &ToolArg) -> Result<()> {
+        let mut ts = ToolsetBuilder::new()
+            .with_args(std::slice::from_ref(tool))
+            .build(config)?;
+        ts.install_arg_versions(config, &InstallOptions::new())?;
+        let Some((backend, tv)) = ts
+            .list_current_installed_versions()
+            .into_iter()
+            .find(|(b, _)| b.fa() == &tool.backend)
+        else {
+            // with no version asked for and none active, fall back to the newest one installed
+            let backend = backend::get(&tool.backend);
+            if let (None, Some(env)) = (&tool.tvr, backend.latest_installed_exec_env(config, &ts)?)
+            {
+                miseprint!("{}", self.get_shell().export_env(&env))?;
+                return Ok(());
+            }
+            bail!("{} is not installed", tool.style());
+        };
+        miseprint!(
+            "{}",
+            backend.export_env_script(config, &ts, &tv, &*self.get_shell())?
+        )?;
+        Ok(())
+    }
+
+    fn get_shell(&self) -> Box<dyn Shell> {
+        let default_shell = get_shell(Some(ShellType::Bash)).unwrap();
+        get_shell(self.shell).unwrap_or(default_shell)
+    }
 }
 
 static AFTER_LONG_HELP: &str = color_print::cstr!(
@@ -64,6 +104,7 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>eval "$(mise env -s zsh)"</bold>
     $ <bold>mise env -s fish | source</bold>
     $ <bold>execx($(mise env -s xonsh))</bold>
+    $ <bold>mise env -s fish --tool java@21</bold>
 "#
 );
 
@@ -125,6 +166,13 @@ mod tests {
         assert_str_eq!(grep(stdout, "JDXCODE"), "export JDXCODE_TINY=2.1.0");
     }
 
+    #[test]
+    fn test_env_tool() {
+        reset();
+        let stdout = assert_cli!("env", "--tool", "tiny@2", "-s", "fish");
+        assert_str_eq!(stdout, "set -gx JDXCODE_TINY 2.1.0");
+    }
+
     #[test]
     fn test_env_default_shell() {
         reset();
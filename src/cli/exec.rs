@@ -63,6 +63,7 @@ impl Exec {
             jobs: self.jobs,
             raw: self.raw,
             latest_versions: false,
+            keep_going: false,
         };
         ts.install_arg_versions(&config, &opts)?;
         ts.notify_if_versions_missing();
@@ -2,10 +2,14 @@ use eyre::Result;
 use itertools::Itertools;
 use std::collections::HashSet;
 
+use tabled::Table;
+
 use crate::cli::args::{BackendArg, ToolArg};
 use crate::config::Config;
+use crate::timings::{self, TimingRow};
 use crate::toolset::{InstallOptions, ToolRequest, ToolVersion, ToolVersionOptions, Toolset};
 use crate::ui::multi_progress_report::MultiProgressReport;
+use crate::ui::table;
 
 /// Install a tool version
 ///
@@ -33,11 +37,22 @@ pub struct Install {
     #[clap(long, short, env = "MISE_JOBS", verbatim_doc_comment)]
     jobs: Option<usize>,
 
+    /// Keep installing the other tools if one fails
+    /// all of the failures are reported at the end
+    #[clap(long, short = 'k', verbatim_doc_comment)]
+    keep_going: bool,
+
     /// Directly pipe stdin/stdout/stderr from plugin to user
     /// Sets --jobs=1
     #[clap(long, overrides_with = "jobs")]
     raw: bool,
 
+    /// Show how long each phase of installing the plugins and tools took
+    /// e.g.: cloning a plugin or running its download script
+    /// This is only measured and shown locally, nothing is sent anywhere
+    #[clap(long, verbatim_doc_comment)]
+    timings: bool,
+
     /// Show installation output
     ///
     /// This argument will print plugin output such as download, configuration, and compilation output.
@@ -48,10 +63,17 @@ pub struct Install {
 impl Install {
     pub fn run(self) -> Result<()> {
         let config = Config::try_get()?;
-        match &self.tool {
-            Some(runtime) => self.install_runtimes(&config, runtime)?,
-            None => self.install_missing_runtimes(&config)?,
+        if self.timings {
+            timings::enable();
+        }
+        let installed = match &self.tool {
+            Some(runtime) => self.install_runtimes(&config, runtime),
+            None => self.install_missing_runtimes(&config),
         };
+        if self.timings {
+            show_timings();
+        }
+        installed?;
 
         Ok(())
     }
@@ -74,6 +96,7 @@ impl Install {
             jobs: self.jobs,
             raw: self.raw,
             latest_versions: true,
+            keep_going: self.keep_going,
         }
     }
 
@@ -130,6 +153,18 @@ impl Install {
     }
 }
 
+/// prints the timings recorded during the install to stderr, even if it failed since knowing
+/// where the time went is most useful for installs that timed out
+fn show_timings() {
+    let rows = timings::take().iter().map(TimingRow::from).collect_vec();
+    if rows.is_empty() {
+        return;
+    }
+    let mut table = Table::new(rows);
+    table::default_style(&mut table, false);
+    eprintln!("{table}");
+}
+
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
 
@@ -142,6 +177,7 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 
 #[cfg(test)]
 mod tests {
+    use indoc::indoc;
     use pretty_assertions::assert_str_eq;
 
     use crate::dirs;
@@ -179,6 +215,48 @@ mod tests {
         assert_cli!("global", "--unset", "dummy");
     }
 
+    #[test]
+    fn test_install_keep_going() {
+        reset();
+        assert_cli!("uninstall", "tiny@1.0.1");
+        let err = assert_cli_err!(
+            "install",
+            "--keep-going",
+            "keep-going-missing@1",
+            "tiny@1.0.1"
+        );
+        assert_str_eq!(
+            err.to_string(),
+            "[keep-going-missing] plugin install: No repository found for plugin keep-going-missing"
+        );
+        assert!(dirs::INSTALLS.join("tiny/1.0.1").exists());
+
+        assert_cli!("uninstall", "tiny@1.0.1");
+        let err = assert_cli_err!(
+            "install",
+            "--keep-going",
+            "keep-going-missing@1",
+            "keep-going-missing2@1",
+            "tiny@1.0.1"
+        );
+        assert_str_eq!(
+            err.to_string(),
+            indoc! {"
+                2 tools failed to install:
+                [keep-going-missing2] plugin install: No repository found for plugin keep-going-missing2
+                [keep-going-missing] plugin install: No repository found for plugin keep-going-missing"}
+        );
+        assert!(dirs::INSTALLS.join("tiny/1.0.1").exists());
+
+        assert_cli!("uninstall", "tiny@1.0.1");
+        let err = assert_cli_err!("install", "keep-going-missing@1", "tiny@1.0.1");
+        assert_str_eq!(
+            err.to_string(),
+            "No repository found for plugin keep-going-missing"
+        );
+        assert_cli!("uninstall", "tiny@1.0.1");
+    }
+
     #[test]
     fn test_install_nothing() {
         reset();
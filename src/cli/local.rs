@@ -191,6 +191,10 @@ mod tests {
             assert_cli!("local", "--pin", "tiny", "2");
             let stdout = assert_cli!("local");
             assert_str_eq!(grep(stdout, "tiny"), "tiny 2.1.0");
+            // no remote version matches so the requested version is pinned as-is
+            assert_cli!("local", "--pin", "tiny@9");
+            let stdout = assert_cli!("local");
+            assert_str_eq!(grep(stdout, "tiny"), "tiny 9");
         });
     }
 
@@ -0,0 +1,42 @@
+use eyre::Result;
+
+use crate::backend::unalias_backend;
+use crate::plugins;
+use crate::plugins::asdf_plugin::AsdfPlugin;
+use crate::plugins::Plugin;
+use crate::ui::style;
+
+/// Disables a plugin without uninstalling it
+///
+/// The plugin is added to `disable_tools` in ~/.config/mise/config.toml
+/// so it's hidden along with its tools until `mise plugins enable` is run.
+/// Its installed versions are kept.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct PluginsDisable {
+    /// Plugin(s) to disable
+    #[clap(required = true, verbatim_doc_comment)]
+    plugin: Vec<String>,
+}
+
+impl PluginsDisable {
+    pub fn run(self) -> Result<()> {
+        for name in &self.plugin {
+            let name = unalias_backend(name);
+            if !plugins::get(name).is_installed() {
+                warn!("{} is not installed", style::eblue(name));
+                continue;
+            }
+            AsdfPlugin::new(name.to_string()).disable()?;
+            info!("disabled {}", style::eblue(name));
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise plugins disable node</bold>
+"#
+);
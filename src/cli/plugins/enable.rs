@@ -0,0 +1,35 @@
+use eyre::Result;
+
+use crate::backend::unalias_backend;
+use crate::plugins::asdf_plugin::AsdfPlugin;
+use crate::plugins::Plugin;
+use crate::ui::style;
+
+/// Enables a plugin disabled with `mise plugins disable`
+///
+/// The plugin is removed from `disable_tools` in ~/.config/mise/config.toml
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct PluginsEnable {
+    /// Plugin(s) to enable
+    #[clap(required = true, verbatim_doc_comment)]
+    plugin: Vec<String>,
+}
+
+impl PluginsEnable {
+    pub fn run(self) -> Result<()> {
+        for name in &self.plugin {
+            let name = unalias_backend(name);
+            AsdfPlugin::new(name.to_string()).enable()?;
+            info!("enabled {}", style::eblue(name));
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise plugins enable node</bold>
+"#
+);
@@ -3,10 +3,15 @@ use rayon::prelude::*;
 use rayon::ThreadPoolBuilder;
 use url::Url;
 
-use crate::backend::asdf::Asdf;
+use crate::backend::asdf::{Asdf, DevRef};
 use crate::backend::{unalias_backend, Backend};
 use crate::config::{Config, Settings};
+use crate::env;
+use crate::file::display_path;
+use crate::plugins::asdf_plugin::AsdfPlugin;
 use crate::plugins::core::CORE_PLUGINS;
+use crate::plugins::Plugin;
+use crate::shorthands::expand_git_host_shorthand;
 use crate::toolset::ToolsetBuilder;
 use crate::ui::multi_progress_report::MultiProgressReport;
 use crate::ui::style;
@@ -23,6 +28,7 @@ pub struct PluginsInstall {
     /// The name of the plugin to install
     /// e.g.: node, ruby
     /// Can specify multiple plugins: `mise plugins install node ruby python`
+    /// A semver constraint installs the highest plugin tag matching it, e.g.: node@^1.2
     #[clap(required_unless_present = "all", verbatim_doc_comment)]
     new_plugin: Option<String>,
 
@@ -35,10 +41,35 @@ pub struct PluginsInstall {
     #[clap(short, long, verbatim_doc_comment)]
     force: bool,
 
+    /// Re-clone an installed plugin, e.g.: if its scripts are broken
+    /// Unlike --force, its installed versions and shims are kept
+    /// and its remove hooks don't run
+    #[clap(long, conflicts_with = "force", verbatim_doc_comment)]
+    refresh: bool,
+
+    /// Check out the ref after @ of an installed plugin in a worktree
+    /// so several refs of the plugin can be used side by side
+    /// e.g.: `mise plugins install node@v1.0.0 --worktree`
+    #[clap(long, conflicts_with_all = ["force", "refresh", "all"], verbatim_doc_comment)]
+    worktree: bool,
+
+    /// Install the plugin from a GitHub pull request to test it before it is merged
+    /// The PR is never trusted so installing it always asks for confirmation
+    /// and `mise plugins update` keeps following it
+    /// e.g.: `mise plugins install node --pr 123`
+    #[clap(long, conflicts_with_all = ["branch", "worktree", "all"], verbatim_doc_comment)]
+    pr: Option<u32>,
+
+    /// Install the plugin from a branch to test it before it is merged
+    /// Like --pr, the branch is never trusted and `mise plugins update` keeps following it
+    /// e.g.: `mise plugins install node --branch wip`
+    #[clap(long, conflicts_with_all = ["worktree", "all"], verbatim_doc_comment)]
+    branch: Option<String>,
+
     /// Install all missing plugins
     /// This will only install plugins that have matching shorthands.
     /// i.e.: they don't need the full git repo url
-    #[clap(short, long, conflicts_with_all = ["new_plugin", "force"], verbatim_doc_comment)]
+    #[clap(short, long, conflicts_with_all = ["new_plugin", "force", "refresh"], verbatim_doc_comment)]
     all: bool,
 
     /// Show installation output
@@ -108,18 +139,65 @@ impl PluginsInstall {
         git_url: Option<String>,
         mpr: &MultiProgressReport,
     ) -> Result<()> {
+        let (name, ref_constraint) = match name.split_once('@') {
+            Some((name, constraint)) => (unalias_backend(name).to_string(), Some(constraint)),
+            None => (name, None),
+        };
+        if self.worktree {
+            let Some(gitref) = ref_constraint else {
+                bail!("{name}: --worktree needs a ref to check out, e.g.: {name}@v1.0.0");
+            };
+            let worktree = AsdfPlugin::new(name.clone()).add_worktree(gitref)?;
+            info!(
+                "checked out {}@{gitref} at {}",
+                style::eblue(&name),
+                display_path(&worktree.repo.dir)
+            );
+            return Ok(());
+        }
+        if ref_constraint.is_some() && git_url.as_ref().is_some_and(|url| url.contains('#')) {
+            bail!("{name}: a plugin can't have both a version constraint and a #ref in its url");
+        }
+        let dev_ref = match (self.pr, &self.branch) {
+            (Some(pr), _) => Some(DevRef::Pr(pr)),
+            (None, Some(branch)) => Some(DevRef::Branch(branch.clone())),
+            (None, None) => None,
+        };
         let mut plugin = Asdf::new(name.clone());
         plugin.repo_url = git_url;
-        if !self.force && plugin.is_installed() {
+        plugin.ref_constraint = ref_constraint.map(String::from);
+        plugin.dev_ref = dev_ref;
+        if self.refresh && plugin.is_installed() {
+            let pr = mpr.add(&format!("plugin:{}", style::eblue(&name)));
+            plugin.refresh_plugin(pr.as_ref())?;
+        } else if !self.force && plugin.is_installed() {
             warn!("Plugin {name} already installed");
-            warn!("Use --force to install anyway");
+            warn!("Use --force to install anyway or --refresh to re-clone it");
         } else {
             plugin.ensure_installed(mpr, self.force)?;
+            show_hints(&name)?;
         }
         Ok(())
     }
 }
 
+/// tells the user about the defaults/requirements the plugin author suggests in mise.plugin.toml
+fn show_hints(name: &str) -> Result<()> {
+    let hints = AsdfPlugin::new(name.to_string()).hints()?;
+    if let Some(version) = hints.default_version {
+        info!(
+            "{} recommends version {version}, use it with `mise use {name}@{version}`",
+            style::eblue(name)
+        );
+    }
+    for var in hints.required_env {
+        if env::var_os(&var).is_none() {
+            warn!("{} requires {var} to be set", style::eblue(name));
+        }
+    }
+    Ok(())
+}
+
 fn get_name_and_url(name: &str, git_url: &Option<String>) -> Result<(String, Option<String>)> {
     let name = unalias_backend(name);
     Ok(match git_url {
@@ -128,7 +206,10 @@ fn get_name_and_url(name: &str, git_url: &Option<String>) -> Result<(String, Opt
             false => (name.to_string(), None),
         },
         None => match name.contains(':') {
-            true => (get_name_from_url(name)?, Some(name.to_string())),
+            true => (
+                get_name_from_url(&expand_git_host_shorthand(name))?,
+                Some(name.to_string()),
+            ),
             false => (name.to_string(), None),
         },
     })
@@ -162,6 +243,24 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 
     # install the node plugin using a specific ref
     $ <bold>mise plugins install node https://github.com/mise-plugins/rtx-nodejs.git#v1.0.0</bold>
+
+    # install the highest tag of the node plugin matching a semver constraint
+    $ <bold>mise plugins install node@^1.2</bold>
+
+    # install a plugin hosted on a gitea/forgejo instance
+    $ <bold>mise plugins install node gitea:codeberg.org/someone/asdf-nodejs</bold>
+
+    # install a plugin published as a release asset instead of a git repo
+    $ <bold>mise plugins install node https://example.com/releases/latest/download/mise-node.tar.gz</bold>
+
+    # re-clone a plugin with broken scripts, keeping its installed versions
+    $ <bold>mise plugins install node --refresh</bold>
+
+    # check out another ref of the installed node plugin alongside it
+    $ <bold>mise plugins install node@v1.0.0 --worktree</bold>
+
+    # test an unmerged pull request of the node plugin
+    $ <bold>mise plugins install node --pr 123 --force</bold>
 "#
 );
 
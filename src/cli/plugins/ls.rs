@@ -40,6 +40,16 @@ pub struct PluginsLs {
     /// e.g.: main 1234abc
     #[clap(long, hide = true, verbatim_doc_comment)]
     pub refs: bool,
+
+    /// Show a stable one-line summary for each plugin
+    /// e.g.: asdf:node type=asdf installed=true ref=main sha=1234abc url=https://github.com/asdf-vm/asdf-nodejs.git
+    #[clap(long, verbatim_doc_comment, conflicts_with_all = ["urls", "refs"])]
+    pub describe: bool,
+
+    /// Show whether each plugin is trusted and why
+    /// e.g.: tiny    trusted    maintained by the mise team
+    #[clap(long, verbatim_doc_comment, conflicts_with_all = ["urls", "refs", "describe"])]
+    pub trust: bool,
 }
 
 impl PluginsLs {
@@ -59,7 +69,26 @@ impl PluginsLs {
             tools.retain(|_, p| matches!(p.get_plugin_type(), PluginType::Asdf));
         }
 
-        if self.urls || self.refs {
+        if self.describe {
+            for line in plugins::describe_all(tools)? {
+                miseprintln!("{line}");
+            }
+        } else if self.trust {
+            let data = tools
+                .into_par_iter()
+                .map(|(name, p)| {
+                    let status = p.trust_status();
+                    TrustRow {
+                        plugin: name,
+                        trust: status.to_string(),
+                        reason: status.reason().to_string(),
+                    }
+                })
+                .collect::<Vec<_>>();
+            let mut table = Table::new(data);
+            table::default_style(&mut table, false);
+            miseprintln!("{table}");
+        } else if self.urls || self.refs {
             let data = tools
                 .into_par_iter()
                 .map(|(name, p)| {
@@ -109,6 +138,14 @@ struct Row {
     sha: String,
 }
 
+#[derive(Tabled)]
+#[tabled(rename_all = "PascalCase")]
+struct TrustRow {
+    plugin: String,
+    trust: String,
+    reason: String,
+}
+
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
 
@@ -150,6 +187,20 @@ mod tests {
         assert_snapshot!(grep(stdout, "zephyr"));
     }
 
+    #[test]
+    fn test_plugin_list_describe() {
+        reset();
+        let stdout = assert_cli!("plugin", "list", "--core", "--describe");
+        assert_snapshot!(grep(stdout, "node"), @"core:node type=core installed=true ref=- sha=- url=-");
+    }
+
+    #[test]
+    fn test_plugin_list_trust() {
+        reset();
+        let stdout = assert_cli!("plugin", "list", "--core", "--trust");
+        assert_snapshot!(grep(stdout, "node"), @"node    trusted core plugin");
+    }
+
     #[test]
     fn test_plugin_refs() {
         reset();
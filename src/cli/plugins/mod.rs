@@ -3,10 +3,13 @@ use eyre::Result;
 
 use crate::config::Config;
 
+mod disable;
+mod enable;
 mod install;
 mod link;
 mod ls;
 mod ls_remote;
+mod search;
 mod uninstall;
 mod update;
 
@@ -47,10 +50,13 @@ pub struct Plugins {
 
 #[derive(Debug, Subcommand)]
 enum Commands {
+    Disable(disable::PluginsDisable),
+    Enable(enable::PluginsEnable),
     Install(install::PluginsInstall),
     Link(link::PluginsLink),
     Ls(ls::PluginsLs),
     LsRemote(ls_remote::PluginsLsRemote),
+    Search(search::PluginsSearch),
     Uninstall(uninstall::PluginsUninstall),
     Update(update::Update),
 }
@@ -58,10 +64,13 @@ enum Commands {
 impl Commands {
     pub fn run(self, config: &Config) -> Result<()> {
         match self {
+            Self::Disable(cmd) => cmd.run(),
+            Self::Enable(cmd) => cmd.run(),
             Self::Install(cmd) => cmd.run(config),
             Self::Link(cmd) => cmd.run(),
             Self::Ls(cmd) => cmd.run(config),
             Self::LsRemote(cmd) => cmd.run(config),
+            Self::Search(cmd) => cmd.run(),
             Self::Uninstall(cmd) => cmd.run(),
             Self::Update(cmd) => cmd.run(),
         }
@@ -76,6 +85,8 @@ impl Plugins {
             core: self.core,
             refs: self.refs,
             urls: self.urls,
+            describe: false,
+            trust: false,
             user: self.user,
         }));
 
@@ -0,0 +1,63 @@
+use console::{measure_text_width, pad_str, Alignment};
+use eyre::Result;
+use itertools::Itertools;
+
+use crate::plugins;
+
+/// Search the tools mise can use without installing a plugin first
+///
+/// Lists core plugins, installed plugins and shorthands along with
+/// where each one comes from.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct PluginsSearch {
+    /// Only show tools whose name contains this
+    query: Option<String>,
+}
+
+impl PluginsSearch {
+    pub fn run(self) -> Result<()> {
+        let tools = plugins::known_tools()?
+            .into_iter()
+            .filter(|(name, _)| match &self.query {
+                Some(query) => name.contains(query.as_str()),
+                None => true,
+            })
+            .collect_vec();
+        let max_name_len = tools
+            .iter()
+            .map(|(name, _)| measure_text_width(name))
+            .max()
+            .unwrap_or(0);
+        for (name, sources) in tools {
+            let name = pad_str(&name, max_name_len, Alignment::Left, None);
+            miseprintln!("{}  {}", name, sources.iter().join(", "));
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise plugins search node</bold>
+    node  core, shorthand
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use crate::test::reset;
+
+    #[test]
+    fn test_plugins_search() {
+        reset();
+        let stdout = assert_cli!("plugins", "search", "tiny");
+        assert_eq!(
+            stdout,
+            "tiny     installed-asdf, shorthand\ntinytex  shorthand"
+        );
+    }
+}
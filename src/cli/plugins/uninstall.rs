@@ -1,9 +1,13 @@
 use eyre::Result;
+use indicatif::HumanBytes;
 
-use crate::backend::unalias_backend;
+use crate::backend::{unalias_backend, ABackend};
+use crate::config::Settings;
 use crate::plugins;
+use crate::plugins::asdf_plugin::AsdfPlugin;
+use crate::plugins::Plugin;
 use crate::ui::multi_progress_report::MultiProgressReport;
-use crate::ui::style;
+use crate::ui::{prompt, style};
 
 /// Removes a plugin
 #[derive(Debug, clap::Args)]
@@ -44,6 +48,15 @@ impl PluginsUninstall {
     fn uninstall_one(&self, plugin_name: &str, mpr: &MultiProgressReport) -> Result<()> {
         match plugins::get(plugin_name) {
             plugin if plugin.is_installed() => {
+                if let Some(warning) = self.uninstall_warning(&plugin)? {
+                    let settings = Settings::try_get()?;
+                    if !settings.yes
+                        && !prompt::confirm(format!("{warning}\nUninstall {plugin_name}?"))?
+                    {
+                        warn!("not uninstalling {}", style::eblue(plugin_name));
+                        return Ok(());
+                    }
+                }
                 let prefix = format!("plugin:{}", style::eblue(&plugin.id()));
                 let pr = mpr.add(&prefix);
                 plugin.uninstall(pr.as_ref())?;
@@ -56,6 +69,33 @@ impl PluginsUninstall {
         }
         Ok(())
     }
+
+    /// describes the installed versions if there are enough of them (or they are big enough)
+    /// that uninstalling the plugin should be confirmed first
+    fn uninstall_warning(&self, plugin: &ABackend) -> Result<Option<String>> {
+        let settings = Settings::try_get()?;
+        let p = AsdfPlugin::new(plugin.id().to_string());
+        let count = p.current_version_count()?;
+        if count == 0 {
+            return Ok(None);
+        }
+        let size = p.size_on_disk()?;
+        if count <= settings.plugin_uninstall_confirm_versions
+            && size <= settings.plugin_uninstall_confirm_size_mb * 1024 * 1024
+        {
+            return Ok(None);
+        }
+        let consequence = match self.purge {
+            true => "will be removed",
+            false => "will no longer be usable",
+        };
+        Ok(Some(format!(
+            "{} has {count} installed versions ({}) that {consequence}: {}",
+            style::eblue(plugin.id()),
+            HumanBytes(size),
+            plugin.list_installed_versions()?.join(", ")
+        )))
+    }
 }
 
 static AFTER_LONG_HELP: &str = color_print::cstr!(
@@ -64,3 +104,53 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>mise uninstall node</bold>
 "#
 );
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use test_log::test;
+
+    use crate::test::reset;
+    use crate::{dirs, file};
+
+    use super::*;
+
+    #[test]
+    fn test_uninstall_warning() {
+        reset();
+        // installed to the kebab-cased path
+        let installs = dirs::INSTALLS.join("uninstall-confirm");
+        for v in ["1.0.0", "1.1.0", "2.0.0"] {
+            file::create_dir_all(installs.join(v)).unwrap();
+            file::write(installs.join(v).join("bin"), "1234").unwrap();
+        }
+        let cmd = PluginsUninstall {
+            plugin: vec![],
+            purge: true,
+            all: false,
+        };
+        let plugin = plugins::get("uninstall_confirm");
+        let below = cmd.uninstall_warning(&plugin).unwrap();
+        env::set_var("MISE_PLUGIN_UNINSTALL_CONFIRM_VERSIONS", "2");
+        Settings::reset(None);
+        let above = cmd.uninstall_warning(&plugin).unwrap();
+        env::set_var("MISE_PLUGIN_UNINSTALL_CONFIRM_VERSIONS", "3");
+        env::set_var("MISE_PLUGIN_UNINSTALL_CONFIRM_SIZE_MB", "0");
+        Settings::reset(None);
+        let above_size = cmd.uninstall_warning(&plugin).unwrap();
+        file::remove_all(&installs).unwrap();
+        let none_installed = cmd.uninstall_warning(&plugin).unwrap();
+        env::remove_var("MISE_PLUGIN_UNINSTALL_CONFIRM_VERSIONS");
+        env::remove_var("MISE_PLUGIN_UNINSTALL_CONFIRM_SIZE_MB");
+        Settings::reset(None);
+
+        assert_eq!(below, None);
+        assert_eq!(
+            above.unwrap(),
+            "uninstall_confirm has 3 installed versions (12 B) that will be removed: 1.0.0, 1.1.0, 2.0.0"
+        );
+        assert!(above_size.is_some());
+        assert_eq!(none_installed, None);
+    }
+}
@@ -16,8 +16,13 @@ pub struct Update {
     #[clap()]
     plugin: Option<Vec<String>>,
 
+    /// Allow updating a plugin to a ref older than its current commit
+    /// without confirming
+    #[clap(long, verbatim_doc_comment)]
+    allow_downgrade: bool,
+
     /// Number of jobs to run in parallel
-    /// Default: 4
+    /// Default: `list_concurrency` if set, otherwise 4
     #[clap(long, short, verbatim_doc_comment)]
     jobs: Option<usize>,
 }
@@ -45,7 +50,11 @@ impl Update {
         let settings = Settings::try_get()?;
         let mpr = MultiProgressReport::get();
         let mut errors = rayon::ThreadPoolBuilder::new()
-            .num_threads(self.jobs.unwrap_or(settings.jobs))
+            .num_threads(
+                self.jobs
+                    .or(settings.list_concurrency)
+                    .unwrap_or(settings.jobs),
+            )
             .build()?
             .install(|| {
                 plugins
@@ -54,7 +63,7 @@ impl Update {
                         let prefix = format!("plugin:{}", style(plugin.id()).blue().for_stderr());
                         let pr = mpr.add(&prefix);
                         plugin
-                            .update(pr.as_ref(), ref_)
+                            .update(pr.as_ref(), ref_, self.allow_downgrade)
                             .map_err(|e| eyre!("[{plugin}] plugin update: {e:?}"))
                     })
                     .filter_map(|r| r.err())
@@ -79,6 +88,7 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>mise plugins update</bold>            # update all plugins
     $ <bold>mise plugins update node</bold>       # update only node
     $ <bold>mise plugins update node#beta</bold>  # specify a ref
+    $ <bold>mise plugins update node#v1.0.0 --allow-downgrade</bold>  # move back to an older ref
 "#
 );
 
@@ -28,7 +28,9 @@ impl SettingsSet {
             "color" => parse_bool(&self.value)?,
             "disable_default_shorthands" => parse_bool(&self.value)?,
             "disable_tools" => self.value.split(',').map(|s| s.to_string()).collect(),
+            "download_rate_limit" => parse_i64(&self.value)?,
             "experimental" => parse_bool(&self.value)?,
+            "git_credential_helper" => self.value.into(),
             "go_default_packages_file" => self.value.into(),
             "go_download_mirror" => self.value.into(),
             "go_repo" => self.value.into(),
@@ -37,12 +39,33 @@ impl SettingsSet {
             "go_set_goroot" => parse_bool(&self.value)?,
             "go_skip_checksum" => parse_bool(&self.value)?,
             "http_timeout" => parse_i64(&self.value)?,
+            "install_log_file" => self.value.into(),
+            "install_symlink_policy" => self.value.into(),
+            "isolated_plugin_env" => parse_bool(&self.value)?,
+            "isolated_plugin_env_allow" => self.value.split(',').map(|s| s.to_string()).collect(),
             "jobs" => parse_i64(&self.value)?,
             "legacy_version_file" => parse_bool(&self.value)?,
+            "list_concurrency" => parse_i64(&self.value)?,
+            "min_free_disk_space" => parse_i64(&self.value)?,
             "node_compile" => parse_bool(&self.value)?,
             "not_found_auto_install" => parse_bool(&self.value)?,
             "paranoid" => parse_bool(&self.value)?,
+            "plugin_auto_gc" => parse_bool(&self.value)?,
             "plugin_autoupdate_last_check_duration" => self.value.into(),
+            "plugin_build_requirements" => parse_bool(&self.value)?,
+            "plugin_clone_cache" => parse_bool(&self.value)?,
+            "plugin_fetch_refspecs" => parse_map(&self.value)?,
+            "plugin_git_lfs" => parse_bool(&self.value)?,
+            "plugin_install_preflight" => parse_bool(&self.value)?,
+            "plugin_pin_policy" => self.value.into(),
+            "plugin_platform_check" => self.value.into(),
+            "plugin_reference_dir" => self.value.into(),
+            "plugin_reference_dissociate" => parse_bool(&self.value)?,
+            "plugin_tag_filters" => parse_map(&self.value)?,
+            "plugin_uninstall_confirm_size_mb" => parse_i64(&self.value)?,
+            "plugin_uninstall_confirm_versions" => parse_i64(&self.value)?,
+            "plugin_update_refuse_dirty" => parse_bool(&self.value)?,
+            "plugin_url_transform" => self.value.into(),
             "python_compile" => parse_bool(&self.value)?,
             "python_venv_auto_create" => parse_bool(&self.value)?,
             "quiet" => parse_bool(&self.value)?,
@@ -52,6 +75,7 @@ impl SettingsSet {
             "status.show_env" => parse_bool(&self.value)?,
             "status.show_tools" => parse_bool(&self.value)?,
             "task_output" => self.value.into(),
+            "total_install_timeout" => self.value.into(),
             "trusted_config_paths" => self.value.split(':').map(|s| s.to_string()).collect(),
             "verbose" => parse_bool(&self.value)?,
             "yes" => parse_bool(&self.value)?,
@@ -99,6 +123,17 @@ fn parse_i64(value: &str) -> Result<toml_edit::Value> {
     }
 }
 
+fn parse_map(value: &str) -> Result<toml_edit::Value> {
+    let mut table = toml_edit::InlineTable::new();
+    for entry in value.split(',').filter(|s| !s.is_empty()) {
+        match entry.split_once('=') {
+            Some((k, v)) => table.insert(k, v.into()),
+            None => return Err(eyre!("{} must be key=value pairs separated by ','", value)),
+        };
+    }
+    Ok(table.into())
+}
+
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
 
@@ -133,6 +168,7 @@ pub mod tests {
         color = true
         disable_default_shorthands = false
         disable_tools = []
+        download_rate_limit = 0
         experimental = true
         go_default_packages_file = "~/.default-go-packages"
         go_download_mirror = "https://dl.google.com/go"
@@ -141,14 +177,29 @@ pub mod tests {
         go_set_goroot = true
         go_skip_checksum = false
         http_timeout = 30
+        install_symlink_policy = "skip"
+        isolated_plugin_env = false
+        isolated_plugin_env_allow = []
         jobs = 2
         legacy_version_file = false
         legacy_version_file_disable_tools = []
         libgit2 = true
+        min_free_disk_space = 100
         node_compile = false
         not_found_auto_install = true
         paranoid = false
+        plugin_auto_gc = false
         plugin_autoupdate_last_check_duration = "1"
+        plugin_build_requirements = true
+        plugin_clone_cache = false
+        plugin_git_lfs = true
+        plugin_install_preflight = true
+        plugin_pin_policy = "branch"
+        plugin_platform_check = "warn"
+        plugin_reference_dissociate = false
+        plugin_uninstall_confirm_size_mb = 1024
+        plugin_uninstall_confirm_versions = 10
+        plugin_update_refuse_dirty = false
         python_default_packages_file = "~/.default-python-packages"
         python_pyenv_repo = "https://github.com/pyenv/pyenv.git"
         quiet = false
@@ -157,6 +208,10 @@ pub mod tests {
         verbose = true
         yes = true
 
+        [plugin_fetch_refspecs]
+
+        [plugin_tag_filters]
+
         [status]
         missing_tools = "never"
         show_env = false
@@ -59,6 +59,7 @@ mod tests {
         color = true
         disable_default_shorthands = false
         disable_tools = []
+        download_rate_limit = 0
         experimental = true
         go_default_packages_file = "~/.default-go-packages"
         go_download_mirror = "https://dl.google.com/go"
@@ -67,14 +68,29 @@ mod tests {
         go_set_goroot = true
         go_skip_checksum = false
         http_timeout = 30
+        install_symlink_policy = "skip"
+        isolated_plugin_env = false
+        isolated_plugin_env_allow = []
         jobs = 4
         legacy_version_file = true
         legacy_version_file_disable_tools = []
         libgit2 = true
+        min_free_disk_space = 100
         node_compile = false
         not_found_auto_install = true
         paranoid = false
+        plugin_auto_gc = false
         plugin_autoupdate_last_check_duration = "20m"
+        plugin_build_requirements = true
+        plugin_clone_cache = false
+        plugin_git_lfs = true
+        plugin_install_preflight = true
+        plugin_pin_policy = "branch"
+        plugin_platform_check = "warn"
+        plugin_reference_dissociate = false
+        plugin_uninstall_confirm_size_mb = 1024
+        plugin_uninstall_confirm_versions = 10
+        plugin_update_refuse_dirty = false
         python_default_packages_file = "~/.default-python-packages"
         python_pyenv_repo = "https://github.com/pyenv/pyenv.git"
         quiet = false
@@ -83,6 +99,10 @@ mod tests {
         verbose = true
         yes = true
 
+        [plugin_fetch_refspecs]
+
+        [plugin_tag_filters]
+
         [status]
         missing_tools = "if_other_versions_installed"
         show_env = false
@@ -99,6 +99,7 @@ impl Upgrade {
             jobs: self.jobs,
             raw: self.raw,
             latest_versions: true,
+            keep_going: false,
         };
         let new_versions = new_versions.into_iter().map(|tv| tv.request).collect();
         ts.install_versions(config, new_versions, &mpr, &opts)?;
@@ -99,6 +99,7 @@ impl Use {
                 jobs: self.jobs,
                 raw: self.raw,
                 latest_versions: false,
+                keep_going: false,
             },
         )?;
 
@@ -1,5 +1,6 @@
 use eyre::{bail, Result};
 
+use crate::backend;
 use crate::cli::args::ToolArg;
 use crate::config::Config;
 use crate::dirs::SHIMS;
@@ -21,6 +22,11 @@ pub struct Which {
     #[clap(long, conflicts_with = "plugin")]
     pub version: bool,
 
+    /// Show the type of backend that handles BIN_NAME as a tool name, e.g.: core for node
+    /// This only looks at what's installed, nothing is installed or fetched
+    #[clap(long, conflicts_with_all = ["plugin", "version", "tool"], verbatim_doc_comment)]
+    pub backend: bool,
+
     /// Use a specific tool@version
     /// e.g.: `mise which npm --tool=node@20`
     #[clap(short, long, value_name = "TOOL@VERSION", verbatim_doc_comment)]
@@ -29,6 +35,10 @@ pub struct Which {
 
 impl Which {
     pub fn run(self) -> Result<()> {
+        if self.backend {
+            miseprintln!("{}", backend::resolve_backend_type(&self.bin_name));
+            return Ok(());
+        }
         let ts = self.get_toolset()?;
 
         match ts.which(&self.bin_name) {
@@ -78,6 +88,8 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     node
     $ <bold>mise which node --version</bold>
     20.0.0
+    $ <bold>mise which node --backend</bold>
+    core
 "#
 );
 
@@ -122,4 +134,12 @@ mod tests {
         assert_cli!("install", "dummy@1.0.1");
         assert_cli_snapshot!("which", "dummy", "--tool=dummy@1.0.1");
     }
+
+    #[test]
+    fn test_which_backend() {
+        reset();
+        assert_eq!(assert_cli!("which", "--backend", "dummy"), "asdf");
+        assert_eq!(assert_cli!("which", "--backend", "node"), "core");
+        assert_eq!(assert_cli!("which", "--backend", "npm:prettier"), "npm");
+    }
 }
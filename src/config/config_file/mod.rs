@@ -6,6 +6,7 @@ use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
 use eyre::eyre;
+use itertools::Itertools;
 use once_cell::sync::Lazy;
 use serde_derive::Deserialize;
 use versions::Versioning;
@@ -20,7 +21,7 @@ use crate::errors::Error::UntrustedConfig;
 use crate::file::display_path;
 use crate::hash::{file_hash_sha256, hash_to_str};
 use crate::task::Task;
-use crate::toolset::{ToolRequestSet, ToolSource, ToolVersionList, Toolset};
+use crate::toolset::{resolve_all, ToolRequestSet, ToolSource, ToolVersionList, Toolset};
 use crate::ui::{prompt, style};
 use crate::{backend, dirs, env, file};
 
@@ -111,19 +112,42 @@ impl dyn ConfigFile {
             ts.versions.insert(fa.clone(), tvl);
         }
         ts.resolve()?;
-        for (fa, versions) in plugins_to_update {
-            let versions = versions
+        let requests = plugins_to_update
+            .values()
+            .flatten()
+            .map(|tvr| (*tvr).clone())
+            .collect_vec();
+        let resolved = match pin {
+            true => resolve_all(requests, false)?
+                .into_iter()
+                .map(|(tr, tv)| {
+                    let version = match tv {
+                        Ok(tv) => tv.version,
+                        // pin what the request resolves to when it can't be resolved concretely
+                        Err(_) => {
+                            tr.resolve(backend::get(tr.backend()).as_ref(), false)?
+                                .version
+                        }
+                    };
+                    Ok((tr, version))
+                })
+                .collect::<eyre::Result<Vec<_>>>()?,
+            false => requests
                 .into_iter()
-                .map(|tvr| {
-                    if pin {
-                        let plugin = backend::get(&fa);
-                        let tv = tvr.resolve(plugin.as_ref(), false)?;
-                        Ok(tv.version)
-                    } else {
-                        Ok(tvr.version())
-                    }
+                .map(|tr| {
+                    let version = tr.version();
+                    (tr, version)
                 })
-                .collect::<eyre::Result<Vec<_>>>()?;
+                .collect(),
+        };
+        let mut versions: HashMap<BackendArg, Vec<String>> = HashMap::new();
+        for (tr, version) in resolved {
+            versions
+                .entry(tr.backend().clone())
+                .or_default()
+                .push(version);
+        }
+        for (fa, versions) in versions {
             self.replace_versions(&fa, &versions)?;
         }
 
@@ -1,4 +1,4 @@
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt::{Debug, Display, Formatter};
 use std::iter::once;
 use std::path::PathBuf;
@@ -7,12 +7,13 @@ use std::sync::{Arc, Mutex, RwLock};
 #[allow(unused_imports)]
 use confique::env::parse::{list_by_colon, list_by_comma};
 use confique::{Config, Partial};
-use eyre::{bail, Result};
+use eyre::Result;
 use once_cell::sync::Lazy;
 use serde::ser::Error;
 use serde_derive::{Deserialize, Serialize};
 
 use crate::config::{system_config_files, DEFAULT_CONFIG_FILENAMES};
+use crate::errors::Error::ExperimentalDisabled;
 use crate::file::FindUp;
 use crate::{config, dirs, env, file};
 
@@ -44,8 +45,15 @@ pub struct Settings {
     pub disable_default_shorthands: bool,
     #[config(env = "MISE_DISABLE_TOOLS", default = [], parse_env = list_by_comma)]
     pub disable_tools: BTreeSet<String>,
+    /// max bytes per second to download tools and plugins at, 0 for unlimited
+    #[config(env = "MISE_DOWNLOAD_RATE_LIMIT", default = 0)]
+    pub download_rate_limit: u64,
     #[config(env = "MISE_EXPERIMENTAL", default = false)]
     pub experimental: bool,
+    /// credential helper used for plugin clones/fetches instead of the ones from the ambient git config
+    /// e.g.: "store", "osxkeychain" or "!f() { echo password=$TOKEN; }; f"
+    #[config(env = "MISE_GIT_CREDENTIAL_HELPER")]
+    pub git_credential_helper: Option<String>,
     /// after installing a go version, run `go install` on packages listed in this file
     #[config(env = "MISE_GO_DEFAULT_PACKAGES_FILE", default = "~/.default-go-packages")]
     pub go_default_packages_file: PathBuf,
@@ -76,6 +84,21 @@ pub struct Settings {
     pub go_skip_checksum: bool,
     #[config(env = "MISE_HTTP_TIMEOUT", default = 30)]
     pub http_timeout: u64,
+    /// also write the output of plugin/tool installs and updates to this file
+    /// the log from the previous run is kept at <path>.1
+    #[config(env = "MISE_INSTALL_LOG_FILE")]
+    pub install_log_file: Option<PathBuf>,
+    /// what to do when installing a version whose install path is a symlink to somewhere else,
+    /// e.g.: one created with `mise link`
+    #[config(env = "MISE_INSTALL_SYMLINK_POLICY", default = "skip")]
+    pub install_symlink_policy: SettingsInstallSymlinkPolicy,
+    /// run plugin scripts (install, hooks, etc.) with only an allowlist of ambient env vars
+    /// plus the ones mise sets for the script, so unrelated tool versions don't leak into builds
+    #[config(env = "MISE_ISOLATED_PLUGIN_ENV", default = false)]
+    pub isolated_plugin_env: bool,
+    /// additional ambient env vars to pass through when isolated_plugin_env is enabled
+    #[config(env = "MISE_ISOLATED_PLUGIN_ENV_ALLOW", default = [], parse_env = list_by_comma)]
+    pub isolated_plugin_env_allow: BTreeSet<String>,
     #[config(env = "MISE_JOBS", default = 4)]
     pub jobs: usize,
     #[config(env = "MISE_LEGACY_VERSION_FILE", default = true)]
@@ -84,14 +107,66 @@ pub struct Settings {
     pub legacy_version_file_disable_tools: BTreeSet<String>,
     #[config(env = "MISE_LIBGIT2", default = true)]
     pub libgit2: bool,
+    /// max number of plugins to walk in parallel when listing/updating plugins
+    /// defaults to the number of cpus (or `jobs` for updates)
+    #[config(env = "MISE_LIST_CONCURRENCY")]
+    pub list_concurrency: Option<usize>,
+    /// MiB of disk space that must be left free when installing plugins and tools, 0 to disable
+    #[config(env = "MISE_MIN_FREE_DISK_SPACE", default = 100)]
+    pub min_free_disk_space: u64,
     #[config(env = "MISE_NODE_COMPILE", default = false)]
     pub node_compile: bool,
     #[config(env = "MISE_NOT_FOUND_AUTO_INSTALL", default = true)]
     pub not_found_auto_install: bool,
     #[config(env = "MISE_PARANOID", default = false)]
     pub paranoid: bool,
+    /// run `git gc` on plugin repos every so many updates or once they have many loose objects
+    #[config(env = "MISE_PLUGIN_AUTO_GC", default = false)]
+    pub plugin_auto_gc: bool,
     #[config(env = "MISE_PLUGIN_AUTOUPDATE_LAST_CHECK_DURATION", default = "7d")]
     pub plugin_autoupdate_last_check_duration: String,
+    /// check the build requirements a plugin declares in its mise.plugin.toml before installing it
+    #[config(env = "MISE_PLUGIN_BUILD_REQUIREMENTS", default = true)]
+    pub plugin_build_requirements: bool,
+    /// reuse clones of plugins previously installed from the same url and ref instead of cloning again
+    #[config(env = "MISE_PLUGIN_CLONE_CACHE", default = false)]
+    pub plugin_clone_cache: bool,
+    /// refspec to fetch when updating a plugin instead of its current branch, by plugin name
+    #[config(env = "MISE_PLUGIN_FETCH_REFSPECS", default = {}, parse_env = map_by_comma)]
+    pub plugin_fetch_refspecs: BTreeMap<String, String>,
+    /// fetch the git-lfs files of plugins that track files with git-lfs after cloning them
+    #[config(env = "MISE_PLUGIN_GIT_LFS", default = true)]
+    pub plugin_git_lfs: bool,
+    /// check that the plugin's git remote is reachable before installing it
+    #[config(env = "MISE_PLUGIN_INSTALL_PREFLIGHT", default = true)]
+    pub plugin_install_preflight: bool,
+    /// what to check out when installing a plugin without a ref
+    #[config(env = "MISE_PLUGIN_PIN_POLICY", default = "branch")]
+    pub plugin_pin_policy: SettingsPluginPinPolicy,
+    /// what to do when installing a plugin whose mise.plugin.toml doesn't list this platform
+    #[config(env = "MISE_PLUGIN_PLATFORM_CHECK", default = "warn")]
+    pub plugin_platform_check: SettingsPluginPlatformCheck,
+    /// directory of local clones of plugins by name (e.g.: <dir>/node) to clone with --reference
+    #[config(env = "MISE_PLUGIN_REFERENCE_DIR")]
+    pub plugin_reference_dir: Option<PathBuf>,
+    /// copy the objects borrowed from plugin_reference_dir so plugins don't depend on it
+    #[config(env = "MISE_PLUGIN_REFERENCE_DISSOCIATE", default = false)]
+    pub plugin_reference_dissociate: bool,
+    /// glob of the tags to fetch when cloning a plugin instead of all of them, by plugin name
+    #[config(env = "MISE_PLUGIN_TAG_FILTERS", default = {}, parse_env = map_by_comma)]
+    pub plugin_tag_filters: BTreeMap<String, String>,
+    /// prompt before uninstalling a plugin whose installed versions use more than this many MiB
+    #[config(env = "MISE_PLUGIN_UNINSTALL_CONFIRM_SIZE_MB", default = 1024)]
+    pub plugin_uninstall_confirm_size_mb: u64,
+    /// prompt before uninstalling a plugin with more than this many installed versions
+    #[config(env = "MISE_PLUGIN_UNINSTALL_CONFIRM_VERSIONS", default = 10)]
+    pub plugin_uninstall_confirm_versions: usize,
+    /// refuse to update plugins with local changes instead of only warning
+    #[config(env = "MISE_PLUGIN_UPDATE_REFUSE_DIRTY", default = false)]
+    pub plugin_update_refuse_dirty: bool,
+    /// command run with a plugin's repo url as $1 that prints the url to use instead
+    #[config(env = "MISE_PLUGIN_URL_TRANSFORM")]
+    pub plugin_url_transform: Option<String>,
     #[config(env = "MISE_PYTHON_COMPILE")]
     pub python_compile: Option<bool>,
     #[config(env = "MISE_PYTHON_DEFAULT_PACKAGES_FILE")]
@@ -115,6 +190,9 @@ pub struct Settings {
     pub status: SettingsStatus,
     #[config(env = "MISE_TASK_OUTPUT")]
     pub task_output: Option<String>,
+    /// wall-clock budget for all the installs of a command, e.g.: "10m"
+    #[config(env = "MISE_TOTAL_INSTALL_TIMEOUT")]
+    pub total_install_timeout: Option<String>,
     #[config(env = "MISE_TRUSTED_CONFIG_PATHS", default = [], parse_env = list_by_colon)]
     pub trusted_config_paths: BTreeSet<PathBuf>,
     #[config(env = "MISE_QUIET", default = false)]
@@ -174,6 +252,75 @@ pub enum SettingsStatusMissingTools {
     Always,
 }
 
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    Default,
+    PartialEq,
+    strum::EnumString,
+    strum::Display,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum SettingsPluginPinPolicy {
+    /// the remote's default branch
+    #[default]
+    Branch,
+    /// the highest tag
+    LatestTag,
+    /// the highest tag that is a stable semver version, e.g.: "v1.2.3" but not "v1.3.0-rc.1"
+    LatestSemverTag,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    Default,
+    PartialEq,
+    strum::EnumString,
+    strum::Display,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum SettingsPluginPlatformCheck {
+    /// install the plugin anyway
+    Off,
+    /// install the plugin with a warning
+    #[default]
+    Warn,
+    /// fail the install
+    Error,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    Default,
+    PartialEq,
+    strum::EnumString,
+    strum::Display,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum SettingsInstallSymlinkPolicy {
+    /// leave the symlink alone and treat the version as installed outside of mise
+    #[default]
+    Skip,
+    /// remove the symlink (but not what it points to) and install the version in its place
+    Replace,
+    /// fail the install
+    Error,
+}
+
 pub type SettingsPartial = <Settings as Config>::Partial;
 
 static SETTINGS: RwLock<Option<Arc<Settings>>> = RwLock::new(None);
@@ -389,7 +536,7 @@ impl Settings {
 
     pub fn ensure_experimental(&self, what: &str) -> Result<()> {
         if !self.experimental {
-            bail!("{what} is experimental. Enable it with `mise settings set experimental true`");
+            Err(ExperimentalDisabled(what.to_string()))?
         }
         Ok(())
     }
@@ -437,6 +584,45 @@ impl Display for Settings {
     }
 }
 
+/// parses "a=1,b=2" env vars into a map
+fn map_by_comma(input: &str) -> std::io::Result<BTreeMap<String, String>> {
+    input
+        .split(',')
+        .filter(|s| !s.is_empty())
+        .map(|s| match s.split_once('=') {
+            Some((k, v)) => Ok((k.to_string(), v.to_string())),
+            None => Err(std::io::Error::other(format!(
+                "expected key=value, got {s}"
+            ))),
+        })
+        .collect()
+}
+
 pub fn ensure_experimental(what: &str) -> Result<()> {
     Settings::get().ensure_experimental(what)
 }
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn test_ensure_experimental() {
+        let settings = Settings {
+            experimental: false,
+            ..Default::default()
+        };
+        let err = settings.ensure_experimental("foo backend").unwrap_err();
+        assert_snapshot!(err, @r###"
+        foo backend is experimental. Enable it with `mise settings set experimental true` or `MISE_EXPERIMENTAL=1`
+        See https://mise.jdx.dev/configuration.html#mise-experimental-1
+        "###);
+        let settings = Settings {
+            experimental: true,
+            ..Default::default()
+        };
+        assert!(settings.ensure_experimental("foo backend").is_ok());
+    }
+}
@@ -2,7 +2,7 @@ use std::path::{Path, PathBuf};
 
 use once_cell::sync::Lazy;
 
-use crate::env;
+use crate::{env, file};
 
 pub static HOME: Lazy<&Path> = Lazy::new(|| &env::HOME);
 pub static CWD: Lazy<Option<PathBuf>> = Lazy::new(|| env::current_dir().ok());
@@ -19,3 +19,26 @@ pub static SHIMS: Lazy<&Path> = Lazy::new(|| &env::MISE_SHIMS_DIR);
 
 pub static TRACKED_CONFIGS: Lazy<PathBuf> = Lazy::new(|| STATE.join("tracked-configs"));
 pub static TRUSTED_CONFIGS: Lazy<PathBuf> = Lazy::new(|| STATE.join("trusted-configs"));
+
+/// .mise/plugins in the current directory or the nearest parent that has one, plugins there take
+/// precedence over the ones in PLUGINS and plugins installed from inside the project land there
+pub fn project_plugins() -> Option<PathBuf> {
+    let cwd = env::current_dir().ok()?;
+    file::find_up(&cwd, &[".mise/plugins"]).filter(|p| p.is_dir())
+}
+
+/// the project-local plugins dir (if any) followed by PLUGINS
+pub fn plugins_dirs() -> Vec<PathBuf> {
+    project_plugins()
+        .into_iter()
+        .chain([PLUGINS.to_path_buf()])
+        .collect()
+}
+
+/// where the plugin `name` is installed or would be installed, see [`project_plugins`]
+pub fn plugin_path(name: &str) -> PathBuf {
+    match project_plugins() {
+        Some(dir) if dir.join(name).exists() || !PLUGINS.join(name).exists() => dir.join(name),
+        _ => PLUGINS.join(name),
+    }
+}
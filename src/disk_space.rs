@@ -0,0 +1,147 @@
+//! checks that there is enough free disk space to install plugins and tools. Running out of space
+//! mid-clone or mid-download otherwise fails with confusing partial-write errors.
+
+use std::io;
+use std::path::Path;
+
+use color_eyre::Section;
+use eyre::{eyre, Report, Result};
+
+use crate::config::Settings;
+use crate::errors::Error::InsufficientDiskSpace;
+use crate::file::display_path;
+
+/// bytes available on the filesystem `path` is on, or would be on if it doesn't exist yet
+pub fn free_space(path: &Path) -> Result<u64> {
+    let existing = path
+        .ancestors()
+        .find(|p| p.exists())
+        .unwrap_or(Path::new("/"));
+    // POSIX output, e.g.: "Filesystem 1024-blocks Used Available Capacity Mounted on\n..."
+    let out = cmd!("df", "-Pk", existing).read()?;
+    let kb = out
+        .lines()
+        .nth(1)
+        .and_then(|l| l.split_whitespace().nth(3))
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .ok_or_else(|| eyre!("unexpected df output: {out}"))?;
+    Ok(kb * 1024)
+}
+
+/// fails with InsufficientDiskSpace unless `required` bytes plus `min_free_disk_space` are free
+/// where `path` is. Passes if the free space can't be read (e.g.: no df) so it never blocks an
+/// install on its own
+pub fn ensure_free_space(path: &Path, required: u64) -> Result<()> {
+    let min_free = Settings::get().min_free_disk_space * 1024 * 1024;
+    if min_free == 0 {
+        return Ok(());
+    }
+    let available = match free_space(path) {
+        Ok(available) => available,
+        Err(err) => {
+            debug!(
+                "failed to read free space of {}: {err:#}",
+                display_path(path)
+            );
+            return Ok(());
+        }
+    };
+    if available < min_free + required {
+        return Err(InsufficientDiskSpace {
+            path: display_path(path),
+            available: to_mib(available),
+            required: to_mib(min_free + required),
+        })
+        .suggestion("free up some disk space or lower min_free_disk_space");
+    }
+    Ok(())
+}
+
+/// ENOSPC, the same on linux and macos
+const ENOSPC: i32 = 28;
+
+/// `err` replaced with an InsufficientDiskSpace error if it was caused by the disk `path` is on
+/// filling up. Any other error is kept, with a note if free space is low since that may still
+/// be why it failed
+pub fn explain_failure(path: &Path, err: Report) -> Report {
+    if !is_out_of_space(&err) {
+        return match ensure_free_space(path, 0) {
+            Ok(()) => err,
+            Err(disk_err) => err.note(disk_err.to_string()),
+        };
+    }
+    debug!("failed with a full disk: {err:#}");
+    let min_free = Settings::get().min_free_disk_space * 1024 * 1024;
+    let available = free_space(path).unwrap_or_default();
+    Report::from(InsufficientDiskSpace {
+        path: display_path(path),
+        available: to_mib(available),
+        required: to_mib(min_free),
+    })
+    .suggestion("free up some disk space")
+}
+
+/// true if an io error in the chain of `err` is from a write to a full disk
+fn is_out_of_space(err: &Report) -> bool {
+    err.chain()
+        .filter_map(|e| e.downcast_ref::<io::Error>())
+        .any(|e| e.raw_os_error() == Some(ENOSPC))
+}
+
+fn to_mib(bytes: u64) -> u64 {
+    bytes / 1024 / 1024
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use test_log::test;
+
+    use crate::errors::{Error, ErrorKind};
+    use crate::test::reset;
+    use crate::{dirs, env};
+
+    use super::*;
+
+    #[test]
+    fn test_ensure_free_space() {
+        reset();
+        let missing = dirs::DATA.join("does/not/exist");
+        let available = free_space(&missing).unwrap();
+        let passed = ensure_free_space(&missing, 0);
+        let too_large = ensure_free_space(&missing, available * 2).unwrap_err();
+        env::set_var("MISE_MIN_FREE_DISK_SPACE", "0");
+        Settings::reset(None);
+        let disabled = ensure_free_space(&missing, available * 2);
+        env::remove_var("MISE_MIN_FREE_DISK_SPACE");
+        Settings::reset(None);
+
+        assert!(available > 0);
+        assert!(passed.is_ok());
+        assert_eq!(Error::kind(&too_large), Some(ErrorKind::DiskSpace));
+        assert!(too_large
+            .to_string()
+            .starts_with("insufficient disk space on "));
+        assert!(disabled.is_ok());
+    }
+
+    #[test]
+    fn test_explain_failure() {
+        reset();
+        let path: &Path = &dirs::DATA;
+        let available = free_space(path).unwrap() / 1024 / 1024;
+        let full = io::Error::from_raw_os_error(ENOSPC);
+        let full = explain_failure(path, Report::new(full).wrap_err("failed to write"));
+        let network = explain_failure(path, eyre!("connection reset"));
+        env::set_var("MISE_MIN_FREE_DISK_SPACE", (available + 1).to_string());
+        Settings::reset(None);
+        let low = explain_failure(path, eyre!("connection reset"));
+        env::remove_var("MISE_MIN_FREE_DISK_SPACE");
+        Settings::reset(None);
+
+        assert_eq!(Error::kind(&full), Some(ErrorKind::DiskSpace));
+        assert_eq!(network.to_string(), "connection reset");
+        assert_eq!(Error::kind(&low), None);
+        assert_eq!(low.to_string(), "connection reset");
+    }
+}
@@ -57,6 +57,17 @@ impl EnvDiff {
         diff
     }
 
+    /// unlike `new`, keys missing from `new` are removals rather than left unchanged
+    pub fn between(old: &HashMap<String, String>, new: &HashMap<String, String>) -> EnvDiff {
+        let mut diff = Self::new(old, new.clone());
+        for (key, val) in old {
+            if !new.contains_key(key) {
+                diff.old.insert(key.clone(), val.clone());
+            }
+        }
+        diff
+    }
+
     pub fn from_bash_script<T, U, V>(script: &Path, env: T) -> Result<Self>
     where
         T: IntoIterator<Item = (U, V)>,
@@ -262,6 +273,23 @@ mod tests {
         assert_debug_snapshot!(diff.to_patches());
     }
 
+    #[test]
+    fn test_between() {
+        reset();
+        let diff = EnvDiff::between(&new_to_hashmap(), &new_from_hashmap());
+        assert_debug_snapshot!(diff, @r###"
+        EnvDiff {
+            old: [
+                "b=3",
+                "c=4",
+            ],
+            new: [
+                "b=2",
+            ],
+        }
+        "###);
+    }
+
     #[test]
     fn test_reverse() {
         reset();
@@ -20,6 +20,47 @@ pub enum Error {
     ScriptFailed(String, Option<ExitStatus>),
     #[error("Config file is not trusted.\nTrust it with `mise trust`.")]
     UntrustedConfig(),
+    #[error("Paranoid mode is enabled, refusing to install community-developed plugin")]
+    PluginNotTrusted(String),
+    #[error("Checksum mismatch for file {path}:\nExpected: {expected}\nActual:   {actual}")]
+    ChecksumMismatch {
+        path: String,
+        expected: String,
+        actual: String,
+    },
+    #[error(
+        "plugin {0} returned no versions, it may be misconfigured or have no releases upstream"
+    )]
+    NoRemoteVersions(String),
+    #[error("[{0}] install cancelled")]
+    Cancelled(String),
+    #[error("insufficient disk space on {path}: {available}MiB available, {required}MiB required")]
+    InsufficientDiskSpace {
+        path: String,
+        available: u64,
+        required: u64,
+    },
+    /// a git command talking to a remote failed, e.g.: clone, fetch or ls-remote
+    #[error("{0}")]
+    GitRemoteFailed(String),
+    #[error("[{0}] not started, total_install_timeout exceeded")]
+    InstallTimeoutExceeded(String),
+    #[error("{0} is experimental. Enable it with `mise settings set experimental true` or `MISE_EXPERIMENTAL=1`\n\
+             See https://mise.jdx.dev/configuration.html#mise-experimental-1")]
+    ExperimentalDisabled(String),
+}
+
+/// machine-readable cause of a plugin/backend failure for callers that need to branch on it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Network,
+    TrustRefused,
+    NotInstalled,
+    Checksum,
+    ExperimentalDisabled,
+    Timeout,
+    Cancelled,
+    DiskSpace,
 }
 
 fn render_exit_status(exit_status: &Option<ExitStatus>) -> String {
@@ -30,6 +71,30 @@ fn render_exit_status(exit_status: &Option<ExitStatus>) -> String {
 }
 
 impl Error {
+    /// finds the first typed cause in the chain of `err`
+    pub fn kind(err: &Report) -> Option<ErrorKind> {
+        err.chain().find_map(|e| {
+            if let Some(e) = e.downcast_ref::<Error>() {
+                return match e {
+                    Error::PluginNotInstalled(_) => Some(ErrorKind::NotInstalled),
+                    Error::PluginNotTrusted(_) => Some(ErrorKind::TrustRefused),
+                    Error::ChecksumMismatch { .. } => Some(ErrorKind::Checksum),
+                    Error::ExperimentalDisabled(_) => Some(ErrorKind::ExperimentalDisabled),
+                    Error::InstallTimeoutExceeded(_) => Some(ErrorKind::Timeout),
+                    Error::Cancelled(_) => Some(ErrorKind::Cancelled),
+                    Error::InsufficientDiskSpace { .. } => Some(ErrorKind::DiskSpace),
+                    Error::GitRemoteFailed(_) => Some(ErrorKind::Network),
+                    _ => None,
+                };
+            }
+            e.downcast_ref::<reqwest::Error>()
+                .map(|e| match e.is_timeout() {
+                    true => ErrorKind::Timeout,
+                    false => ErrorKind::Network,
+                })
+        })
+    }
+
     pub fn is_argument_err(err: &Report) -> bool {
         err.downcast_ref::<Error>()
             .map(|e| {
@@ -44,3 +109,76 @@ impl Error {
             .unwrap_or(false)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use eyre::eyre;
+
+    use crate::backend::asdf::Asdf;
+    use crate::backend::Backend;
+    use crate::config::Settings;
+    use crate::git::Git;
+    use crate::http::HTTP;
+    use crate::test::{reset, serve_responses, Response};
+    use crate::ui::multi_progress_report::MultiProgressReport;
+    use crate::{env, file, hash, test};
+
+    use super::*;
+
+    #[test]
+    fn test_kind() {
+        reset();
+        let plugin = Asdf::new("kind-not-installed".into());
+        let err = plugin.is_installed_err().unwrap_err();
+        assert_eq!(Error::kind(&err), Some(ErrorKind::NotInstalled));
+        assert_eq!(
+            err.to_string(),
+            "asdf plugin kind-not-installed is not installed"
+        );
+
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        file::write(tmp.path(), "foo").unwrap();
+        let err = hash::ensure_checksum_sha256(tmp.path(), "1234", None).unwrap_err();
+        assert_eq!(Error::kind(&err), Some(ErrorKind::Checksum));
+
+        let err = Settings::default().ensure_experimental("foo").unwrap_err();
+        assert_eq!(Error::kind(&err), Some(ErrorKind::ExperimentalDisabled));
+
+        let err = HTTP.get_text(test::serve(vec![], 0)).unwrap_err();
+        assert_eq!(Error::kind(&err), Some(ErrorKind::Network));
+        let missing = tempfile::tempdir().unwrap().path().join("missing");
+        let err = Git::ls_remote(&missing.to_string_lossy()).unwrap_err();
+        assert_eq!(Error::kind(&err), Some(ErrorKind::Network));
+        let err = Git::new(missing).clone("/does/not/exist").unwrap_err();
+        assert_eq!(Error::kind(&err), Some(ErrorKind::Network));
+
+        assert_eq!(Error::kind(&eyre!("something else")), None);
+    }
+
+    #[test]
+    fn test_kind_trust_refused() {
+        reset();
+        env::set_var("MISE_PARANOID", "1");
+        env::set_var("MISE_YES", "0");
+        Settings::reset(None);
+        let plugin = Asdf::new("shfmt".into());
+        let err = plugin
+            .ensure_installed(&MultiProgressReport::get(), false)
+            .unwrap_err();
+        env::remove_var("MISE_PARANOID");
+        env::set_var("MISE_YES", "1");
+        Settings::reset(None);
+        assert_eq!(Error::kind(&err), Some(ErrorKind::TrustRefused));
+    }
+
+    #[test]
+    fn test_kind_timeout() {
+        reset();
+        let (url, _) = serve_responses(vec![Response::hang()]);
+        let err = crate::http::Client::new(std::time::Duration::from_millis(100))
+            .unwrap()
+            .get_text(url)
+            .unwrap_err();
+        assert_eq!(Error::kind(&err), Some(ErrorKind::Timeout));
+    }
+}
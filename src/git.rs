@@ -1,13 +1,16 @@
 use std::fmt::Debug;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use color_eyre::Section;
 use duct::Expression;
-use eyre::{eyre, Result, WrapErr};
+use eyre::{eyre, Report, Result, WrapErr};
+use globset::Glob;
 use once_cell::sync::OnceCell;
 use xx::file;
 
 use crate::cmd;
 use crate::config::Settings;
+use crate::errors::Error::GitRemoteFailed;
 use crate::file::touch_dir;
 
 pub struct Git {
@@ -15,6 +18,19 @@ pub struct Git {
     pub repo: OnceCell<git2::Repository>,
 }
 
+/// local changes in a working tree, paths are relative to the repo root
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GitStatus {
+    pub modified: Vec<String>,
+    pub untracked: Vec<String>,
+}
+
+impl GitStatus {
+    pub fn is_dirty(&self) -> bool {
+        !self.modified.is_empty() || !self.untracked.is_empty()
+    }
+}
+
 macro_rules! git_cmd {
     ( $dir:expr $(, $arg:expr )* $(,)? ) => {
         {
@@ -57,45 +73,229 @@ impl Git {
     }
 
     pub fn is_repo(&self) -> bool {
-        self.dir.join(".git").is_dir()
+        // .git is a file in a worktree
+        self.dir.join(".git").exists()
+    }
+
+    /// the git dir of the working tree, `.git` unless it is a worktree whose `.git` file points
+    /// to its own git dir inside the main repo's .git/worktrees
+    pub fn git_dir(&self) -> PathBuf {
+        let dot_git = self.dir.join(".git");
+        if !dot_git.is_file() {
+            return dot_git;
+        }
+        match file::read_to_string(&dot_git) {
+            Ok(content) => match content.trim().strip_prefix("gitdir: ") {
+                Some(dir) => self.dir.join(dir),
+                None => dot_git,
+            },
+            Err(_) => dot_git,
+        }
+    }
+
+    /// the files git updates when the commit HEAD resolves to changes: HEAD itself, the branch it
+    /// points to (which a commit or fast-forward updates without touching HEAD) and packed-refs.
+    /// The branch and packed-refs are left out if they don't exist
+    pub fn head_files(&self) -> Vec<PathBuf> {
+        let git_dir = self.git_dir();
+        // refs are shared by all worktrees so they live in the main repo's git dir
+        let common_dir = match file::read_to_string(git_dir.join("commondir")) {
+            Ok(dir) => git_dir.join(dir.trim()),
+            Err(_) => git_dir.clone(),
+        };
+        let head = git_dir.join("HEAD");
+        let refs = file::read_to_string(&head)
+            .ok()
+            .and_then(|h| h.trim().strip_prefix("ref: ").map(|r| common_dir.join(r)))
+            .into_iter()
+            .chain([common_dir.join("packed-refs")])
+            .filter(|p| p.exists());
+        [head].into_iter().chain(refs).collect()
+    }
+
+    /// checks out `gitref` at `path` as a worktree sharing this repo's objects, fetching it from
+    /// origin first if it isn't known locally (e.g.: a tag missing from a shallow clone)
+    pub fn add_worktree(&self, path: &Path, gitref: &str) -> Result<()> {
+        let commit = format!("{gitref}^{{commit}}");
+        let known = git_cmd!(&self.dir, "rev-parse", "--verify", "--quiet", &commit)
+            .stdout_null()
+            .stderr_null()
+            .unchecked()
+            .run()
+            .is_ok_and(|res| res.status.success());
+        if !known {
+            exec(git_cmd!(&self.dir, "fetch", "--quiet", "origin", gitref))?;
+        }
+        let target = if known { gitref } else { "FETCH_HEAD" };
+        debug!(
+            "adding worktree of {} at {}",
+            self.dir.display(),
+            path.display()
+        );
+        file::mkdirp(path.parent().unwrap())?;
+        exec(git_cmd!(
+            &self.dir, "worktree", "add", "--quiet", "--detach", path, target
+        ))
+    }
+
+    /// the worktrees of the repo, not including its main working tree
+    pub fn worktrees(&self) -> Result<Vec<PathBuf>> {
+        let out = git_cmd_read!(&self.dir, "worktree", "list", "--porcelain")?;
+        Ok(out
+            .lines()
+            .filter_map(|l| l.strip_prefix("worktree "))
+            .skip(1)
+            .map(PathBuf::from)
+            .collect())
+    }
+
+    /// whether `.gitattributes` at the root of the working tree tracks any files with git-lfs
+    pub fn uses_lfs(&self) -> bool {
+        file::read_to_string(self.dir.join(".gitattributes"))
+            .map(|attrs| attrs.lines().any(|l| l.contains("filter=lfs")))
+            .unwrap_or(false)
+    }
+
+    /// whether the git-lfs extension is installed
+    pub fn lfs_available() -> bool {
+        cmd!("git", "lfs", "version")
+            .stdout_null()
+            .stderr_null()
+            .unchecked()
+            .run()
+            .is_ok_and(|res| res.status.success())
+    }
+
+    /// how many objects are stored loose rather than in a pack, these pile up over fetches
+    /// until the repo is gc'd
+    pub fn loose_objects(&self) -> Result<u32> {
+        // e.g.: "12 objects, 48 kilobytes"
+        let out = git_cmd_read!(&self.dir, "count-objects")?;
+        let count = out.split_whitespace().next().unwrap_or_default();
+        count
+            .parse()
+            .wrap_err_with(|| format!("unexpected git count-objects output: {out}"))
+    }
+
+    /// packs loose objects and removes unreachable ones. git only prunes objects older than
+    /// gc.pruneExpire and won't gc a repo another git process is gc'ing, so it is safe while
+    /// other processes read the repo
+    pub fn gc(&self) -> Result<()> {
+        debug!("gc {}", self.dir.display());
+        exec(git_cmd!(&self.dir, "gc", "--quiet"))
+    }
+
+    /// replaces the git-lfs pointer files of the working tree with their contents
+    pub fn lfs_pull(&self) -> Result<()> {
+        debug!("pulling git-lfs files of {}", self.dir.display());
+        exec(cmd::cmd("git", self.lfs_pull_args()))
+    }
+
+    pub fn lfs_pull_args(&self) -> Vec<String> {
+        let mut args = self.dir_args();
+        args.extend(["lfs", "pull"].map(String::from));
+        args
     }
 
     pub fn update(&self, gitref: Option<String>) -> Result<(String, String)> {
+        let gitref = self.fetch(gitref)?;
+        self.checkout(&gitref)
+    }
+
+    /// fetches `gitref` (the current branch if None) from origin without checking it out,
+    /// returns the ref to check out
+    pub fn fetch(&self, gitref: Option<String>) -> Result<String> {
         let gitref = gitref.map_or_else(|| self.current_branch(), Ok)?;
         debug!("updating {} to {}", self.dir.display(), gitref);
-        let exec = |cmd: Expression| match cmd.stderr_to_stdout().stdout_capture().unchecked().run()
-        {
-            Ok(res) => {
-                if res.status.success() {
-                    Ok(())
-                } else {
-                    Err(eyre!(
-                        "git failed: {cmd:?} {}",
-                        String::from_utf8(res.stdout).unwrap()
-                    ))
+        let shallow_clone = self.is_shallow();
+        let is_branch = shallow_clone && self.is_remote_branch(&gitref);
+        let mut plan = self
+            .fetch_plan(&gitref, shallow_clone, is_branch)
+            .into_iter()
+            .peekable();
+        while let Some(args) = plan.next() {
+            match exec(cmd::cmd("git", args)) {
+                Ok(()) => break,
+                Err(err) if plan.peek().is_some() => {
+                    debug!("shallow fetch of {gitref} failed: {err:#}")
                 }
+                Err(err) => return Err(remote_err(err)),
             }
-            Err(err) => Err(eyre!("git failed: {cmd:?} {err:#}")),
+        }
+        Ok(gitref)
+    }
+
+    /// arguments of the fetches `fetch` runs for `gitref`, each only if the one before it
+    /// failed. A shallow clone can fetch a tag or sha on its own instead of the whole history
+    /// behind it, branches are left to the regular fetch since those are updated in place
+    pub fn fetch_plan(
+        &self,
+        gitref: &str,
+        shallow_clone: bool,
+        is_branch: bool,
+    ) -> Vec<Vec<String>> {
+        let mut plan = vec![];
+        if shallow_clone && !is_branch {
+            plan.push(self.shallow_fetch_args(gitref));
+        }
+        plan.push(self.fetch_refspec_args(&format!("{}:{}", gitref, gitref)));
+        plan
+    }
+
+    /// fetches `refspec` from origin without checking it out, returns its destination ref to
+    /// check out, or what was fetched if it doesn't have one
+    pub fn fetch_refspec(&self, refspec: &str) -> Result<String> {
+        let target = parse_refspec(refspec)?;
+        debug!("updating {} with {}", self.dir.display(), refspec);
+        exec(self.fetch_refspec_cmd(refspec))
+            .map_err(remote_err)
+            .wrap_err_with(|| format!("failed to fetch {refspec}"))?;
+        Ok(target)
+    }
+
+    /// true if `gitref` is an older commit in the history of `sha`, so checking it out would
+    /// move the repo backwards from `sha`
+    pub fn is_behind(&self, gitref: &str, sha: &str) -> Result<bool> {
+        let commit = format!("{gitref}^{{commit}}");
+        let Ok(target) = git_cmd_read!(
+            &self.dir,
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            commit.as_str()
+        ) else {
+            return Ok(false);
         };
-        exec(git_cmd!(
+        if target == sha {
+            return Ok(false);
+        }
+        let res = git_cmd!(&self.dir, "merge-base", "--is-ancestor", &target, sha)
+            .stdout_null()
+            .stderr_null()
+            .unchecked()
+            .run()?;
+        Ok(res.status.success())
+    }
+
+    /// subjects of the commits in `from..to`, newest first and at most `limit` of them, along with
+    /// how many commits there are in total
+    pub fn log_subjects(&self, from: &str, to: &str, limit: usize) -> Result<(Vec<String>, usize)> {
+        let range = format!("{from}..{to}");
+        let max_count = format!("--max-count={limit}");
+        let total = git_cmd_read!(&self.dir, "rev-list", "--count", range.as_str())?.parse()?;
+        let subjects = git_cmd_read!(
             &self.dir,
-            "fetch",
-            "--prune",
-            "--update-head-ok",
-            "origin",
-            &format!("{}:{}", gitref, gitref),
-        ))?;
+            "log",
+            "--format=%s",
+            max_count.as_str(),
+            range.as_str()
+        )?;
+        Ok((subjects.lines().map(String::from).collect(), total))
+    }
+
+    pub fn checkout(&self, gitref: &str) -> Result<(String, String)> {
         let prev_rev = self.current_sha()?;
-        exec(git_cmd!(
-            &self.dir,
-            "-c",
-            "advice.detachedHead=false",
-            "-c",
-            "advice.objectNameWarning=false",
-            "checkout",
-            "--force",
-            &gitref
-        ))?;
+        exec(cmd::cmd("git", self.checkout_args(gitref)))?;
         let post_rev = self.current_sha()?;
         touch_dir(&self.dir)?;
 
@@ -107,10 +307,12 @@ impl Git {
         if let Some(parent) = self.dir.parent() {
             file::mkdirp(parent)?;
         }
-        if let Err(err) = git2::Repository::clone(url, &self.dir) {
-            warn!("git clone failed: {err:#}");
-        } else {
-            return Ok(());
+        if !Self::clones_with_cli() {
+            if let Err(err) = git2::Repository::clone(url, &self.dir) {
+                warn!("git clone failed: {err:#}");
+            } else {
+                return Ok(());
+            }
         }
         match get_git_version() {
             Ok(version) => trace!("git version: {}", version),
@@ -119,10 +321,230 @@ impl Git {
                 err
             ),
         }
-        cmd!("git", "clone", "-q", "--depth", "1", url, &self.dir).run()?;
+        self.clone_cmd(url).run().map_err(remote_err)?;
+        Ok(())
+    }
+
+    /// clones another local clone (hard-linking objects where possible) and points origin
+    /// at `remote_url` so it ends up the same as if it was cloned from `remote_url`
+    pub fn clone_local(&self, from: &Path, remote_url: &str) -> Result<()> {
+        debug!("cloning {} to {}", from.display(), self.dir.display());
+        if let Some(parent) = self.dir.parent() {
+            file::mkdirp(parent)?;
+        }
+        // git warns that --local is ignored for shallow clones, which is fine
+        cmd::cmd("git", self.clone_local_args(from))
+            .stderr_capture()
+            .run()
+            .wrap_err_with(|| format!("failed to clone {}", from.display()))?;
+        self.set_remote_url(remote_url)
+    }
+
+    /// clones `url` borrowing objects from the local clone `reference` so only what is missing
+    /// from it is downloaded. With `dissociate` the borrowed objects are copied in afterwards
+    pub fn clone_with_reference(
+        &self,
+        url: &str,
+        reference: &Path,
+        dissociate: bool,
+    ) -> Result<()> {
+        debug!(
+            "cloning {} to {} with reference {}",
+            url,
+            self.dir.display(),
+            reference.display()
+        );
+        if let Some(parent) = self.dir.parent() {
+            file::mkdirp(parent)?;
+        }
+        self.clone_reference_cmd(url, reference, dissociate)
+            .stderr_capture()
+            .run()
+            .map_err(remote_err)
+            .wrap_err_with(|| format!("failed to clone {url}"))?;
         Ok(())
     }
 
+    /// clones `url` without tags and then fetches only the tags matching `tag_glob`, much
+    /// faster for repos with thousands of tags when only a few of them are ever used
+    pub fn clone_with_tag_filter(&self, url: &str, tag_glob: &str) -> Result<()> {
+        debug!(
+            "cloning {} to {} with tags matching {}",
+            url,
+            self.dir.display(),
+            tag_glob
+        );
+        let refs = filter_tag_refs(Self::available_refs(url)?, tag_glob)?;
+        if let Some(parent) = self.dir.parent() {
+            file::mkdirp(parent)?;
+        }
+        self.clone_no_tags_cmd(url)
+            .stderr_capture()
+            .run()
+            .map_err(remote_err)
+            .wrap_err_with(|| format!("failed to clone {url}"))?;
+        let tags = refs
+            .iter()
+            .filter(|r| r.starts_with("refs/tags/"))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !tags.is_empty() {
+            exec(self.fetch_tags_cmd(&tags))
+                .map_err(remote_err)
+                .wrap_err_with(|| format!("failed to fetch tags matching {tag_glob}"))?;
+        }
+        Ok(())
+    }
+
+    fn clone_no_tags_cmd(&self, url: &str) -> Expression {
+        cmd::cmd("git", self.clone_no_tags_args(url))
+    }
+
+    pub fn clone_no_tags_args(&self, url: &str) -> Vec<String> {
+        let mut args = credential_helper_args();
+        args.extend(["clone", "-q", "--no-tags", url].map(String::from));
+        args.push(self.dir.to_string_lossy().to_string());
+        args
+    }
+
+    fn fetch_tags_cmd(&self, tags: &[String]) -> Expression {
+        cmd::cmd("git", self.fetch_tags_args(tags))
+    }
+
+    pub fn fetch_tags_args(&self, tags: &[String]) -> Vec<String> {
+        let mut args = self.dir_args();
+        args.extend(credential_helper_args());
+        args.extend(["fetch", "--no-tags", "origin"].map(String::from));
+        args.extend(tags.iter().map(|t| format!("+{t}:{t}")));
+        args
+    }
+
+    fn clone_cmd(&self, url: &str) -> Expression {
+        cmd::cmd("git", self.clone_args(url))
+    }
+
+    /// libgit2 does not consult git credential helpers so the cli is required to honor one,
+    /// otherwise `clone` uses libgit2 and only falls back to the cli if it fails
+    pub fn clones_with_cli() -> bool {
+        Settings::get().git_credential_helper.is_some()
+    }
+
+    /// arguments of the `git clone` that `clone` runs along with whether it makes a shallow
+    /// clone. A libgit2 clone is listed as the equivalent full clone with the cli
+    pub fn clone_plan(&self, url: &str) -> (Vec<String>, bool) {
+        if Self::clones_with_cli() {
+            return (self.clone_args(url), true);
+        }
+        let mut args = ["clone", "-q", url].map(String::from).to_vec();
+        args.push(self.dir.to_string_lossy().to_string());
+        (args, false)
+    }
+
+    /// arguments of `git clone` when cloning with the cli, libgit2 is used instead when no
+    /// `git_credential_helper` is set and falls back to this if it fails
+    pub fn clone_args(&self, url: &str) -> Vec<String> {
+        let mut args = credential_helper_args();
+        args.extend(["clone", "-q", "--depth", "1", url].map(String::from));
+        args.push(self.dir.to_string_lossy().to_string());
+        args
+    }
+
+    pub fn clone_local_args(&self, from: &Path) -> Vec<String> {
+        let mut args = ["clone", "-q", "--local"].map(String::from).to_vec();
+        args.push(from.to_string_lossy().to_string());
+        args.push(self.dir.to_string_lossy().to_string());
+        args
+    }
+
+    pub fn checkout_args(&self, gitref: &str) -> Vec<String> {
+        let mut args = self.dir_args();
+        args.extend(
+            [
+                "-c",
+                "advice.detachedHead=false",
+                "-c",
+                "advice.objectNameWarning=false",
+                "checkout",
+                "--force",
+                gitref,
+            ]
+            .map(String::from),
+        );
+        args
+    }
+
+    fn clone_reference_cmd(&self, url: &str, reference: &Path, dissociate: bool) -> Expression {
+        cmd::cmd("git", self.clone_reference_args(url, reference, dissociate))
+    }
+
+    pub fn clone_reference_args(
+        &self,
+        url: &str,
+        reference: &Path,
+        dissociate: bool,
+    ) -> Vec<String> {
+        let mut args = credential_helper_args();
+        args.extend(["clone", "-q", "--reference"].map(String::from));
+        args.push(reference.to_string_lossy().to_string());
+        if dissociate {
+            args.push("--dissociate".to_string());
+        }
+        args.push(url.to_string());
+        args.push(self.dir.to_string_lossy().to_string());
+        args
+    }
+
+    fn fetch_cmd(&self, gitref: &str) -> Expression {
+        self.fetch_refspec_cmd(&format!("{}:{}", gitref, gitref))
+    }
+
+    fn fetch_refspec_cmd(&self, refspec: &str) -> Expression {
+        cmd::cmd("git", self.fetch_refspec_args(refspec))
+    }
+
+    /// arguments of a regular (not shallow) fetch of `refspec` from origin
+    pub fn fetch_refspec_args(&self, refspec: &str) -> Vec<String> {
+        let mut args = self.dir_args();
+        args.extend(credential_helper_args());
+        args.extend(["fetch", "--prune", "--update-head-ok", "origin", refspec].map(String::from));
+        args
+    }
+
+    /// runs git in this repo even if it is owned by another user, like `git_cmd!`
+    fn dir_args(&self) -> Vec<String> {
+        vec![
+            "-C".to_string(),
+            self.dir.to_string_lossy().to_string(),
+            "-c".to_string(),
+            format!("safe.directory={}", self.dir.display()),
+        ]
+    }
+
+    fn is_shallow(&self) -> bool {
+        self.dir.join(".git/shallow").exists()
+    }
+
+    fn is_remote_branch(&self, gitref: &str) -> bool {
+        let remote_branch = format!("refs/remotes/origin/{gitref}");
+        git_cmd!(&self.dir, "rev-parse", "--verify", "--quiet", remote_branch)
+            .stdout_null()
+            .unchecked()
+            .run()
+            .is_ok_and(|res| res.status.success())
+    }
+
+    fn shallow_fetch_args(&self, gitref: &str) -> Vec<String> {
+        let refspec = if regex!(r"^[0-9a-f]{40}$").is_match(gitref) {
+            gitref.to_string()
+        } else {
+            format!("+refs/tags/{gitref}:refs/tags/{gitref}")
+        };
+        let mut args = self.dir_args();
+        args.extend(credential_helper_args());
+        args.extend(["fetch", "--depth", "1", "--no-tags", "origin", &refspec].map(String::from));
+        args
+    }
+
     pub fn current_branch(&self) -> Result<String> {
         let dir = &self.dir;
         if let Ok(repo) = self.repo() {
@@ -175,6 +597,35 @@ impl Git {
         Ok(aref)
     }
 
+    pub fn status(&self) -> Result<GitStatus> {
+        let dir = &self.dir;
+        let mut status = GitStatus::default();
+        if let Ok(repo) = self.repo() {
+            let mut opts = git2::StatusOptions::new();
+            opts.include_untracked(true).recurse_untracked_dirs(true);
+            for entry in repo.statuses(Some(&mut opts))?.iter() {
+                let path = entry.path().unwrap_or_default().to_string();
+                match entry.status() {
+                    s if s.is_wt_new() => status.untracked.push(path),
+                    s if s.is_ignored() => {}
+                    _ => status.modified.push(path),
+                }
+            }
+            debug!("git status for {dir:?}: {status:?}");
+            return Ok(status);
+        }
+        let out = git_cmd_read!(&self.dir, "status", "--porcelain", "--untracked-files=all")?;
+        for line in out.lines().filter(|l| l.len() > 3) {
+            let (code, path) = line.split_at(3);
+            match code.trim() {
+                "??" => status.untracked.push(path.to_string()),
+                _ => status.modified.push(path.to_string()),
+            }
+        }
+        debug!("git status for {}: {status:?}", self.dir.display());
+        Ok(status)
+    }
+
     pub fn get_remote_url(&self) -> Option<String> {
         let dir = &self.dir;
         if !self.exists() {
@@ -199,6 +650,11 @@ impl Git {
         }
     }
 
+    pub fn set_remote_url(&self, url: &str) -> Result<()> {
+        git_cmd!(&self.dir, "remote", "set-url", "origin", url).run()?;
+        Ok(())
+    }
+
     pub fn split_url_and_ref(url: &str) -> (String, Option<String>) {
         match url.split_once('#') {
             Some((url, _ref)) => (url.to_string(), Some(_ref.to_string())),
@@ -206,8 +662,54 @@ impl Git {
         }
     }
 
+    /// fails if `url` can't be reached, without prompting for credentials
+    pub fn ls_remote(url: &str) -> Result<()> {
+        let mut args = credential_helper_args();
+        args.extend(["ls-remote", "--exit-code", url, "HEAD"].map(String::from));
+        let output = cmd::cmd("git", args)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .stdout_null()
+            .stderr_capture()
+            .unchecked()
+            .run()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(remote_err(eyre!(
+                "git ls-remote {url} failed: {}",
+                stderr.trim()
+            )));
+        }
+        Ok(())
+    }
+
+    /// refs of the remote at `url` (e.g.: "refs/heads/main", "refs/tags/v1.0.0") in version order
+    pub fn available_refs(url: &str) -> Result<Vec<String>> {
+        let mut args = credential_helper_args();
+        args.extend(
+            [
+                "ls-remote",
+                "--heads",
+                "--tags",
+                "--refs",
+                "--sort=version:refname",
+                url,
+            ]
+            .map(String::from),
+        );
+        let output = cmd::cmd("git", args)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .read()
+            .map_err(remote_err)
+            .wrap_err_with(|| format!("failed to list refs of {url}"))?;
+        Ok(output
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(_sha, r)| r.to_string())
+            .collect())
+    }
+
     pub fn exists(&self) -> bool {
-        self.dir.join(".git").is_dir()
+        self.is_repo()
     }
 
     pub fn get_root() -> eyre::Result<PathBuf> {
@@ -218,6 +720,82 @@ impl Git {
     }
 }
 
+/// replaces the credential helpers from the ambient git config with the one set in
+/// `git_credential_helper`, if any. Otherwise the user's own helpers are left alone.
+fn credential_helper_args() -> Vec<String> {
+    match &Settings::get().git_credential_helper {
+        Some(helper) => vec![
+            "-c".into(),
+            // an empty value clears any helpers configured in gitconfig
+            "credential.helper=".into(),
+            "-c".into(),
+            format!("credential.helper={helper}"),
+        ],
+        None => vec![],
+    }
+}
+
+fn exec(cmd: Expression) -> Result<()> {
+    match cmd.stderr_to_stdout().stdout_capture().unchecked().run() {
+        Ok(res) => {
+            if res.status.success() {
+                Ok(())
+            } else {
+                Err(eyre!(
+                    "git failed: {cmd:?} {}",
+                    String::from_utf8(res.stdout).unwrap()
+                ))
+            }
+        }
+        Err(err) => Err(eyre!("git failed: {cmd:?} {err:#}")),
+    }
+}
+
+/// `err` from a git command talking to a remote, typed so it is classified as a network error
+fn remote_err(err: impl Into<Report>) -> Report {
+    Report::new(GitRemoteFailed(format!("{:#}", err.into())))
+}
+
+/// keeps the branches of `refs` (as returned by `Git::available_refs`) and the tags matching
+/// `tag_glob`
+pub fn filter_tag_refs(refs: Vec<String>, tag_glob: &str) -> Result<Vec<String>> {
+    let glob = Glob::new(tag_glob)
+        .wrap_err_with(|| format!("invalid tag glob: {tag_glob}"))?
+        .compile_matcher();
+    Ok(refs
+        .into_iter()
+        .filter(|r| match r.strip_prefix("refs/tags/") {
+            Some(tag) => glob.is_match(tag),
+            None => true,
+        })
+        .collect())
+}
+
+/// validates a `[+]<src>[:<dst>]` refspec for a single ref and returns the ref to check out after
+/// fetching it
+pub fn parse_refspec(refspec: &str) -> Result<String> {
+    let spec = refspec.strip_prefix('+').unwrap_or(refspec);
+    let (src, dst) = spec.split_once(':').unwrap_or((spec, ""));
+    let is_valid_ref = |r: &str| {
+        !r.contains('*')
+            && cmd!("git", "check-ref-format", "--allow-onelevel", r)
+                .stdout_null()
+                .stderr_null()
+                .unchecked()
+                .run()
+                .is_ok_and(|res| res.status.success())
+    };
+    if src.is_empty() || !is_valid_ref(src) || !(dst.is_empty() || is_valid_ref(dst)) {
+        return Err(eyre!("invalid fetch refspec: {refspec}")).suggestion(
+            "expected [+]<src>[:<dst>] for a single ref, e.g.: +refs/heads/feature-x:refs/remotes/origin/feature-x",
+        );
+    }
+    Ok(match dst {
+        "" => "FETCH_HEAD".to_string(),
+        dst => dst.to_string(),
+    })
+}
+
 fn get_git_version() -> Result<String> {
     let version = cmd!("git", "--version").read()?;
     Ok(version.trim().into())
@@ -250,3 +828,172 @@ impl Debug for Git {
 //         assert_eq!(update_result, (prev_rev, latest));
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Settings;
+    use crate::test;
+    use crate::test::reset;
+
+    use super::*;
+
+    #[test]
+    fn test_credential_helper() {
+        reset();
+        let git = Git::new(PathBuf::from("/tmp/plugin"));
+        let clone = format!("{:?}", git.clone_cmd("https://example.com/repo.git"));
+        assert!(!clone.contains("credential.helper"), "{clone}");
+
+        std::env::set_var("MISE_GIT_CREDENTIAL_HELPER", "store");
+        Settings::reset(None);
+        let clone = format!("{:?}", git.clone_cmd("https://example.com/repo.git"));
+        let fetch = format!("{:?}", git.fetch_cmd("main"));
+        let fetch_refspec = format!("{:?}", git.fetch_refspec_cmd("+refs/heads/x:refs/heads/x"));
+        std::env::remove_var("MISE_GIT_CREDENTIAL_HELPER");
+        Settings::reset(None);
+        assert!(
+            clone.contains(
+                r#""-c", "credential.helper=", "-c", "credential.helper=store", "clone""#
+            ),
+            "{clone}"
+        );
+        assert!(
+            fetch.contains(
+                r#""-c", "credential.helper=", "-c", "credential.helper=store", "fetch""#
+            ),
+            "{fetch}"
+        );
+        assert!(fetch.ends_with(r#""origin", "main:main"])"#), "{fetch}");
+        assert!(
+            fetch_refspec.ends_with(r#""origin", "+refs/heads/x:refs/heads/x"])"#),
+            "{fetch_refspec}"
+        );
+    }
+
+    #[test]
+    fn test_clone_reference_cmd() {
+        reset();
+        let git = Git::new(PathBuf::from("/tmp/plugin"));
+        let url = "https://example.com/repo.git";
+        let reference = Path::new("/tmp/reference");
+        let clone = format!("{:?}", git.clone_reference_cmd(url, reference, false));
+        assert!(
+            clone.ends_with(r#""clone", "-q", "--reference", "/tmp/reference", "https://example.com/repo.git", "/tmp/plugin"])"#),
+            "{clone}"
+        );
+        let clone = format!("{:?}", git.clone_reference_cmd(url, reference, true));
+        assert!(
+            clone.contains(r#""--reference", "/tmp/reference", "--dissociate""#),
+            "{clone}"
+        );
+    }
+
+    #[test]
+    fn test_tag_filter() {
+        reset();
+        let refs = [
+            "refs/heads/main",
+            "refs/tags/v1.0.0",
+            "refs/tags/v1.1.0",
+            "refs/tags/v2.0.0",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(
+            filter_tag_refs(refs, "v*.0.0").unwrap(),
+            vec!["refs/heads/main", "refs/tags/v1.0.0", "refs/tags/v2.0.0"]
+        );
+        assert!(filter_tag_refs(vec![], "v[").is_err());
+
+        let git = Git::new(PathBuf::from("/tmp/plugin"));
+        let fetch = format!("{:?}", git.fetch_tags_cmd(&["refs/tags/v1.0.0".into()]));
+        assert!(
+            fetch.ends_with(
+                r#""fetch", "--no-tags", "origin", "+refs/tags/v1.0.0:refs/tags/v1.0.0"])"#
+            ),
+            "{fetch}"
+        );
+    }
+
+    #[test]
+    fn test_status() {
+        reset();
+        let dir = tempfile::tempdir().unwrap();
+        let git = Git::new(dir.path().to_path_buf());
+        file::write(dir.path().join("install"), "echo installing").unwrap();
+        test::git_init(dir.path(), "init");
+        assert!(!git.status().unwrap().is_dirty());
+
+        file::write(dir.path().join("install"), "echo hacked").unwrap();
+        file::write(dir.path().join("bin/new"), "").unwrap();
+        let status = git.status().unwrap();
+        assert!(status.is_dirty());
+        assert_eq!(status.modified, vec!["install"]);
+        assert_eq!(status.untracked, vec!["bin/new"]);
+    }
+
+    #[test]
+    fn test_update_shallow_tag() {
+        reset();
+        let origin = tempfile::tempdir().unwrap();
+        test::git_init(origin.path(), "init");
+        for version in ["1.0.0", "2.0.0", "3.0.0"] {
+            file::write(origin.path().join("VERSION"), version).unwrap();
+            test::git_commit(origin.path(), version);
+            let tag = format!("v{version}");
+            cmd!("git", "-C", origin.path(), "tag", tag).run().unwrap();
+        }
+        let rev_parse =
+            |dir: &Path, rev: &str| cmd!("git", "-C", dir, "rev-parse", rev).read().unwrap();
+        let tmpdir = tempfile::tempdir().unwrap();
+        let dir = tmpdir.path().join("plugin");
+        let url = format!("file://{}", origin.path().display());
+        cmd!("git", "clone", "-q", "--depth", "1", url, &dir)
+            .run()
+            .unwrap();
+        let git = Git::new(dir.clone());
+        let commit_count = || {
+            cmd!("git", "-C", &dir, "rev-list", "--count", "--all")
+                .read()
+                .unwrap()
+        };
+
+        let (_, tag_rev) = git.update(Some("v2.0.0".into())).unwrap();
+        assert_eq!(tag_rev, rev_parse(origin.path(), "v2.0.0^{commit}"));
+        assert!(tag_rev.starts_with(&git.current_sha_short().unwrap()));
+        assert_eq!(file::read_to_string(dir.join("VERSION")).unwrap(), "2.0.0");
+        // only the tagged commit was fetched, not the history behind it or the other tags
+        assert_eq!(commit_count(), "2");
+        let tags = cmd!("git", "-C", &dir, "tag").read().unwrap();
+        assert_eq!(tags, "v2.0.0\nv3.0.0");
+
+        let sha = rev_parse(origin.path(), "v1.0.0^{commit}");
+        let (_, sha_rev) = git.update(Some(sha.clone())).unwrap();
+        assert_eq!(sha_rev, sha);
+        assert_eq!(file::read_to_string(dir.join("VERSION")).unwrap(), "1.0.0");
+        assert_eq!(commit_count(), "3");
+    }
+
+    #[test]
+    fn test_parse_refspec() {
+        let parse = |refspec| parse_refspec(refspec).map_err(|e| e.to_string());
+        assert_eq!(
+            parse("+refs/heads/feature-x:refs/remotes/upstream/feature-x").unwrap(),
+            "refs/remotes/upstream/feature-x"
+        );
+        assert_eq!(parse("main").unwrap(), "FETCH_HEAD");
+        assert_eq!(parse("main:").unwrap(), "FETCH_HEAD");
+        for invalid in [
+            "",
+            "+",
+            ":main",
+            "refs/heads/*:refs/remotes/origin/*",
+            "a..b:c",
+        ] {
+            assert_eq!(
+                parse(invalid).unwrap_err(),
+                format!("invalid fetch refspec: {invalid}")
+            );
+        }
+    }
+}
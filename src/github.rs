@@ -1,5 +1,7 @@
 use serde_derive::Deserialize;
 
+static API_URL: &str = "https://api.github.com";
+
 #[derive(Debug, Deserialize)]
 pub struct GithubRelease {
     pub tag_name: String,
@@ -10,7 +12,81 @@ pub struct GithubRelease {
     pub published_at: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct GithubRepo {
+    pub archived: bool,
+}
+
 pub fn list_releases(repo: &str) -> eyre::Result<Vec<GithubRelease>> {
-    let url = format!("https://api.github.com/repos/{}/releases", repo);
+    let url = format!("{API_URL}/repos/{}/releases", repo);
+    crate::http::HTTP_FETCH.json(url)
+}
+
+pub fn get_repo(repo: &str) -> eyre::Result<GithubRepo> {
+    get_repo_from(API_URL, repo)
+}
+
+fn get_repo_from(api_url: &str, repo: &str) -> eyre::Result<GithubRepo> {
+    let url = format!("{api_url}/repos/{repo}");
     crate::http::HTTP_FETCH.json(url)
 }
+
+/// "owner/name" of a github.com git url, e.g.: "https://github.com/mise-plugins/rtx-tiny.git"
+/// or "git@github.com:mise-plugins/rtx-tiny.git"
+pub fn repo_from_url(url: &str) -> Option<String> {
+    let path = url
+        .strip_prefix("https://github.com/")
+        .or_else(|| url.strip_prefix("http://github.com/"))
+        .or_else(|| url.strip_prefix("git@github.com:"))
+        .or_else(|| url.strip_prefix("ssh://git@github.com/"))?;
+    let path = path.split('#').next().unwrap();
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    match path.split('/').collect::<Vec<_>>()[..] {
+        [owner, name] if !owner.is_empty() && !name.is_empty() => Some(format!("{owner}/{name}")),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use test_log::test;
+
+    use super::*;
+    use crate::test::{serve_responses, Response};
+
+    #[test]
+    fn test_repo_from_url() {
+        for url in [
+            "https://github.com/mise-plugins/rtx-tiny.git",
+            "https://github.com/mise-plugins/rtx-tiny",
+            "https://github.com/mise-plugins/rtx-tiny/",
+            "https://github.com/mise-plugins/rtx-tiny.git#v1.0.0",
+            "git@github.com:mise-plugins/rtx-tiny.git",
+            "ssh://git@github.com/mise-plugins/rtx-tiny.git",
+        ] {
+            assert_eq!(
+                repo_from_url(url).as_deref(),
+                Some("mise-plugins/rtx-tiny"),
+                "{url}"
+            );
+        }
+        assert_eq!(repo_from_url("https://gitlab.com/someone/plugin.git"), None);
+        assert_eq!(repo_from_url("https://github.com/someone"), None);
+    }
+
+    #[test]
+    fn test_get_repo() {
+        let body = r#"{"full_name":"someone/old-plugin","archived":true}"#;
+        let (api_url, server) = serve_responses(vec![
+            Response::ok(body).with_header("content-type", "application/json")
+        ]);
+        let repo = get_repo_from(&api_url, "someone/old-plugin").unwrap();
+        let requests = server.join().unwrap();
+        assert!(repo.archived);
+        assert!(
+            requests[0].starts_with("GET /repos/someone/old-plugin "),
+            "{requests:?}"
+        );
+    }
+}
@@ -5,10 +5,11 @@ use std::hash::{Hash, Hasher};
 use std::io::{Read, Write};
 use std::path::Path;
 
-use eyre::{ensure, Result};
+use eyre::Result;
 use rayon::prelude::*;
 use sha2::{Digest, Sha256};
 
+use crate::errors::Error::ChecksumMismatch;
 use crate::file::display_path;
 use crate::ui::progress_report::SingleReport;
 
@@ -45,17 +46,37 @@ pub fn file_hash_sha256_prog(path: &Path, pr: Option<&dyn SingleReport>) -> Resu
     Ok(format!("{hash:x}"))
 }
 
+/// sha256 of every file under `dir` along with its path relative to `dir`, in a stable order
+/// so renaming or modifying any file changes the hash
+pub fn dir_hash_sha256(dir: &Path) -> Result<String> {
+    let mut files = crate::file::recursive_ls(dir)?;
+    files.sort();
+    let mut hasher = Sha256::new();
+    for path in files {
+        let rel = path.strip_prefix(dir)?.to_string_lossy().to_string();
+        // streamed since downloads can be too large to read into memory
+        let mut file = File::open(&path)?;
+        hasher.update((rel.len() as u64).to_le_bytes());
+        hasher.update(rel.as_bytes());
+        hasher.update(file.metadata()?.len().to_le_bytes());
+        std::io::copy(&mut file, &mut hasher)?;
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
 pub fn ensure_checksum_sha256(
     path: &Path,
     checksum: &str,
     pr: Option<&dyn SingleReport>,
 ) -> Result<()> {
     let actual = file_hash_sha256_prog(path, pr)?;
-    ensure!(
-        actual == checksum,
-        "Checksum mismatch for file {}:\nExpected: {checksum}\nActual:   {actual}",
-        display_path(path),
-    );
+    if actual != checksum {
+        Err(ChecksumMismatch {
+            path: display_path(path),
+            expected: checksum.to_string(),
+            actual,
+        })?
+    }
     Ok(())
 }
 
@@ -1,20 +1,23 @@
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Utc};
 use eyre::{bail, Report, Result};
 use once_cell::sync::Lazy;
-use reqwest::{ClientBuilder, IntoUrl, Response};
+use reqwest::header::RETRY_AFTER;
+use reqwest::{ClientBuilder, IntoUrl, Response, StatusCode};
 use tokio::runtime::Runtime;
 use url::Url;
 
 use crate::cli::version;
 use crate::config::Settings;
 use crate::env::MISE_FETCH_REMOTE_VERSIONS_TIMEOUT;
+use crate::errors::{Error, ErrorKind};
 use crate::file::display_path;
 use crate::ui::progress_report::SingleReport;
-use crate::{env, file};
+use crate::{disk_space, env, file};
 
 #[cfg(not(test))]
 pub static HTTP_VERSION_CHECK: Lazy<Client> =
@@ -26,13 +29,21 @@ pub static HTTP: Lazy<Client> =
 pub static HTTP_FETCH: Lazy<Client> =
     Lazy::new(|| Client::new(*MISE_FETCH_REMOTE_VERSIONS_TIMEOUT).unwrap());
 
+/// how many times a request is retried after being rate limited
+const RATE_LIMIT_RETRIES: usize = 3;
+/// longest we'll wait on a Retry-After header, some servers ask for an hour
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+/// how many times a download is fetched again after its checksum didn't match, kept low so an
+/// upstream serving a bad file fails instead of downloading it over and over
+const CHECKSUM_MISMATCH_RETRIES: usize = 1;
+
 #[derive(Debug)]
 pub struct Client {
     reqwest: reqwest::Client,
 }
 
 impl Client {
-    fn new(timeout: Duration) -> Result<Self> {
+    pub(crate) fn new(timeout: Duration) -> Result<Self> {
         Ok(Self {
             reqwest: Self::_new()
                 .read_timeout(timeout)
@@ -47,19 +58,36 @@ impl Client {
             .gzip(true)
     }
 
-    async fn get<U: IntoUrl>(&self, url: U) -> Result<Response> {
+    async fn get<U: IntoUrl>(&self, url: U, pr: Option<&dyn SingleReport>) -> Result<Response> {
         let get = |url: Url| async move {
-            debug!("GET {}", &url);
-            let mut req = self.reqwest.get(url.clone());
-            if url.host_str() == Some("api.github.com") {
-                if let Some(token) = &*env::GITHUB_API_TOKEN {
-                    req = req.header("authorization", format!("token {}", token));
+            let mut retries = 0;
+            loop {
+                debug!("GET {}", &url);
+                let mut req = self.reqwest.get(url.clone());
+                if url.host_str() == Some("api.github.com") {
+                    if let Some(token) = &*env::GITHUB_API_TOKEN {
+                        req = req.header("authorization", format!("token {}", token));
+                    }
+                }
+                let resp = req.send().await?;
+                debug!("GET {url} {}", resp.status());
+                if let Some(wait) = retry_after(&resp).filter(|_| retries < RATE_LIMIT_RETRIES) {
+                    retries += 1;
+                    let msg = format!(
+                        "rate limited by {}, retrying in {}s",
+                        url.host_str().unwrap_or_default(),
+                        wait.as_secs_f64().ceil()
+                    );
+                    match pr {
+                        Some(pr) => pr.set_message(msg),
+                        None => warn!("{msg}"),
+                    }
+                    tokio::time::sleep(wait).await;
+                    continue;
                 }
+                resp.error_for_status_ref()?;
+                return Ok(resp);
             }
-            let resp = req.send().await?;
-            debug!("GET {url} {}", resp.status());
-            resp.error_for_status_ref()?;
-            Ok(resp)
         };
         let mut url = url.into_url().unwrap();
         let resp = match get(url.clone()).await {
@@ -80,7 +108,7 @@ impl Client {
         let mut url = url.into_url().unwrap();
         let rt = self.runtime()?;
         let text = rt.block_on(async {
-            let resp = self.get(url.clone()).await?;
+            let resp = self.get(url.clone(), None).await?;
             Ok::<String, eyre::Error>(resp.text().await?)
         })?;
         if text.starts_with("<!DOCTYPE html>") {
@@ -101,12 +129,23 @@ impl Client {
         let url = url.into_url().unwrap();
         let rt = self.runtime()?;
         let json = rt.block_on(async {
-            let resp = self.get(url).await?;
+            let resp = self.get(url, None).await?;
             Ok::<T, eyre::Error>(resp.json().await?)
         })?;
         Ok(json)
     }
 
+    /// requests `url` and returns the url the response ended up at after following redirects
+    pub fn final_url<U: IntoUrl>(&self, url: U) -> Result<Url> {
+        let url = url.into_url()?;
+        let rt = self.runtime()?;
+        let url = rt.block_on(async {
+            let resp = self.get(url, None).await?;
+            Ok::<Url, eyre::Error>(resp.url().clone())
+        })?;
+        Ok(url)
+    }
+
     pub fn download_file<U: IntoUrl>(
         &self,
         url: U,
@@ -118,17 +157,23 @@ impl Client {
 
         let rt = self.runtime()?;
         rt.block_on(async {
-            let mut resp = self.get(url).await?;
+            let mut resp = self.get(url, pr).await?;
             if let Some(length) = resp.content_length() {
                 if let Some(pr) = pr {
                     pr.set_length(length);
                 }
+                disk_space::ensure_free_space(path, length)?;
             }
 
             file::create_dir_all(path.parent().unwrap())?;
-            let mut file = File::create(path)?;
+            let rate_limit = Settings::get().download_rate_limit;
+            let mut file = ThrottledWriter::new(File::create(path)?, rate_limit);
             while let Some(chunk) = resp.chunk().await? {
-                file.write_all(&chunk)?;
+                if let Err(err) = file.write_all(&chunk) {
+                    drop(file);
+                    let _ = file::remove_file(path);
+                    return Err(disk_space::explain_failure(path, err.into()));
+                }
                 if let Some(pr) = pr {
                     pr.inc(chunk.len() as u64);
                 }
@@ -138,6 +183,35 @@ impl Client {
         Ok(())
     }
 
+    /// checks the file downloaded from `url` to `path` with `verify`. If its checksum doesn't match
+    /// (e.g.: it was corrupted in transit) it is deleted and downloaded again, up to
+    /// CHECKSUM_MISMATCH_RETRIES times. A file that still doesn't match is deleted as well so the
+    /// next install doesn't reuse it
+    pub fn verify_download<U: IntoUrl>(
+        &self,
+        url: U,
+        path: &Path,
+        pr: Option<&dyn SingleReport>,
+        verify: impl Fn(&Path) -> Result<()>,
+    ) -> Result<()> {
+        let url = url.into_url()?;
+        let mut retries = 0;
+        loop {
+            match verify(path) {
+                Err(err) if Error::kind(&err) == Some(ErrorKind::Checksum) => {
+                    file::remove_file(path)?;
+                    if retries == CHECKSUM_MISMATCH_RETRIES {
+                        return Err(err);
+                    }
+                    retries += 1;
+                    warn!("{err}\ndownloading {url} again");
+                    self.download_file(url.clone(), path, pr)?;
+                }
+                res => return res,
+            }
+        }
+    }
+
     fn runtime(&self) -> Result<Runtime, Report> {
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_time()
@@ -147,6 +221,69 @@ impl Client {
     }
 }
 
+/// how long a 429 response asked to wait with its Retry-After header before trying again
+fn retry_after(resp: &Response) -> Option<Duration> {
+    if resp.status() != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+    let value = resp.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    parse_retry_after(value, Utc::now())
+}
+
+/// Retry-After is either a number of seconds or an http date, capped at MAX_RETRY_AFTER
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    let wait = match value.parse::<u64>() {
+        Ok(secs) => Duration::from_secs(secs),
+        Err(_) => {
+            let date = DateTime::parse_from_rfc2822(value).ok()?;
+            (date.with_timezone(&Utc) - now)
+                .to_std()
+                .unwrap_or_default()
+        }
+    };
+    Some(wait.min(MAX_RETRY_AFTER))
+}
+
+/// sleeps after each write as needed to keep the average throughput under `rate` bytes/sec.
+/// Blocking the download loop like this also stops reading from the socket so the sender
+/// backs off too. A rate of 0 is unlimited.
+pub struct ThrottledWriter<W: Write> {
+    inner: W,
+    rate: u64,
+    written: u64,
+    start: Instant,
+}
+
+impl<W: Write> ThrottledWriter<W> {
+    pub fn new(inner: W, rate: u64) -> Self {
+        Self {
+            inner,
+            rate,
+            written: 0,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl<W: Write> Write for ThrottledWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        if self.rate > 0 {
+            self.written += n as u64;
+            let expected = Duration::from_secs_f64(self.written as f64 / self.rate as f64);
+            if let Some(ahead) = expected.checked_sub(self.start.elapsed()) {
+                std::thread::sleep(ahead);
+            }
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 pub fn error_code(e: &Report) -> Option<u16> {
     if e.to_string().contains("404") {
         // TODO: not this when I can figure out how to use eyre properly
@@ -158,3 +295,90 @@ pub fn error_code(e: &Report) -> Option<u16> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::hash;
+    use crate::test::{serve_responses, Response};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(parse_retry_after("5", now), Some(Duration::from_secs(5)));
+        assert_eq!(parse_retry_after("3600", now), Some(MAX_RETRY_AFTER));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let (url, server) = serve_responses(vec![
+            Response::new("429 Too Many Requests").with_header("retry-after", 1),
+            Response::ok("ok"),
+        ]);
+        let client = Client::new(Duration::from_secs(5)).unwrap();
+        let start = Instant::now();
+        assert_eq!(client.get_text(&url).unwrap(), "ok");
+        assert!(start.elapsed() >= Duration::from_secs(1));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_verify_download() {
+        let serve = |bodies: [&str; 2]| serve_responses(bodies.map(Response::ok).into());
+        let client = Client::new(Duration::from_secs(5)).unwrap();
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("tool.tar.gz");
+        let checksum = "dc51b8c96c2d745df3bd5590d990230a482fd247123599548e0632fdbf97fc22";
+        let verify = |path: &Path| hash::ensure_checksum_sha256(path, checksum, None);
+
+        // the first download is corrupt, the retry gets the right file
+        let (url, server) = serve(["corrupt", "ok\n"]);
+        client.download_file(&url, &path, None).unwrap();
+        client.verify_download(&url, &path, None, verify).unwrap();
+        server.join().unwrap();
+        assert_eq!(file::read_to_string(&path).unwrap(), "ok\n");
+
+        // only retried once when upstream keeps serving a bad file, which is not kept
+        let (url, server) = serve(["corrupt", "still corrupt"]);
+        client.download_file(&url, &path, None).unwrap();
+        let err = client
+            .verify_download(&url, &path, None, verify)
+            .unwrap_err();
+        server.join().unwrap();
+        assert_eq!(Error::kind(&err), Some(ErrorKind::Checksum));
+        assert!(err.to_string().contains(&format!("Expected: {checksum}")));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_throttled_writer() {
+        let rate = 200_000;
+        let mut writer = ThrottledWriter::new(Vec::new(), rate);
+        let start = Instant::now();
+        for _ in 0..100 {
+            writer.write_all(&[0; 1000]).unwrap();
+        }
+        let elapsed = start.elapsed();
+        assert_eq!(writer.inner.len(), 100_000);
+        // 100KB at 200KB/s
+        assert!(elapsed >= Duration::from_millis(500), "{elapsed:?}");
+        assert!(100_000.0 / elapsed.as_secs_f64() <= rate as f64);
+
+        let mut unlimited = ThrottledWriter::new(Vec::new(), 0);
+        let start = Instant::now();
+        unlimited.write_all(&[0; 100_000]).unwrap();
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+}
@@ -26,6 +26,7 @@ mod config;
 mod default_shorthands;
 mod direnv;
 mod dirs;
+mod disk_space;
 pub mod duration;
 mod env;
 mod env_diff;
@@ -53,6 +54,7 @@ mod shorthands;
 mod task;
 pub mod tera;
 pub mod timeout;
+mod timings;
 mod toml;
 mod toolset;
 mod ui;
@@ -1,8 +1,25 @@
+use crate::backend::asdf;
+use crate::backend::asdf::RELEASE_ASSET_URL_FILE;
+use crate::cache::CacheManager;
 use crate::config::Settings;
-use crate::dirs;
-use crate::git::Git;
-use crate::plugins::{Plugin, PluginList, PluginType};
+use crate::errors::Error::{PluginNotInstalled, PluginNotTrusted};
+use crate::git::{filter_tag_refs, Git, GitStatus};
+use crate::hash;
+use crate::plugins::mise_plugin_toml::{MisePluginToml, MisePluginTomlHints};
+use crate::plugins::{
+    hook_scripts, with_list_concurrency, Plugin, PluginCommand, PluginError, PluginHook,
+    PluginList, PluginType, Provenance, PROVENANCE_FILE,
+};
+use crate::toolset::ToolVersion;
+use crate::ui::prompt;
+use crate::{dirs, env};
+use color_eyre::Section;
+use eyre::{bail, eyre, WrapErr};
+use itertools::Itertools;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use versions::Versioning;
 use xx::file;
 
 #[derive(Debug)]
@@ -10,34 +27,282 @@ pub struct AsdfPlugin {
     pub name: String,
     pub repo: Git,
     pub repo_url: Option<String>,
+    git_head_cache: CacheManager<GitHead>,
+    remote_tags_cache: CacheManager<Vec<String>>,
+}
+
+/// last-known HEAD of the plugin repo, reused while .git/HEAD is unchanged so listing plugins
+/// doesn't need to run git for each one
+#[derive(Debug, Serialize, Deserialize)]
+struct GitHead {
+    abbrev_ref: String,
+    sha_short: String,
+}
+
+/// bump when the fields of GitHead change so caches written by older versions of mise are refetched
+const GIT_HEAD_SCHEMA_VERSION: u32 = 1;
+
+/// where the worktree of plugin `name` checked out at `gitref` goes, see [`AsdfPlugin::add_worktree`]
+pub fn worktree_path(name: &str, gitref: &str) -> PathBuf {
+    worktrees_dir(name).join(gitref.replace('/', "-"))
+}
+
+pub fn worktrees_dir(name: &str) -> PathBuf {
+    dirs::DATA.join("plugin-worktrees").join(name)
 }
 
 impl AsdfPlugin {
     pub fn new(name: String) -> Self {
-        let dir = dirs::PLUGINS.join(&name);
+        let dir = dirs::plugin_path(&name);
+        let cache_dir = dirs::CACHE.join(&name);
+        Self::with_dir(name, dir, cache_dir)
+    }
+
+    /// the plugin checked out at `gitref` in a worktree, see [`AsdfPlugin::add_worktree`]
+    pub fn worktree(name: String, gitref: &str) -> Self {
+        let dir = worktree_path(&name, gitref);
+        let cache_dir = dirs::CACHE
+            .join(&name)
+            .join("worktrees")
+            .join(dir.file_name().unwrap());
+        Self::with_dir(name, dir, cache_dir)
+    }
+
+    fn with_dir(name: String, dir: PathBuf, cache_dir: PathBuf) -> Self {
+        let repo = Git::new(dir);
+        let git_head_cache = repo
+            .head_files()
+            .into_iter()
+            .fold(
+                CacheManager::new(cache_dir.join("git_head-$KEY.msgpack.z")),
+                |cache, path| cache.with_fresh_file(path),
+            )
+            .with_schema_version(GIT_HEAD_SCHEMA_VERSION);
+        let remote_tags_cache = CacheManager::new(cache_dir.join("remote_tags-$KEY.msgpack.z"))
+            .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE);
         Self {
             name,
-            repo: Git::new(dir),
+            repo,
             repo_url: None,
+            git_head_cache,
+            remote_tags_cache,
+        }
+    }
+
+    /// checks out `gitref` of the installed plugin in a worktree sharing the plugin's git objects
+    /// so several refs of the plugin can be used side by side, e.g.: to compare how they resolve
+    /// versions
+    pub fn add_worktree(&self, gitref: &str) -> eyre::Result<AsdfPlugin> {
+        if !self.is_installed() {
+            return Err(eyre!("plugin {} is not installed", self.name)).suggestion(format!(
+                "install it first with `mise plugins install {}`",
+                self.name
+            ));
+        }
+        let path = worktree_path(&self.name, gitref);
+        if path.exists() {
+            bail!("a worktree of {} at {gitref} already exists", self.name);
+        }
+        self.repo
+            .add_worktree(&path, gitref)
+            .wrap_err_with(|| format!("failed to check out {}@{gitref}", self.name))?;
+        Ok(AsdfPlugin::worktree(self.name.clone(), gitref))
+    }
+
+    /// the paths of the worktrees added with [`AsdfPlugin::add_worktree`]
+    pub fn worktrees(&self) -> eyre::Result<Vec<PathBuf>> {
+        if !self.is_installed() {
+            return Ok(vec![]);
+        }
+        self.repo.worktrees()
+    }
+
+    /// tags of the plugin's remote matching `pattern` (e.g.: "v1.*") in version order, e.g.: for
+    /// scripts pinning the plugin. The tags are cached like remote versions
+    pub fn tags_matching(&self, pattern: &str) -> eyre::Result<Vec<String>> {
+        let tags = self.remote_tags_cache.get_or_try_init(|| {
+            let Some(url) = self.get_remote_url()? else {
+                bail!("plugin {} has no remote url to list tags of", self.name);
+            };
+            Ok(Git::available_refs(&url)?
+                .into_iter()
+                .filter(|r| r.starts_with("refs/tags/"))
+                .collect())
+        })?;
+        Ok(filter_tag_refs(tags.clone(), pattern)?
+            .into_iter()
+            .filter_map(|r| r.strip_prefix("refs/tags/").map(String::from))
+            .sorted_by_cached_key(|t| (Versioning::new(t.trim_start_matches('v')), t.clone()))
+            .collect())
+    }
+
+    /// forgets the cached HEAD, needed after the plugin is installed or updated since
+    /// fetching a branch does not necessarily touch .git/HEAD
+    pub fn invalidate_git_head_cache(&self) -> eyre::Result<()> {
+        self.git_head_cache.clear()
+    }
+
+    fn git_head(&self) -> eyre::Result<&GitHead> {
+        self.git_head_cache.get_or_try_init(|| {
+            Ok(GitHead {
+                abbrev_ref: self.repo.current_abbrev_ref()?,
+                sha_short: self.repo.current_sha_short()?,
+            })
+        })
+    }
+
+    fn scripts_hash_path(&self) -> PathBuf {
+        dirs::STATE
+            .join("plugins")
+            .join(&self.name)
+            .join("scripts-hash")
+    }
+
+    /// stores the current scripts_hash, returns true if a different one was recorded before
+    pub fn record_scripts_hash(&self) -> eyre::Result<bool> {
+        let path = self.scripts_hash_path();
+        let prev = file::read_to_string(&path).ok();
+        let hash = self.scripts_hash()?;
+        match &hash {
+            Some(hash) => file::write(&path, hash)?,
+            None => self.forget_scripts_hash()?,
+        }
+        Ok(prev.is_some() && prev != hash)
+    }
+
+    pub fn forget_scripts_hash(&self) -> eyre::Result<()> {
+        let path = self.scripts_hash_path();
+        if path.exists() {
+            crate::file::remove_file(&path)?;
+        }
+        Ok(())
+    }
+
+    fn shorthand_url_path(&self) -> PathBuf {
+        dirs::STATE
+            .join("plugins")
+            .join(&self.name)
+            .join("shorthand-url")
+    }
+
+    /// stores the url the plugin was cloned from when it came from its shorthand so
+    /// `upgrade_shorthand` can tell if the remote still is that url, None forgets it
+    pub fn record_shorthand_url(&self, url: Option<&str>) -> eyre::Result<()> {
+        let path = self.shorthand_url_path();
+        match url {
+            Some(url) => file::write(&path, url)?,
+            None if path.exists() => crate::file::remove_file(&path)?,
+            None => {}
+        }
+        Ok(())
+    }
+
+    fn dev_ref_path(&self) -> PathBuf {
+        dirs::STATE.join("plugins").join(&self.name).join("dev-ref")
+    }
+
+    /// stores the refspec of the PR or branch the plugin was installed from so `update` keeps
+    /// following it, None forgets it
+    pub fn record_dev_ref(&self, refspec: Option<&str>) -> eyre::Result<()> {
+        let path = self.dev_ref_path();
+        match refspec {
+            Some(refspec) => file::write(&path, refspec)?,
+            None if path.exists() => crate::file::remove_file(&path)?,
+            None => {}
+        }
+        Ok(())
+    }
+
+    fn updates_since_gc_path(&self) -> PathBuf {
+        dirs::STATE
+            .join("plugins")
+            .join(&self.name)
+            .join("updates-since-gc")
+    }
+
+    /// counts an update of the plugin's repo for `plugin_auto_gc`, returns how many there were
+    /// since it was last gc'd including this one
+    pub fn record_update_since_gc(&self) -> eyre::Result<u32> {
+        let path = self.updates_since_gc_path();
+        let updates = file::read_to_string(&path)
+            .ok()
+            .and_then(|s| s.trim().parse::<u32>().ok())
+            .unwrap_or_default()
+            + 1;
+        file::write(&path, updates.to_string())?;
+        Ok(updates)
+    }
+
+    pub fn forget_updates_since_gc(&self) -> eyre::Result<()> {
+        let path = self.updates_since_gc_path();
+        if path.exists() {
+            crate::file::remove_file(&path)?;
+        }
+        Ok(())
+    }
+
+    /// records where `tv` was installed from for `provenance` in its install path, its download
+    /// path is hashed if the plugin downloaded anything there
+    pub fn record_provenance(&self, tv: &ToolVersion) -> eyre::Result<()> {
+        let download_path = tv.download_path();
+        let checksum = match download_path.is_dir() {
+            true => Some(hash::dir_hash_sha256(&download_path)?),
+            false => None,
+        };
+        let provenance = Provenance {
+            version: tv.version.clone(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            source_url: self.get_remote_url()?,
+            plugin_sha: self
+                .repo
+                .exists()
+                .then(|| self.repo.current_sha())
+                .transpose()?,
+            checksum,
+        };
+        let path = tv.install_path().join(PROVENANCE_FILE);
+        file::write(path, serde_json::to_string_pretty(&provenance)?)?;
+        Ok(())
+    }
+
+    fn last_error_path(&self) -> PathBuf {
+        dirs::CACHE.join(&self.name).join("last_error.json")
+    }
+
+    /// keeps why installing the plugin failed for `last_error` until it installs successfully
+    pub fn record_last_error(&self, phase: &str, err: &eyre::Report) -> eyre::Result<()> {
+        let last_error = PluginError {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            phase: phase.to_string(),
+            message: format!("{err:#}"),
+        };
+        file::write(self.last_error_path(), serde_json::to_string(&last_error)?)?;
+        Ok(())
+    }
+
+    pub fn clear_last_error(&self) -> eyre::Result<()> {
+        let path = self.last_error_path();
+        if path.exists() {
+            crate::file::remove_file(&path)?;
         }
+        Ok(())
     }
 
     pub fn list() -> eyre::Result<PluginList> {
         let settings = Settings::get();
-        match file::ls(*dirs::PLUGINS) {
-            Ok(dirs) => {
-                let plugins = dirs
-                    .into_par_iter()
-                    .map(|dir| {
-                        let name = dir.file_name().unwrap().to_string_lossy().to_string();
-                        Box::new(AsdfPlugin::new(name)) as Box<dyn Plugin>
-                    })
-                    .filter(|p| !settings.disable_tools.contains(p.name()))
-                    .collect();
-                Ok(plugins)
-            }
-            Err(_) => Ok(PluginList::new()),
-        }
+        let names = dirs::plugins_dirs()
+            .iter()
+            .flat_map(|dir| file::ls(dir).unwrap_or_default())
+            .map(|dir| dir.file_name().unwrap().to_string_lossy().to_string())
+            .unique()
+            .collect_vec();
+        with_list_concurrency(|| {
+            names
+                .into_par_iter()
+                .map(|name| Box::new(AsdfPlugin::new(name)) as Box<dyn Plugin>)
+                .filter(|p| !settings.disable_tools.contains(p.name()))
+                .collect()
+        })
     }
 }
 
@@ -51,7 +316,11 @@ impl Plugin for AsdfPlugin {
     }
 
     fn get_remote_url(&self) -> eyre::Result<Option<String>> {
-        let url = self.repo.get_remote_url();
+        let url = self.repo.get_remote_url().or_else(|| {
+            file::read_to_string(self.repo.dir.join(RELEASE_ASSET_URL_FILE))
+                .ok()
+                .map(|url| url.trim().to_string())
+        });
         Ok(url.or(self.repo_url.clone()))
     }
 
@@ -59,17 +328,98 @@ impl Plugin for AsdfPlugin {
         if !self.is_installed() {
             return Ok(None);
         }
-        self.repo.current_abbrev_ref().map(Some)
+        Ok(Some(self.git_head()?.abbrev_ref.clone()))
     }
 
     fn current_sha_short(&self) -> eyre::Result<Option<String>> {
         if !self.is_installed() {
             return Ok(None);
         }
-        self.repo.current_sha_short().map(Some)
+        Ok(Some(self.git_head()?.sha_short.clone()))
     }
 
     fn is_installed(&self) -> bool {
         self.repo.exists()
     }
+
+    fn hints(&self) -> eyre::Result<MisePluginTomlHints> {
+        let toml = MisePluginToml::from_file(&self.repo.dir.join("mise.plugin.toml"))?;
+        Ok(toml.hints)
+    }
+
+    fn last_error(&self) -> eyre::Result<Option<PluginError>> {
+        let path = self.last_error_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&file::read_to_string(&path)?)?))
+    }
+
+    fn list_hooks(&self) -> eyre::Result<Vec<PluginHook>> {
+        if !self.is_installed() {
+            Err(PluginNotInstalled(self.name.clone()))?
+        }
+        Ok(hook_scripts()
+            .into_iter()
+            .map(|script| {
+                let path = self.repo.dir.join("bin").join(script.to_string());
+                PluginHook {
+                    name: script.to_string(),
+                    present: path.is_file(),
+                    path,
+                }
+            })
+            .collect())
+    }
+
+    fn install_dry_run(&self) -> eyre::Result<Vec<PluginCommand>> {
+        asdf::Asdf::new(self.name.clone()).install_plan()
+    }
+
+    fn dev_ref(&self) -> Option<String> {
+        file::read_to_string(self.dev_ref_path()).ok()
+    }
+
+    fn upgrade_shorthand(&self) -> eyre::Result<Option<String>> {
+        let recorded = file::read_to_string(self.shorthand_url_path()).ok();
+        let (Some(recorded), Some(remote)) = (recorded, self.repo.get_remote_url()) else {
+            return Ok(None);
+        };
+        let Some(current) = asdf::shorthand_remote(&self.name)? else {
+            return Ok(None);
+        };
+        if !asdf::same_remote(&remote, &recorded) || asdf::same_remote(&remote, &current) {
+            return Ok(None);
+        }
+        let settings = Settings::get();
+        if settings.paranoid && !asdf::is_trusted_plugin(&self.name, &current) {
+            Err(PluginNotTrusted(self.name.clone()))?
+        }
+        if !settings.yes
+            && !prompt::confirm(format!(
+                "plugin {} moved from {remote} to {current}, update its remote?",
+                self.name
+            ))?
+        {
+            return Ok(None);
+        }
+        self.repo.set_remote_url(&current)?;
+        self.record_shorthand_url(Some(&current))?;
+        Ok(Some(current))
+    }
+
+    fn scripts_hash(&self) -> eyre::Result<Option<String>> {
+        let bin = self.repo.dir.join("bin");
+        if !bin.is_dir() {
+            return Ok(None);
+        }
+        Ok(Some(hash::dir_hash_sha256(&bin)?))
+    }
+
+    fn git_status(&self) -> eyre::Result<Option<GitStatus>> {
+        if !self.is_installed() {
+            return Ok(None);
+        }
+        self.repo.status().map(Some)
+    }
 }
@@ -6,7 +6,7 @@ use eyre::Result;
 use itertools::Itertools;
 use versions::Versioning;
 
-use crate::backend::Backend;
+use crate::backend::{Backend, Capability};
 use crate::cli::args::BackendArg;
 use crate::cli::version::{ARCH, OS};
 use crate::cmd::CmdLineRunner;
@@ -100,6 +100,9 @@ impl Backend for DenoPlugin {
     fn fa(&self) -> &BackendArg {
         &self.core.fa
     }
+    fn supports(&self, capability: Capability) -> bool {
+        matches!(capability, Capability::EnvInjection)
+    }
 
     fn _list_remote_versions(&self) -> Result<Vec<String>> {
         self.core
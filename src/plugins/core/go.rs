@@ -6,7 +6,7 @@ use itertools::Itertools;
 use tempfile::tempdir_in;
 use versions::Versioning;
 
-use crate::backend::Backend;
+use crate::backend::{Backend, Capability};
 use crate::cli::args::BackendArg;
 use crate::cli::version::{ARCH, OS};
 use crate::cmd::CmdLineRunner;
@@ -128,7 +128,9 @@ impl GoPlugin {
             if !settings.go_skip_checksum {
                 pr.set_message(format!("verifying {filename}"));
                 let checksum = checksum_handle.join().unwrap()?;
-                hash::ensure_checksum_sha256(&tarball_path, &checksum, Some(pr))?;
+                HTTP.verify_download(&tarball_url, &tarball_path, Some(pr), |path| {
+                    hash::ensure_checksum_sha256(path, &checksum, Some(pr))
+                })?;
             }
             Ok(tarball_path)
         })
@@ -189,6 +191,12 @@ impl Backend for GoPlugin {
     fn fa(&self) -> &BackendArg {
         &self.core.fa
     }
+    fn supports(&self, capability: Capability) -> bool {
+        matches!(
+            capability,
+            Capability::ChecksumVerification | Capability::EnvInjection
+        )
+    }
     fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
         self.core
             .remote_version_cache
@@ -12,7 +12,7 @@ use regex::Regex;
 use serde_derive::{Deserialize, Serialize};
 use versions::Versioning;
 
-use crate::backend::Backend;
+use crate::backend::{Backend, Capability};
 use crate::cache::CacheManager;
 use crate::cli::args::BackendArg;
 use crate::cli::version::{ARCH, OS};
@@ -140,7 +140,9 @@ impl JavaPlugin {
         pr.set_message(format!("downloading {filename}"));
         HTTP.download_file(&m.url, &tarball_path, Some(pr))?;
 
-        hash::ensure_checksum_sha256(&tarball_path, &m.sha256, Some(pr))?;
+        HTTP.verify_download(&m.url, &tarball_path, Some(pr), |path| {
+            hash::ensure_checksum_sha256(path, &m.sha256, Some(pr))
+        })?;
 
         Ok(tarball_path)
     }
@@ -305,6 +307,12 @@ impl Backend for JavaPlugin {
     fn fa(&self) -> &BackendArg {
         &self.core.fa
     }
+    fn supports(&self, capability: Capability) -> bool {
+        matches!(
+            capability,
+            Capability::ChecksumVerification | Capability::EnvInjection
+        )
+    }
 
     fn _list_remote_versions(&self) -> Result<Vec<String>> {
         self.core
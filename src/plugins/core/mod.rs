@@ -7,7 +7,7 @@ use once_cell::sync::Lazy;
 
 pub use python::PythonPlugin;
 
-use crate::backend::{Backend, BackendList};
+use crate::backend::{Backend, BackendList, REMOTE_VERSIONS_SCHEMA_VERSION};
 use crate::cache::CacheManager;
 use crate::cli::args::BackendArg;
 use crate::config::Settings;
@@ -74,7 +74,8 @@ impl CorePlugin {
             remote_version_cache: CacheManager::new(
                 fa.cache_path.join("remote_versions-$KEY.msgpack.z"),
             )
-            .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE),
+            .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE)
+            .with_schema_version(REMOTE_VERSIONS_SCHEMA_VERSION),
             fa,
         }
     }
@@ -6,7 +6,7 @@ use serde_derive::Deserialize;
 use tempfile::tempdir_in;
 use url::Url;
 
-use crate::backend::Backend;
+use crate::backend::{Backend, Capability};
 use crate::build_time::built_info;
 use crate::cli::args::BackendArg;
 use crate::cmd::CmdLineRunner;
@@ -116,7 +116,9 @@ impl NodePlugin {
         }
         if *env::MISE_NODE_VERIFY {
             pr.set_message(format!("verifying {tarball_name}"));
-            self.verify(local, version, pr)?;
+            HTTP.verify_download(url.clone(), local, Some(pr), |path| {
+                self.verify(path, version, pr)
+            })?;
         }
         Ok(())
     }
@@ -238,6 +240,9 @@ impl Backend for NodePlugin {
     fn fa(&self) -> &BackendArg {
         &self.core.fa
     }
+    fn supports(&self, capability: Capability) -> bool {
+        matches!(capability, Capability::ChecksumVerification)
+    }
 
     fn _list_remote_versions(&self) -> Result<Vec<String>> {
         self.core
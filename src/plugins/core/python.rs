@@ -4,7 +4,7 @@ use std::path::{Path, PathBuf};
 use eyre::{bail, eyre};
 use itertools::Itertools;
 
-use crate::backend::Backend;
+use crate::backend::{Backend, Capability};
 use crate::build_time::built_info;
 use crate::cache::CacheManager;
 use crate::cli::args::BackendArg;
@@ -314,6 +314,9 @@ impl Backend for PythonPlugin {
     fn fa(&self) -> &BackendArg {
         &self.core.fa
     }
+    fn supports(&self, capability: Capability) -> bool {
+        matches!(capability, Capability::EnvInjection)
+    }
 
     fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
         if Settings::get().python_compile == Some(false) {
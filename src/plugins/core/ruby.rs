@@ -5,7 +5,7 @@ use std::path::{Path, PathBuf};
 use contracts::requires;
 use eyre::{Result, WrapErr};
 
-use crate::backend::Backend;
+use crate::backend::{Backend, Capability};
 use crate::cli::args::BackendArg;
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
@@ -331,6 +331,9 @@ impl Backend for RubyPlugin {
     fn fa(&self) -> &BackendArg {
         &self.core.fa
     }
+    fn supports(&self, capability: Capability) -> bool {
+        matches!(capability, Capability::EnvInjection)
+    }
     fn _list_remote_versions(&self) -> Result<Vec<String>> {
         self.core
             .remote_version_cache
@@ -5,7 +5,7 @@ use eyre::Result;
 use itertools::Itertools;
 use versions::Versioning;
 
-use crate::backend::Backend;
+use crate::backend::{Backend, Capability};
 use crate::cli::args::BackendArg;
 use crate::cli::version::{ARCH, OS};
 use crate::cmd::CmdLineRunner;
@@ -131,6 +131,9 @@ impl Backend for ZigPlugin {
     fn fa(&self) -> &BackendArg {
         &self.core.fa
     }
+    fn supports(&self, capability: Capability) -> bool {
+        matches!(capability, Capability::RefPinning)
+    }
 
     fn _list_remote_versions(&self) -> Result<Vec<String>> {
         self.core
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use color_eyre::eyre::eyre;
@@ -12,12 +13,43 @@ pub struct MisePluginTomlScriptConfig {
     pub data: Option<String>,
 }
 
+/// suggestions from the plugin author for users installing the tool
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MisePluginTomlHints {
+    /// version to suggest when the tool is first installed
+    pub default_version: Option<String>,
+    /// env vars the plugin needs to be set to install/use the tool
+    pub required_env: Vec<String>,
+    /// boolean mise settings the plugin needs enabled, e.g.: "experimental"
+    pub required_settings: Vec<String>,
+}
+
+/// platforms the plugin can install the tool on, empty for any. Names are the ones from
+/// `mise version`, e.g.: "linux"/"macos" and "x64"/"arm64"
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MisePluginTomlPlatforms {
+    pub os: Vec<String>,
+    pub arch: Vec<String>,
+}
+
+impl MisePluginTomlPlatforms {
+    pub fn supports(&self, os: &str, arch: &str) -> bool {
+        (self.os.is_empty() || self.os.iter().any(|o| o == os))
+            && (self.arch.is_empty() || self.arch.iter().any(|a| a == arch))
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct MisePluginToml {
+    /// commands needed to install the tool (e.g.: compilers) with their minimum version, "*" for
+    /// any version
+    pub build_requirements: BTreeMap<String, String>,
     pub exec_env: MisePluginTomlScriptConfig,
+    pub hints: MisePluginTomlHints,
     pub list_aliases: MisePluginTomlScriptConfig,
     pub list_bin_paths: MisePluginTomlScriptConfig,
     pub list_legacy_filenames: MisePluginTomlScriptConfig,
+    pub platforms: MisePluginTomlPlatforms,
 }
 
 impl MisePluginToml {
@@ -42,12 +74,17 @@ impl MisePluginToml {
         let doc: DocumentMut = s.parse().suggestion("ensure file is valid TOML")?;
         for (k, v) in doc.iter() {
             match k {
+                "build-requirements" => {
+                    self.build_requirements = self.parse_build_requirements(k, v)?
+                }
                 "exec-env" => self.exec_env = self.parse_script_config(k, v)?,
+                "hints" => self.hints = self.parse_hints(k, v)?,
                 "list-aliases" => self.list_aliases = self.parse_script_config(k, v)?,
                 "list-bin-paths" => self.list_bin_paths = self.parse_script_config(k, v)?,
                 "list-legacy-filenames" => {
                     self.list_legacy_filenames = self.parse_script_config(k, v)?
                 }
+                "platforms" => self.platforms = self.parse_platforms(k, v)?,
                 // this is an old key used in rtx-python
                 // this file is invalid, so just stop parsing entirely if we see it
                 "legacy-filenames" => return Ok(()),
@@ -78,6 +115,72 @@ impl MisePluginToml {
         }
     }
 
+    fn parse_hints(&mut self, key: &str, v: &Item) -> Result<MisePluginTomlHints> {
+        match v.as_table_like() {
+            Some(table) => {
+                let mut hints = MisePluginTomlHints::default();
+                for (k, v) in table.iter() {
+                    let key = format!("{}.{}", key, k);
+                    match k {
+                        "default-version" => match v.as_value() {
+                            Some(v) => hints.default_version = Some(self.parse_string(k, v)?),
+                            _ => parse_error!(key, v, "string"),
+                        },
+                        "required-env" => hints.required_env = self.parse_string_array(k, v)?,
+                        "required-settings" => {
+                            hints.required_settings = self.parse_string_array(k, v)?
+                        }
+                        _ => parse_error!(
+                            key,
+                            v,
+                            "one of: default-version, required-env, required-settings"
+                        ),
+                    }
+                }
+                Ok(hints)
+            }
+            _ => parse_error!(key, v, "table"),
+        }
+    }
+
+    fn parse_platforms(&mut self, key: &str, v: &Item) -> Result<MisePluginTomlPlatforms> {
+        match v.as_table_like() {
+            Some(table) => {
+                let mut platforms = MisePluginTomlPlatforms::default();
+                for (k, v) in table.iter() {
+                    let key = format!("{}.{}", key, k);
+                    match k {
+                        "os" => platforms.os = self.parse_string_array(k, v)?,
+                        "arch" => platforms.arch = self.parse_string_array(k, v)?,
+                        _ => parse_error!(key, v, "one of: os, arch"),
+                    }
+                }
+                Ok(platforms)
+            }
+            _ => parse_error!(key, v, "table"),
+        }
+    }
+
+    fn parse_build_requirements(
+        &mut self,
+        key: &str,
+        v: &Item,
+    ) -> Result<BTreeMap<String, String>> {
+        match v.as_table_like() {
+            Some(table) => {
+                let mut requirements = BTreeMap::new();
+                for (k, v) in table.iter() {
+                    match v.as_value() {
+                        Some(v) => requirements.insert(k.to_string(), self.parse_string(k, v)?),
+                        _ => parse_error!(format!("{}.{}", key, k), v, "string"),
+                    };
+                }
+                Ok(requirements)
+            }
+            _ => parse_error!(key, v, "table"),
+        }
+    }
+
     fn parse_string_array(&mut self, k: &str, v: &Item) -> Result<Vec<String>> {
         match v.as_array() {
             Some(arr) => {
@@ -113,6 +216,7 @@ mod tests {
         let cf = MisePluginToml::from_file(&dirs::HOME.join("fixtures/mise.plugin.toml")).unwrap();
 
         assert_debug_snapshot!(cf.exec_env);
+        assert_eq!(cf.hints.required_settings, vec!["experimental"]);
     }
 
     #[test]
@@ -141,6 +245,21 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn test_platforms() {
+        let cf = parse(&formatdoc! {r#"
+        [platforms]
+        os = ["linux"]
+        "#});
+
+        assert_eq!(cf.platforms.os, vec!["linux"]);
+        assert!(cf.platforms.supports("linux", "arm64"));
+        assert!(!cf.platforms.supports("macos", "arm64"));
+        assert!(MisePluginTomlPlatforms::default().supports("macos", "x64"));
+        let mut cf = MisePluginToml::init();
+        assert!(cf.parse("[platforms]\nos = \"linux\"\n").is_err());
+    }
+
     fn parse(s: &str) -> MisePluginToml {
         let mut cf = MisePluginToml::init();
         cf.parse(s).unwrap();
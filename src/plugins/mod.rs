@@ -1,16 +1,32 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{Debug, Display};
+use std::path::{Path, PathBuf};
 
+use eyre::eyre;
+use itertools::Itertools;
 use once_cell::sync::Lazy;
+use rayon::prelude::*;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use toml_edit::DocumentMut;
 
 pub use script_manager::{Script, ScriptManager};
 
 use crate::backend;
 use crate::backend::{ABackend, BackendList, BackendType};
 use crate::cli::args::BackendArg;
+use crate::config::Settings;
+use crate::file::display_path;
+use crate::git::GitStatus;
+use crate::http::HTTP;
 use crate::plugins::asdf_plugin::AsdfPlugin;
-use crate::plugins::core::CorePlugin;
+use crate::plugins::core::{CorePlugin, CORE_PLUGINS};
+use crate::plugins::mise_plugin_toml::MisePluginTomlHints;
+use crate::runtime_symlinks::is_runtime_symlink;
+use crate::shorthands::get_shorthands;
+use crate::toolset::{ToolRequest, ToolVersion};
+use crate::ui::progress_report::SingleReport;
+use crate::{dirs, env, file, github};
 
 pub mod asdf_plugin;
 pub mod core;
@@ -23,6 +39,12 @@ pub enum PluginType {
     Asdf,
 }
 
+impl Display for PluginType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
 pub static VERSION_REGEX: Lazy<regex::Regex> = Lazy::new(|| {
     Regex::new(
         r"(^Available versions:|-src|-dev|-latest|-stm|[-\\.]rc|-milestone|-alpha|-beta|[-\\.]pre|-next|([abc])[0-9]+|snapshot|SNAPSHOT|master)"
@@ -30,8 +52,22 @@ pub static VERSION_REGEX: Lazy<regex::Regex> = Lazy::new(|| {
         .unwrap()
 });
 
+/// accepts a plugin name or its canonical name (see [`Plugin::canonical_name`]) to pick between
+/// a core and an asdf plugin of the same name, e.g.: "asdf:node" for the asdf-nodejs plugin
 pub fn get(name: &str) -> ABackend {
-    BackendArg::new(BackendType::Asdf, name).into()
+    let fa = match name.split_once(':') {
+        Some(("core" | "asdf", _)) => BackendArg::from(name),
+        _ => BackendArg::new(BackendType::Asdf, name),
+    };
+    match fa.backend_type {
+        // an installed asdf plugin of the same name is registered in place of the core plugin
+        BackendType::Core => CORE_PLUGINS
+            .iter()
+            .find(|p| p.name() == fa.name)
+            .cloned()
+            .unwrap_or_else(|| fa.into()),
+        _ => fa.into(),
+    }
 }
 
 pub fn list() -> BackendList {
@@ -51,6 +87,157 @@ pub fn list2() -> eyre::Result<PluginMap> {
     Ok(core.chain(asdf).collect())
 }
 
+/// describes each plugin, running the git lookups in parallel
+pub fn describe_all(plugins: PluginMap) -> eyre::Result<Vec<String>> {
+    with_list_concurrency(|| plugins.into_par_iter().map(|(_, p)| p.describe()).collect())
+}
+
+/// runs `f` in a pool of `list_concurrency` threads so parallel walks over the plugins
+/// (which may spawn a git process per plugin) can be throttled. Uses the global pool if unset.
+pub fn with_list_concurrency<R: Send>(f: impl FnOnce() -> R + Send) -> eyre::Result<R> {
+    match Settings::try_get()?.list_concurrency {
+        Some(n) => Ok(rayon::ThreadPoolBuilder::new()
+            .num_threads(n)
+            .build()?
+            .install(f)),
+        None => Ok(f()),
+    }
+}
+
+/// runs [`Plugin::remote_url_redirect_check`] for each plugin, `jobs` at a time
+pub fn remote_url_redirect_check_all(
+    plugins: PluginMap,
+) -> eyre::Result<Vec<(String, eyre::Result<Option<RemoteUrlCheck>>)>> {
+    let settings = Settings::try_get()?;
+    let checks = rayon::ThreadPoolBuilder::new()
+        .num_threads(settings.jobs)
+        .build()?
+        .install(|| {
+            plugins
+                .into_par_iter()
+                .map(|(name, p)| (name, p.remote_url_redirect_check()))
+                .collect()
+        });
+    Ok(checks)
+}
+
+/// where a tool name known to mise comes from, see [`known_tools`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, strum::Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ToolSource {
+    Core,
+    InstalledAsdf,
+    /// a default shorthand or one from `shorthands_file`
+    Shorthand,
+}
+
+/// the settings in `required` which are not enabled, unknown settings count as not enabled
+pub fn disabled_settings(required: &[String]) -> eyre::Result<Vec<String>> {
+    let settings = Settings::try_get()?.as_dict()?;
+    Ok(required
+        .iter()
+        .filter(|s| settings.get(s.as_str()).and_then(|v| v.as_bool()) != Some(true))
+        .cloned()
+        .collect())
+}
+
+/// see [`Plugin::normalize_installed_layout`], `legacy` is where older versions of mise put the
+/// installs before they were named after the kebab-case id, e.g.: "installs/my_tool"
+fn normalize_installed_layout(legacy: &Path, installs_path: &Path) -> eyre::Result<Vec<String>> {
+    let mut changes = vec![];
+    let mut rename = |from: &Path, to: &Path| -> eyre::Result<()> {
+        let change = format!("moved {} to {}", display_path(from), display_path(to));
+        info!("{change}");
+        file::create_dir_all(to.parent().unwrap())?;
+        file::rename(from, to)?;
+        changes.push(change);
+        Ok(())
+    };
+    if legacy != installs_path && legacy.is_dir() {
+        for v in file::dir_subdirs(legacy)? {
+            let to = installs_path.join(&v);
+            // installed by both, the current one is kept
+            if !to.exists() {
+                rename(&legacy.join(&v), &to)?;
+            }
+        }
+        if file::ls(legacy)?.is_empty() {
+            file::remove_dir(legacy)?;
+        }
+    }
+    // version dirs used to keep the ":" which is replaced with "-" now, e.g.: "ref:main"
+    for v in file::dir_subdirs(installs_path)? {
+        let to = installs_path.join(v.replace(':', "-"));
+        if v.contains(':') && !is_runtime_symlink(&installs_path.join(&v)) && !to.exists() {
+            rename(&installs_path.join(&v), &to)?;
+        }
+    }
+    Ok(changes)
+}
+
+/// see [`Plugin::relink`], `installs_dir` is the current ~/.local/share/mise/installs
+fn relink(installs_path: &Path, installs_dir: &Path) -> eyre::Result<Vec<String>> {
+    let mut changes = vec![];
+    if !installs_path.exists() {
+        return Ok(changes);
+    }
+    for entry in walkdir::WalkDir::new(installs_path) {
+        let link = entry?.into_path();
+        let Ok(target) = link.read_link() else {
+            continue;
+        };
+        // relative links (e.g.: runtime symlinks) still work after the dir is moved
+        if target.is_relative() || link.exists() {
+            continue;
+        }
+        // the same path under the current installs dir, e.g.:
+        // "/old/home/.local/share/mise/installs/node/20.0.0/bin/node"
+        let components = target.components().collect_vec();
+        let relinked = components
+            .iter()
+            .positions(|c| c.as_os_str() == "installs")
+            .map(|i| installs_dir.join(components[i + 1..].iter().collect::<PathBuf>()))
+            .find(|p| p.exists());
+        let Some(relinked) = relinked else {
+            warn!(
+                "{} links to {} which doesn't exist",
+                display_path(&link),
+                display_path(&target)
+            );
+            continue;
+        };
+        file::make_symlink(&relinked, &link)?;
+        let change = format!(
+            "relinked {} to {}",
+            display_path(&link),
+            display_path(&relinked)
+        );
+        info!("{change}");
+        changes.push(change);
+    }
+    Ok(changes)
+}
+
+/// every tool name that resolves to a plugin without installing anything first, sorted by name
+/// along with where each one comes from, e.g.: for `mise plugins search`
+pub fn known_tools() -> eyre::Result<BTreeMap<String, BTreeSet<ToolSource>>> {
+    let settings = Settings::try_get()?;
+    let core = CorePlugin::list()
+        .into_iter()
+        .map(|p| (p.name().to_string(), ToolSource::Core));
+    let installed = AsdfPlugin::list()?
+        .into_iter()
+        .map(|p| (p.name().to_string(), ToolSource::InstalledAsdf));
+    let shorthands = get_shorthands(&settings)
+        .into_keys()
+        .map(|name| (name, ToolSource::Shorthand));
+    let mut tools: BTreeMap<String, BTreeSet<ToolSource>> = BTreeMap::new();
+    for (name, source) in core.chain(installed).chain(shorthands) {
+        tools.entry(name).or_default().insert(source);
+    }
+    Ok(tools)
+}
+
 pub fn list_external() -> BackendList {
     list()
         .into_iter()
@@ -58,6 +245,250 @@ pub fn list_external() -> BackendList {
         .collect()
 }
 
+/// the name of the file in an install dir holding its [`Provenance`]
+pub const PROVENANCE_FILE: &str = ".mise-provenance.json";
+
+/// where an installed version came from, for auditing. See [`Plugin::provenance`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub version: String,
+    /// RFC 3339, when the version was installed
+    pub timestamp: String,
+    /// the remote of the plugin that installed it
+    pub source_url: Option<String>,
+    /// the commit the plugin was at
+    pub plugin_sha: Option<String>,
+    /// sha256 of what was downloaded to install it, see [`crate::hash::dir_hash_sha256`]
+    pub checksum: Option<String>,
+}
+
+/// adds or removes `name` from `disable_tools` in the global config, removing the setting once
+/// it's empty so enabling every disabled plugin leaves the file as it was
+fn set_disabled(name: &str, disabled: bool) -> eyre::Result<()> {
+    let path = &*env::MISE_GLOBAL_CONFIG_FILE;
+    let raw = file::read_to_string(path).unwrap_or_default();
+    let mut config: DocumentMut = raw.parse()?;
+    let settings = config
+        .entry("settings")
+        .or_insert(toml_edit::table())
+        .as_table_mut()
+        .ok_or_else(|| eyre!("[settings] in {} is not a table", display_path(path)))?;
+    let mut tools: BTreeSet<String> = settings
+        .get("disable_tools")
+        .and_then(|v| v.as_array())
+        .map(|a| {
+            a.iter()
+                .filter_map(|v| v.as_str())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+    let changed = match disabled {
+        true => tools.insert(name.to_string()),
+        false => tools.remove(name),
+    };
+    if !changed {
+        return Ok(());
+    }
+    if tools.is_empty() {
+        settings.remove("disable_tools");
+    } else {
+        let tools = tools.into_iter().collect::<toml_edit::Array>();
+        settings.insert("disable_tools", toml_edit::value(tools));
+    }
+    file::create_dir_all(path.parent().unwrap())?;
+    file::write(path, config.to_string())
+}
+
+/// why installing a plugin failed, see [`Plugin::last_error`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginError {
+    /// RFC 3339, e.g.: "2024-07-01T12:00:00+00:00"
+    pub timestamp: String,
+    /// e.g.: "clone" or "post-plugin-add"
+    pub phase: String,
+    pub message: String,
+}
+
+/// a script mise may run from a plugin, see [`Plugin::list_hooks`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginHook {
+    /// e.g.: "install" or "post-plugin-update"
+    pub name: String,
+    pub path: PathBuf,
+    /// false if the plugin doesn't have it, mise skips it or does the default
+    pub present: bool,
+}
+
+/// a command installing a plugin would run, see [`Plugin::install_dry_run`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginCommand {
+    /// e.g.: "git" or the path to a hook
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl PluginCommand {
+    pub fn new(program: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            program: program.into(),
+            args,
+        }
+    }
+}
+
+impl Display for PluginCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cmd = std::iter::once(&self.program)
+            .chain(&self.args)
+            .map(|arg| shell_escape::escape(arg.as_str().into()))
+            .collect::<Vec<_>>();
+        write!(f, "{}", cmd.join(" "))
+    }
+}
+
+/// the scripts mise may run from an asdf plugin's bin dir, roughly in the order they run
+pub fn hook_scripts() -> Vec<Script> {
+    let hooks = [
+        "post-plugin-add",
+        "post-plugin-checkout",
+        "post-plugin-update",
+        "pre-plugin-remove",
+        "post-plugin-remove",
+    ]
+    .map(|hook| Script::Hook(hook.to_string()));
+    hooks
+        .into_iter()
+        .chain([
+            Script::ListAll,
+            Script::ListAllAfter(String::new()),
+            Script::LatestStable,
+            Script::ListAliases,
+            Script::ListLegacyFilenames,
+            Script::ParseLegacyFile(String::new()),
+            Script::Download,
+            Script::Install,
+            Script::ListBinPaths,
+            Script::ExecEnv,
+            Script::Uninstall,
+        ])
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteUrlCheck {
+    pub url: String,
+    pub reachable: bool,
+    /// where the server sent us if the url has moved
+    pub redirect: Option<String>,
+}
+
+impl RemoteUrlCheck {
+    pub fn check(url: &str) -> Self {
+        let (reachable, redirect) = match url.starts_with("https://") || url.starts_with("http://")
+        {
+            true => {
+                // the same endpoint `git clone` requests first, so redirects are the ones git would follow
+                let refs_url = format!(
+                    "{}/info/refs?service=git-upload-pack",
+                    url.trim_end_matches('/')
+                );
+                match HTTP.final_url(&refs_url) {
+                    Ok(final_url) if final_url.as_str() != refs_url => {
+                        let moved = final_url.as_str().split("/info/refs").next().unwrap();
+                        (true, Some(moved.to_string()))
+                    }
+                    Ok(_) => (true, None),
+                    Err(err) => {
+                        debug!("{url} is unreachable: {err:#}");
+                        (false, None)
+                    }
+                }
+            }
+            false => {
+                let res = cmd!("git", "ls-remote", url, "HEAD")
+                    .env("GIT_TERMINAL_PROMPT", "0")
+                    .stdout_null()
+                    .stderr_null()
+                    .unchecked()
+                    .run();
+                (res.is_ok_and(|o| o.status.success()), None)
+            }
+        };
+        Self {
+            url: url.to_string(),
+            reachable,
+            redirect,
+        }
+    }
+}
+
+/// whether a plugin comes from a source mise considers trustworthy, with the reason why
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrustStatus {
+    /// core plugins, plugins maintained by the mise team and first-party plugins
+    Trusted(String),
+    /// the default shorthand for a plugin developed by the community
+    Community(String),
+    /// the name of a default shorthand installed from a different repo
+    Shadowing(String),
+    /// not a default shorthand or the remote url is unknown
+    Unknown(String),
+    /// checked out from an unmerged PR or branch, it is never trusted
+    Development(String),
+}
+
+impl TrustStatus {
+    pub fn reason(&self) -> &str {
+        match self {
+            Self::Trusted(r)
+            | Self::Community(r)
+            | Self::Shadowing(r)
+            | Self::Unknown(r)
+            | Self::Development(r) => r,
+        }
+    }
+}
+
+impl Display for TrustStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            Self::Trusted(_) => "trusted",
+            Self::Community(_) => "community",
+            Self::Shadowing(_) => "shadowing",
+            Self::Unknown(_) => "unknown",
+            Self::Development(_) => "development",
+        };
+        write!(f, "{status}")
+    }
+}
+
+/// how a plugin's remote compares to the default shorthand of the same name, the remotes are
+/// normalized to "host/path"
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShorthandComparison {
+    /// the remote is the shorthand's repo
+    Match,
+    /// the remote is on another host than the shorthand, e.g.: a mirror
+    DifferentHost { remote: String, shorthand: String },
+    /// the remote is another repo on the shorthand's host, e.g.: a fork
+    DifferentPath { remote: String, shorthand: String },
+}
+
+impl Display for ShorthandComparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Match => write!(f, "match"),
+            Self::DifferentHost { remote, shorthand } => {
+                write!(f, "different-host: {remote} instead of {shorthand}")
+            }
+            Self::DifferentPath { remote, shorthand } => {
+                write!(f, "different-path: {remote} instead of {shorthand}")
+            }
+        }
+    }
+}
+
 pub type APlugin = Box<dyn Plugin>;
 pub type PluginMap = BTreeMap<String, APlugin>;
 pub type PluginList = Vec<APlugin>;
@@ -69,6 +500,240 @@ pub trait Plugin: Debug + Send {
     fn current_abbrev_ref(&self) -> eyre::Result<Option<String>>;
     fn current_sha_short(&self) -> eyre::Result<Option<String>>;
     fn is_installed(&self) -> bool;
+    /// name prefixed with the plugin type, e.g.: "core:node" or "asdf:node"
+    fn canonical_name(&self) -> String {
+        format!("{}:{}", self.get_plugin_type(), self.name())
+    }
+    /// local changes in the plugin's working tree, e.g.: hand-edited scripts.
+    /// None if the plugin is not installed from a git repo
+    fn git_status(&self) -> eyre::Result<Option<GitStatus>> {
+        Ok(None)
+    }
+    /// recommended default version and required env from the plugin's mise.plugin.toml
+    fn hints(&self) -> eyre::Result<MisePluginTomlHints> {
+        Ok(Default::default())
+    }
+    /// boolean settings the plugin's mise.plugin.toml says need to be enabled to use it, e.g.:
+    /// "experimental". Only used to advise users, they are never enabled automatically
+    fn required_settings(&self) -> eyre::Result<Vec<String>> {
+        Ok(self.hints()?.required_settings)
+    }
+    /// why the last install of the plugin failed, None unless it failed since it last installed
+    fn last_error(&self) -> eyre::Result<Option<PluginError>> {
+        Ok(None)
+    }
+    /// hash of the plugin's bin/ scripts, used to notice when an update changes what the plugin
+    /// runs. None if the plugin has no scripts
+    fn scripts_hash(&self) -> eyre::Result<Option<String>> {
+        Ok(None)
+    }
+    /// every script mise may run from the plugin and whether the plugin has it, e.g.: to review
+    /// them before trusting the plugin. Empty for plugins without scripts
+    fn list_hooks(&self) -> eyre::Result<Vec<PluginHook>> {
+        Ok(vec![])
+    }
+    /// the commands installing the plugin would run (clone, checkout, hooks, etc.) in order,
+    /// without running them, e.g.: to debug a plugin's install. Empty for plugins that aren't
+    /// installed with commands
+    fn install_dry_run(&self) -> eyre::Result<Vec<PluginCommand>> {
+        Ok(vec![])
+    }
+    /// refspec of the unmerged PR or branch the plugin was installed from with
+    /// `mise plugins install --pr/--branch`, None if it wasn't
+    fn dev_ref(&self) -> Option<String> {
+        None
+    }
+    /// the same trust decision made when installing the plugin, without installing it
+    fn trust_status(&self) -> TrustStatus {
+        if self.get_plugin_type() == PluginType::Core {
+            return TrustStatus::Trusted("core plugin".into());
+        }
+        if let Some(refspec) = self.dev_ref() {
+            return TrustStatus::Development(format!("installed from {refspec}"));
+        }
+        match self.get_remote_url() {
+            Ok(Some(url)) => backend::asdf::trust_status(self.name(), &url),
+            Ok(None) => TrustStatus::Unknown("no remote url".into()),
+            Err(err) => TrustStatus::Unknown(format!("{err:#}")),
+        }
+    }
+    /// compares the plugin's remote to the default shorthand it is named after to notice plugins
+    /// that drifted from the official source. None for core plugins, plugins that aren't named
+    /// after a default shorthand and plugins without a remote url
+    fn compare_to_shorthand(&self) -> eyre::Result<Option<ShorthandComparison>> {
+        if self.get_plugin_type() == PluginType::Core {
+            return Ok(None);
+        }
+        match self.get_remote_url()? {
+            Some(url) => backend::asdf::compare_to_shorthand(self.name(), &url),
+            None => Ok(None),
+        }
+    }
+    /// points the plugin's remote at the url its shorthand has now if it was installed from the
+    /// shorthand and the shorthand moved since, e.g.: after a plugin was transferred to another
+    /// org. Plugins whose remote was changed by hand are left alone. Returns the new url if the
+    /// remote was changed
+    fn upgrade_shorthand(&self) -> eyre::Result<Option<String>> {
+        Ok(None)
+    }
+    /// stable, uncolored one-line summary of the plugin meant for grep-friendly output
+    /// e.g.: "asdf:tiny type=asdf installed=true ref=main sha=1234abc url=https://github.com/mise-plugins/mise-tiny"
+    fn describe(&self) -> String {
+        let or_dash = |r: eyre::Result<Option<String>>| {
+            r.unwrap_or_else(|e| {
+                warn!("{}: {e:?}", self.name());
+                None
+            })
+            .unwrap_or_else(|| "-".to_string())
+        };
+        format!(
+            "{} type={} installed={} ref={} sha={} url={}",
+            self.canonical_name(),
+            self.get_plugin_type(),
+            self.is_installed(),
+            or_dash(self.current_abbrev_ref()),
+            or_dash(self.current_sha_short()),
+            or_dash(self.get_remote_url()),
+        )
+    }
+    /// the backend arg of the plugin's tools, its paths are where their versions are installed
+    /// and cached, e.g.: ~/.local/share/mise/installs/<kebab-cased name>
+    fn backend_arg(&self) -> BackendArg {
+        BackendArg::new(BackendType::Asdf, self.name())
+    }
+    /// ~/.local/share/mise/installs/<name>/<version>
+    fn install_path_for(&self, version: &str) -> PathBuf {
+        self.backend_arg()
+            .installs_path
+            .join(ToolVersion::pathname(version))
+    }
+    /// where `version` was installed from, recorded by the backend when it was installed. None if
+    /// it wasn't recorded, e.g.: for versions installed by older versions of mise
+    fn provenance(&self, version: &str) -> eyre::Result<Option<Provenance>> {
+        let path = self.install_path_for(version).join(PROVENANCE_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&file::read_to_string(&path)?)?))
+    }
+    /// number of completed installs in ~/.local/share/mise/installs/<name>
+    fn current_version_count(&self) -> eyre::Result<usize> {
+        let BackendArg {
+            installs_path,
+            cache_path,
+            ..
+        } = self.backend_arg();
+        let count = file::dir_subdirs(&installs_path)?
+            .into_iter()
+            .filter(|v| !v.starts_with('.'))
+            .filter(|v| !is_runtime_symlink(&installs_path.join(v)))
+            .filter(|v| !installs_path.join(v).join("incomplete").exists())
+            .filter(|v| !cache_path.join(v).join("incomplete").exists())
+            .count();
+        Ok(count)
+    }
+    /// moves installed versions left in the layout of older mise versions to where they are
+    /// looked for now so they aren't "installed but not detected", e.g.: for `mise doctor --fix`.
+    /// Returns what was changed, running it again changes nothing
+    fn normalize_installed_layout(&self) -> eyre::Result<Vec<String>> {
+        let installs_path = self.backend_arg().installs_path;
+        normalize_installed_layout(&dirs::INSTALLS.join(self.name()), &installs_path)
+    }
+    /// points symlinks in the plugin's installs that broke because the mise data dir moved (e.g.:
+    /// restored from a backup on another machine) at the same path under the current one, e.g.:
+    /// for `mise doctor --fix`. Returns what was changed, links that can't be fixed are warned about
+    fn relink(&self) -> eyre::Result<Vec<String>> {
+        let installs_path = self.backend_arg().installs_path;
+        relink(&installs_path, &dirs::INSTALLS)
+    }
+    /// total size in bytes of ~/.local/share/mise/installs/<name>, symlinks are not followed
+    fn size_on_disk(&self) -> eyre::Result<u64> {
+        let installs_path = self.backend_arg().installs_path;
+        if !installs_path.exists() {
+            return Ok(0);
+        }
+        let mut size = 0;
+        for entry in walkdir::WalkDir::new(installs_path) {
+            let metadata = entry?.metadata()?;
+            if metadata.is_file() {
+                size += metadata.len();
+            }
+        }
+        Ok(size)
+    }
+    /// checks that the plugin's remote url (or its shorthand url if it is not installed) is still
+    /// reachable and whether it redirects somewhere else, used to flag stale shorthands
+    fn remote_url_redirect_check(&self) -> eyre::Result<Option<RemoteUrlCheck>> {
+        let url = match self.get_remote_url()? {
+            Some(url) => url,
+            None => match get_shorthands(&Settings::get()).get(self.name()) {
+                Some(url) => url.clone(),
+                None => return Ok(None),
+            },
+        };
+        Ok(Some(RemoteUrlCheck::check(&url)))
+    }
+    /// true if the plugin's remote is a GitHub repo that has been archived so it won't get updates
+    /// anymore. None if it can't be told, e.g.: without GITHUB_API_TOKEN (to not run into the
+    /// API's rate limit), for remotes not on GitHub or if the API isn't reachable
+    fn upstream_archived(&self) -> Option<bool> {
+        env::GITHUB_API_TOKEN.as_ref()?;
+        let url = self.get_remote_url().ok().flatten()?;
+        let repo = github::repo_from_url(&url)?;
+        match github::get_repo(&repo) {
+            Ok(repo) => Some(repo.archived),
+            Err(err) => {
+                debug!("failed to check if {repo} is archived: {err:#}");
+                None
+            }
+        }
+    }
+    /// hides the plugin from `list` (and its tools from toolsets) by adding it to `disable_tools`
+    /// in the global config. Its files and installed versions are kept. Takes effect the next time
+    /// settings are loaded
+    fn disable(&self) -> eyre::Result<()> {
+        set_disabled(self.name(), true)
+    }
+    /// undoes [`Plugin::disable`]
+    fn enable(&self) -> eyre::Result<()> {
+        set_disabled(self.name(), false)
+    }
+    /// uninstalls every version in ~/.local/share/mise/installs/<name> that is not in `keep`,
+    /// running the backend's uninstall hook for each one. Versions that are symlinks to somewhere
+    /// else (e.g.: from `mise link`) are only unlinked and runtime symlinks left dangling are removed.
+    /// Returns the versions that were pruned.
+    fn prune_versions(&self, keep: &[String], pr: &dyn SingleReport) -> eyre::Result<Vec<String>> {
+        let backend_type = match self.get_plugin_type() {
+            PluginType::Core => BackendType::Core,
+            PluginType::Asdf => BackendType::Asdf,
+        };
+        let backend = backend::get(&BackendArg::new(backend_type, self.name()));
+        let installs_path = &backend.fa().installs_path;
+        let mut pruned = vec![];
+        for v in backend.list_installed_versions()? {
+            if keep.contains(&v) {
+                continue;
+            }
+            let path = installs_path.join(&v);
+            if path.is_symlink() {
+                pr.set_message(format!("unlinking {}", display_path(&path)));
+                file::remove_file(&path)?;
+            } else {
+                let tr = ToolRequest::new(backend.fa().clone(), &v)?;
+                let tv = ToolVersion::new(backend.as_ref(), tr, v.clone());
+                backend.uninstall_version(&tv, pr, false)?;
+            }
+            pr.println(format!("pruned {}@{v}", self.name()));
+            pruned.push(v);
+        }
+        for name in file::dir_subdirs(installs_path)? {
+            let path = installs_path.join(name);
+            if is_runtime_symlink(&path) && !path.exists() {
+                file::remove_file(&path)?;
+            }
+        }
+        Ok(pruned)
+    }
 }
 
 impl Ord for APlugin {
@@ -99,12 +764,31 @@ impl Display for APlugin {
 
 #[cfg(test)]
 mod tests {
-    use pretty_assertions::assert_str_eq;
+    use pretty_assertions::{assert_eq, assert_str_eq};
     use test_log::test;
 
+    use std::collections::BTreeSet;
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use itertools::Itertools;
+    use rayon::prelude::*;
+
     use crate::backend::asdf::Asdf;
     use crate::backend::Backend;
+    use crate::config::Settings;
+    use crate::plugins::asdf_plugin::AsdfPlugin;
+    use crate::plugins::core::CorePlugin;
+    use crate::plugins::{
+        get, hook_scripts, known_tools, with_list_concurrency, Plugin, PluginType, RemoteUrlCheck,
+        ToolSource,
+    };
+    use crate::test;
     use crate::test::reset;
+    use crate::ui::progress_report::QuietReport;
+    use crate::{dirs, env, file};
 
     #[test]
     fn test_exact_match() {
@@ -120,6 +804,219 @@ mod tests {
         assert_str_eq!(version, "3.1.0");
     }
 
+    #[test]
+    fn test_current_version_count() {
+        reset();
+        let installs = dirs::INSTALLS.join("version-count");
+        let cache = dirs::CACHE.join("version-count");
+        for v in ["1.0.0", "2.0.0", "3.0.0"] {
+            file::create_dir_all(installs.join(v)).unwrap();
+        }
+        file::create_dir_all(installs.join(".tmp")).unwrap();
+        file::make_symlink(Path::new("./2.0.0"), &installs.join("latest")).unwrap();
+        file::create_dir_all(cache.join("3.0.0")).unwrap();
+        file::write(cache.join("3.0.0/incomplete"), "").unwrap();
+
+        let plugin = AsdfPlugin::new(String::from("version-count"));
+        assert_eq!(plugin.current_version_count().unwrap(), 2);
+        // installed to the kebab-cased path
+        let snake_case = AsdfPlugin::new(String::from("version_count"));
+        assert_eq!(snake_case.current_version_count().unwrap(), 2);
+
+        file::write(installs.join("1.0.0/bin"), "12345").unwrap();
+        file::write(installs.join("3.0.0/bin"), "123").unwrap();
+        assert_eq!(plugin.size_on_disk().unwrap(), 8);
+        assert_eq!(snake_case.size_on_disk().unwrap(), 8);
+
+        file::remove_all(&installs).unwrap();
+        file::remove_all(&cache).unwrap();
+        assert_eq!(plugin.current_version_count().unwrap(), 0);
+        assert_eq!(plugin.size_on_disk().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_prune_versions() {
+        reset();
+        let plugin_path = dirs::PLUGINS.join("prune-test");
+        let installs = dirs::INSTALLS.join("prune-test");
+        let log = dirs::CACHE.join("prune-test.log");
+        file::create_dir_all(plugin_path.join("bin")).unwrap();
+        file::write(
+            plugin_path.join("bin/uninstall"),
+            format!(
+                "#!/usr/bin/env bash\necho $ASDF_INSTALL_VERSION >> {}\n",
+                log.display()
+            ),
+        )
+        .unwrap();
+        file::make_executable(plugin_path.join("bin/uninstall")).unwrap();
+        for v in ["1.0.0", "2.0.0", "3.0.0"] {
+            file::create_dir_all(installs.join(v)).unwrap();
+        }
+        file::make_symlink(Path::new("./3.0.0"), &installs.join("latest")).unwrap();
+        file::make_symlink(Path::new("./2.0.0"), &installs.join("2")).unwrap();
+        let linked = tempfile::tempdir().unwrap();
+        file::write(linked.path().join("keep-me"), "").unwrap();
+        file::make_symlink(linked.path(), &installs.join("4.0.0")).unwrap();
+
+        let plugin = AsdfPlugin::new(String::from("prune-test"));
+        let pruned = plugin
+            .prune_versions(&["2.0.0".to_string()], &QuietReport::new())
+            .unwrap();
+        assert_eq!(pruned, vec!["1.0.0", "3.0.0", "4.0.0"]);
+        let mut remaining = file::dir_subdirs(&installs).unwrap();
+        remaining.sort();
+        assert_eq!(remaining, vec!["2", "2.0.0"]);
+        assert!(linked.path().join("keep-me").exists());
+        assert_str_eq!(file::read_to_string(&log).unwrap(), "1.0.0\n3.0.0\n");
+
+        file::remove_all(&plugin_path).unwrap();
+        file::remove_all(&installs).unwrap();
+        file::remove_file(&log).unwrap();
+    }
+
+    #[test]
+    fn test_remote_url_redirect_check() {
+        reset();
+        let remote = tempfile::tempdir().unwrap();
+        test::git_init(remote.path(), "init");
+        let url = format!("file://{}", remote.path().display());
+        assert_eq!(
+            RemoteUrlCheck::check(&url),
+            RemoteUrlCheck {
+                url: url.clone(),
+                reachable: true,
+                redirect: None,
+            }
+        );
+        let missing = format!("file://{}", remote.path().join("missing").display());
+        assert!(!RemoteUrlCheck::check(&missing).reachable);
+
+        let reachable = test::serve(vec![], 1);
+        assert_eq!(
+            RemoteUrlCheck::check(&reachable),
+            RemoteUrlCheck {
+                url: reachable.clone(),
+                reachable: true,
+                redirect: None,
+            }
+        );
+        let unreachable = test::serve(vec![], 0);
+        assert!(!RemoteUrlCheck::check(&unreachable).reachable);
+
+        let plugin = AsdfPlugin::new(String::from("not-a-shorthand"));
+        assert_eq!(plugin.remote_url_redirect_check().unwrap(), None);
+    }
+
+    #[test]
+    fn test_describe() {
+        reset();
+        let remote = tempfile::tempdir().unwrap();
+        file::create_dir_all(remote.path().join("bin")).unwrap();
+        test::git_init(remote.path(), "init");
+        let url = format!("file://{}", remote.path().display());
+        assert_cli!("plugin", "install", "describe-test", &url);
+        let sha = cmd!("git", "-C", remote.path(), "rev-parse", "--short", "HEAD")
+            .read()
+            .unwrap();
+
+        let plugin = AsdfPlugin::new(String::from("describe-test"));
+        assert_eq!(
+            plugin.describe(),
+            format!("asdf:describe-test type=asdf installed=true ref=main sha={sha} url={url}")
+        );
+        assert_cli!("plugin", "uninstall", "describe-test");
+        assert_eq!(
+            plugin.describe(),
+            "asdf:describe-test type=asdf installed=false ref=- sha=- url=-"
+        );
+    }
+
+    #[test]
+    fn test_list_hooks() {
+        reset();
+        let plugin_path = dirs::PLUGINS.join("list-hooks");
+        for script in ["list-all", "install", "post-plugin-update"] {
+            file::create_dir_all(plugin_path.join("bin")).unwrap();
+            file::write(plugin_path.join("bin").join(script), "#!/bin/sh\n").unwrap();
+        }
+        test::git_init(&plugin_path, "init");
+        let plugin = AsdfPlugin::new("list-hooks".into());
+
+        let hooks = plugin.list_hooks().unwrap();
+        file::remove_all(&plugin_path).unwrap();
+        let not_installed = plugin.list_hooks().unwrap_err();
+
+        let present = hooks.iter().filter(|h| h.present).collect_vec();
+        assert_eq!(
+            present.iter().map(|h| h.name.as_str()).collect_vec(),
+            vec!["post-plugin-update", "list-all", "install"]
+        );
+        assert_eq!(present[2].path, plugin_path.join("bin/install"));
+        assert_eq!(hooks.len(), hook_scripts().len());
+        assert!(hooks
+            .iter()
+            .any(|h| h.name == "pre-plugin-remove" && !h.present));
+        assert_eq!(
+            not_installed.to_string(),
+            "[list-hooks] plugin not installed"
+        );
+        assert!(CorePlugin::list()[0].list_hooks().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tags_matching() {
+        reset();
+        let remote = tempfile::tempdir().unwrap();
+        file::create_dir_all(remote.path().join("bin")).unwrap();
+        test::git_init(remote.path(), "init");
+        for tag in ["v1.10.0", "v1.2.0", "v2.0.0", "v1.0.0", "1.5.0"] {
+            cmd!("git", "-C", remote.path(), "tag", tag).run().unwrap();
+        }
+        let mut plugin = AsdfPlugin::new(String::from("tags-matching"));
+        plugin.repo_url = Some(remote.path().to_string_lossy().to_string());
+
+        let v1 = plugin.tags_matching("v1.*");
+        cmd!("git", "-C", remote.path(), "tag", "v1.3.0")
+            .run()
+            .unwrap();
+        let cached = plugin.tags_matching("v1.*");
+        let all = plugin.tags_matching("*");
+        let invalid = plugin.tags_matching("v1.[");
+        file::remove_all(dirs::CACHE.join("tags-matching")).unwrap();
+
+        assert_eq!(v1.unwrap(), vec!["v1.0.0", "v1.2.0", "v1.10.0"]);
+        assert_eq!(cached.unwrap(), vec!["v1.0.0", "v1.2.0", "v1.10.0"]);
+        assert_eq!(
+            all.unwrap(),
+            vec!["v1.0.0", "v1.2.0", "1.5.0", "v1.10.0", "v2.0.0"]
+        );
+        assert_eq!(invalid.unwrap_err().to_string(), "invalid tag glob: v1.[");
+    }
+
+    #[test]
+    fn test_canonical_name() {
+        reset();
+        assert_eq!(AsdfPlugin::new("tiny".into()).canonical_name(), "asdf:tiny");
+        assert_eq!(CorePlugin::new("node".into()).canonical_name(), "core:node");
+
+        assert_eq!(get("node").get_plugin_type(), PluginType::Core);
+        assert_eq!(get("core:node").get_plugin_type(), PluginType::Core);
+        let asdf_node = get("asdf:node");
+        assert_eq!(asdf_node.get_plugin_type(), PluginType::Asdf);
+        assert_eq!(asdf_node.name(), "node");
+        assert_eq!(asdf_node.id(), "asdf:node");
+        assert_eq!(
+            asdf_node.fa().installs_path,
+            dirs::INSTALLS.join("asdf-node")
+        );
+        assert_eq!(get("node").fa().installs_path, dirs::INSTALLS.join("node"));
+        assert!(Arc::ptr_eq(&asdf_node, &get("asdf:node")));
+        let tiny = get("asdf:tiny");
+        assert_eq!(tiny.get_plugin_type(), PluginType::Asdf);
+        assert!(tiny.is_installed());
+    }
+
     #[test]
     fn test_latest_stable() {
         reset();
@@ -127,4 +1024,251 @@ mod tests {
         let version = plugin.latest_version(None).unwrap().unwrap();
         assert_str_eq!(version, "2.0.0");
     }
+
+    #[test]
+    fn test_with_list_concurrency() {
+        reset();
+        let default = with_list_concurrency(rayon::current_num_threads).unwrap();
+        assert_eq!(default, rayon::current_num_threads());
+
+        std::env::set_var("MISE_LIST_CONCURRENCY", "2");
+        Settings::reset(None);
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+        let threads = with_list_concurrency(|| {
+            (0..16).into_par_iter().for_each(|_| {
+                let n = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(n, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(5));
+                running.fetch_sub(1, Ordering::SeqCst);
+            });
+            rayon::current_num_threads()
+        })
+        .unwrap();
+        std::env::remove_var("MISE_LIST_CONCURRENCY");
+        Settings::reset(None);
+        assert_eq!(threads, 2);
+        assert!(max_running.into_inner() <= 2);
+    }
+
+    #[test]
+    fn test_disable() {
+        reset();
+        let is_listed = || {
+            Settings::reset(None);
+            let plugins = AsdfPlugin::list().unwrap();
+            plugins.iter().any(|p| p.name() == "tiny")
+        };
+        let config = file::read_to_string(&*env::MISE_GLOBAL_CONFIG_FILE).unwrap();
+        let plugin = AsdfPlugin::new("tiny".into());
+
+        assert!(is_listed());
+        plugin.disable().unwrap();
+        plugin.disable().unwrap();
+        assert!(!is_listed());
+        assert!(plugin.repo.dir.exists());
+        plugin.enable().unwrap();
+        assert!(is_listed());
+        let restored = file::read_to_string(&*env::MISE_GLOBAL_CONFIG_FILE).unwrap();
+        assert_str_eq!(restored, config);
+        reset();
+    }
+
+    #[test]
+    fn test_known_tools() {
+        reset();
+        let shorthands = tempfile::NamedTempFile::new().unwrap();
+        file::write(
+            shorthands.path(),
+            "node = \"https://github.com/someone/asdf-node\"\nmy-tool = \"https://github.com/someone/asdf-my-tool\"\n",
+        )
+        .unwrap();
+        env::set_var("MISE_SHORTHANDS_FILE", shorthands.path());
+        env::set_var("MISE_DISABLE_DEFAULT_SHORTHANDS", "1");
+        Settings::reset(None);
+        let tools = known_tools();
+        env::remove_var("MISE_SHORTHANDS_FILE");
+        env::remove_var("MISE_DISABLE_DEFAULT_SHORTHANDS");
+        Settings::reset(None);
+
+        let tools = tools
+            .unwrap()
+            .into_iter()
+            .map(|(name, sources)| format!("{name}: {}", sources.iter().join(", ")))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tools,
+            vec![
+                "bun: core",
+                "deno: core",
+                "dummy: installed-asdf",
+                "erlang: core",
+                "go: core",
+                "java: core",
+                "my-tool: shorthand",
+                "node: core, shorthand",
+                "python: core",
+                "ruby: core",
+                "tiny: installed-asdf",
+                "zig: core",
+            ]
+        );
+        let tools = known_tools().unwrap();
+        assert_eq!(
+            tools["tiny"],
+            BTreeSet::from([ToolSource::InstalledAsdf, ToolSource::Shorthand])
+        );
+    }
+
+    #[test]
+    fn test_git_head_cache() {
+        reset();
+        let plugin_path = dirs::PLUGINS.join("git-head-cache");
+        file::create_dir_all(&plugin_path).unwrap();
+        let short = |sha: String| {
+            cmd!("git", "-C", &plugin_path, "rev-parse", "--short", sha)
+                .read()
+                .unwrap()
+        };
+        let first = short(test::git_init(&plugin_path, "first"));
+        let plugin = AsdfPlugin::new("git-head-cache".into());
+        plugin.invalidate_git_head_cache().unwrap();
+        assert_eq!(plugin.current_sha_short().unwrap(), Some(first.clone()));
+
+        let cached = AsdfPlugin::new("git-head-cache".into())
+            .current_sha_short()
+            .unwrap();
+
+        // committing on the current branch updates the branch's ref but not .git/HEAD
+        std::thread::sleep(Duration::from_millis(10));
+        let second = short(test::git_commit(&plugin_path, "second"));
+        let committed = AsdfPlugin::new("git-head-cache".into())
+            .current_sha_short()
+            .unwrap();
+
+        // switching branches rewrites .git/HEAD which makes git get queried again
+        std::thread::sleep(Duration::from_millis(10));
+        cmd!("git", "-C", &plugin_path, "checkout", "-q", "-b", "other")
+            .run()
+            .unwrap();
+        let changed = AsdfPlugin::new("git-head-cache".into());
+        let (sha, abbrev_ref) = (
+            changed.current_sha_short().unwrap(),
+            changed.current_abbrev_ref().unwrap(),
+        );
+        file::remove_all(&plugin_path).unwrap();
+        file::remove_all(dirs::CACHE.join("git-head-cache")).unwrap();
+
+        assert_eq!(cached, Some(first));
+        assert_eq!(committed, Some(second.clone()));
+        assert_eq!(sha, Some(second));
+        assert_eq!(abbrev_ref, Some("other".into()));
+    }
+
+    #[test]
+    fn test_scripts_hash() {
+        reset();
+        let plugin_path = dirs::PLUGINS.join("scripts-hash");
+        file::create_dir_all(&plugin_path).unwrap();
+        let plugin = AsdfPlugin::new("scripts-hash".into());
+        let no_scripts = plugin.scripts_hash().unwrap();
+        file::create_dir_all(plugin_path.join("bin")).unwrap();
+        file::write(plugin_path.join("bin/list-all"), "echo 1.0.0").unwrap();
+        let original = plugin.scripts_hash().unwrap();
+        let again = plugin.scripts_hash().unwrap();
+        file::write(plugin_path.join("bin/list-all"), "echo 2.0.0").unwrap();
+        let modified = plugin.scripts_hash().unwrap();
+        file::remove_all(&plugin_path).unwrap();
+
+        assert_eq!(no_scripts, None);
+        assert!(original.is_some());
+        assert_eq!(original, again);
+        assert_ne!(original, modified);
+    }
+
+    #[test]
+    fn test_normalize_installed_layout() {
+        reset();
+        let legacy = dirs::INSTALLS.join("legacy_layout");
+        let installs = dirs::INSTALLS.join("legacy-layout");
+        for dir in [
+            legacy.join("1.0.0/bin"),
+            legacy.join("2.0.0/old"),
+            installs.join("2.0.0/new"),
+            installs.join("ref:main/bin"),
+        ] {
+            file::create_dir_all(dir).unwrap();
+        }
+        let plugin = AsdfPlugin::new("legacy_layout".into());
+
+        let changes = plugin.normalize_installed_layout().unwrap();
+        let again = plugin.normalize_installed_layout().unwrap();
+        let versions = file::dir_subdirs(&installs).unwrap().into_iter().sorted();
+        let kept = installs.join("2.0.0/new").exists();
+        let legacy_left = file::dir_subdirs(&legacy).unwrap();
+        file::remove_all(&legacy).unwrap();
+        file::remove_all(&installs).unwrap();
+
+        assert_eq!(
+            changes,
+            vec![
+                "moved ~/data/installs/legacy_layout/1.0.0 to ~/data/installs/legacy-layout/1.0.0",
+                "moved ~/data/installs/legacy-layout/ref:main to ~/data/installs/legacy-layout/ref-main",
+            ]
+        );
+        assert_eq!(again, Vec::<String>::new());
+        assert_eq!(versions.collect_vec(), vec!["1.0.0", "2.0.0", "ref-main"]);
+        assert!(kept);
+        assert_eq!(legacy_left, vec!["2.0.0"]);
+    }
+
+    #[test]
+    fn test_relink() {
+        reset();
+        let installs = dirs::INSTALLS.join("relink");
+        let bin = installs.join("1.0.0/bin");
+        file::create_dir_all(&bin).unwrap();
+        file::write(bin.join("relink"), "").unwrap();
+        let moved = Path::new("/old/home/.local/share/mise/installs/relink/1.0.0/bin/relink");
+        file::make_symlink(moved, &installs.join("1.0.0/relink")).unwrap();
+        file::make_symlink(Path::new("/missing/installs/gone"), &installs.join("gone")).unwrap();
+        file::make_symlink(Path::new("./1.0.0"), &installs.join("1")).unwrap();
+        let plugin = AsdfPlugin::new("relink".into());
+
+        let changes = plugin.relink().unwrap();
+        let again = plugin.relink().unwrap();
+        let target = installs.join("1.0.0/relink").read_link().unwrap();
+        let runtime_symlink = installs.join("1").read_link().unwrap();
+        file::remove_all(&installs).unwrap();
+
+        assert_eq!(
+            changes,
+            vec![
+                "relinked ~/data/installs/relink/1.0.0/relink to ~/data/installs/relink/1.0.0/bin/relink"
+            ]
+        );
+        assert_eq!(again, Vec::<String>::new());
+        assert_eq!(target, bin.join("relink"));
+        assert_eq!(runtime_symlink, PathBuf::from("./1.0.0"));
+    }
+
+    #[test]
+    fn test_hints() {
+        reset();
+        let plugin_path = dirs::PLUGINS.join("hints");
+        file::create_dir_all(&plugin_path).unwrap();
+        let plugin = AsdfPlugin::new("hints".into());
+        let empty = plugin.hints().unwrap();
+        file::write(
+            plugin_path.join("mise.plugin.toml"),
+            "[hints]\ndefault-version = \"1.2\"\nrequired-env = [\"HINTS_TOKEN\"]\n",
+        )
+        .unwrap();
+        let hints = plugin.hints().unwrap();
+        file::remove_all(&plugin_path).unwrap();
+
+        assert_eq!(empty, Default::default());
+        assert_eq!(hints.default_version, Some("1.2".into()));
+        assert_eq!(hints.required_env, vec!["HINTS_TOKEN"]);
+    }
 }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::ffi::OsString;
 use std::fmt;
 use std::fmt::{Display, Formatter};
@@ -32,6 +32,8 @@ pub enum Script {
     LatestStable,
     ListAliases,
     ListAll,
+    /// versions newer than the given one for plugins that can fetch only those
+    ListAllAfter(String),
     ListLegacyFilenames,
     ParseLegacyFile(String),
 
@@ -50,6 +52,7 @@ impl Display for Script {
             // Plugin
             Script::LatestStable => write!(f, "latest-stable"),
             Script::ListAll => write!(f, "list-all"),
+            Script::ListAllAfter(_) => write!(f, "list-all-after"),
             Script::ListLegacyFilenames => write!(f, "list-legacy-filenames"),
             Script::ListAliases => write!(f, "list-aliases"),
             Script::ParseLegacyFile(_) => write!(f, "parse-legacy-file"),
@@ -66,12 +69,43 @@ impl Display for Script {
     }
 }
 
+/// ambient env vars passed to plugin scripts when `isolated_plugin_env` is enabled
+/// more can be added with `isolated_plugin_env_allow`
+const ISOLATED_ENV_ALLOWLIST: &[&str] = &[
+    "GITHUB_API_TOKEN",
+    "GITHUB_TOKEN",
+    "HOME",
+    "HTTPS_PROXY",
+    "HTTP_PROXY",
+    "LANG",
+    "LC_ALL",
+    "LOGNAME",
+    "NO_PROXY",
+    "PATH",
+    "SHELL",
+    "SSL_CERT_DIR",
+    "SSL_CERT_FILE",
+    "TERM",
+    "TMPDIR",
+    "USER",
+    "https_proxy",
+    "http_proxy",
+    "no_proxy",
+];
+
 static INITIAL_ENV: Lazy<HashMap<OsString, OsString>> = Lazy::new(|| {
-    let settings = Settings::get();
     let mut env: HashMap<OsString, OsString> = env::PRISTINE_ENV
         .iter()
         .map(|(k, v)| (k.into(), v.into()))
         .collect();
+    env.extend(MISE_ENV.clone());
+    env
+});
+
+/// env vars mise itself sets for plugin scripts
+static MISE_ENV: Lazy<HashMap<OsString, OsString>> = Lazy::new(|| {
+    let settings = Settings::get();
+    let mut env = HashMap::new();
     if settings.trace {
         env.insert("MISE_TRACE".into(), "1".into());
     }
@@ -98,7 +132,11 @@ static INITIAL_ENV: Lazy<HashMap<OsString, OsString>> = Lazy::new(|| {
 
 impl ScriptManager {
     pub fn new(plugin_path: PathBuf) -> Self {
-        let mut env = INITIAL_ENV.clone();
+        let settings = Settings::get();
+        let mut env = match settings.isolated_plugin_env {
+            true => isolated_env(&settings.isolated_plugin_env_allow),
+            false => INITIAL_ENV.clone(),
+        };
         if let Some(failure) = env::var_os("MISE_FAILURE") {
             // used for testing failure cases
             env.insert("MISE_FAILURE".into(), failure);
@@ -137,6 +175,7 @@ impl ScriptManager {
     pub fn cmd(&self, script: &Script) -> Expression {
         let args = match script {
             Script::ParseLegacyFile(filename) => vec![filename.clone()],
+            Script::ListAllAfter(version) => vec![version.clone()],
             Script::RunExternalCommand(_, args) => args.clone(),
             _ => vec![],
         };
@@ -181,12 +220,24 @@ impl ScriptManager {
     }
 }
 
+fn isolated_env(allow: &BTreeSet<String>) -> HashMap<OsString, OsString> {
+    let mut env: HashMap<OsString, OsString> = env::PRISTINE_ENV
+        .iter()
+        .filter(|(k, _)| ISOLATED_ENV_ALLOWLIST.contains(&k.as_str()) || allow.contains(*k))
+        .map(|(k, v)| (k.into(), v.into()))
+        .collect();
+    env.extend(MISE_ENV.clone());
+    env
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
     use test_log::test;
 
+    use crate::file;
     use crate::test::reset;
+    use crate::ui::progress_report::QuietReport;
 
     use super::*;
 
@@ -198,6 +249,42 @@ mod tests {
         assert_eq!(script_manager.plugin_path, plugin_path);
     }
 
+    #[test]
+    fn test_isolated_plugin_env() {
+        reset();
+        let plugin_path = tempfile::tempdir().unwrap();
+        let out = plugin_path.path().join("out");
+        file::create_dir_all(plugin_path.path().join("bin")).unwrap();
+        file::write(
+            plugin_path.path().join("bin/install"),
+            format!(
+                "#!/usr/bin/env bash\necho \"${{MISE_USE_TOML:-unset}} ${{HOME:-unset}}\" > {}\n",
+                out.display()
+            ),
+        )
+        .unwrap();
+        file::make_executable(plugin_path.path().join("bin/install")).unwrap();
+        let run = || {
+            Settings::reset(None);
+            let sm = ScriptManager::new(plugin_path.path().to_path_buf());
+            sm.run_by_line(&Script::Install, &QuietReport::new())
+                .unwrap();
+            file::read_to_string(&out).unwrap()
+        };
+        let home = env::HOME.display();
+        assert_eq!(run(), format!("0 {home}\n"));
+
+        env::set_var("MISE_ISOLATED_PLUGIN_ENV", "1");
+        assert_eq!(run(), format!("unset {home}\n"));
+
+        env::set_var("MISE_ISOLATED_PLUGIN_ENV_ALLOW", "MISE_USE_TOML");
+        assert_eq!(run(), format!("0 {home}\n"));
+
+        env::remove_var("MISE_ISOLATED_PLUGIN_ENV");
+        env::remove_var("MISE_ISOLATED_PLUGIN_ENV_ALLOW");
+        Settings::reset(None);
+    }
+
     #[test]
     fn test_get_script_path() {
         reset();
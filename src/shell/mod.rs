@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use std::path::Path;
 
@@ -55,6 +56,10 @@ pub trait Shell {
     fn set_env(&self, k: &str, v: &str) -> String;
     fn prepend_env(&self, k: &str, v: &str) -> String;
     fn unset_env(&self, k: &str) -> String;
+    /// a script setting every var in `env` when sourced, values are quoted as needed
+    fn export_env(&self, env: &BTreeMap<String, String>) -> String {
+        env.iter().map(|(k, v)| self.set_env(k, v)).collect()
+    }
 }
 
 pub fn get_shell(shell: Option<ShellType>) -> Option<Box<dyn Shell>> {
@@ -67,3 +72,52 @@ pub fn get_shell(shell: Option<ShellType>) -> Option<Box<dyn Shell>> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_str_eq;
+
+    use super::*;
+
+    fn export_env(shell: ShellType) -> String {
+        let env = BTreeMap::from([
+            ("FOO".to_string(), "1".to_string()),
+            (
+                "GREETING".to_string(),
+                r#"it's a "quoted" value"#.to_string(),
+            ),
+        ]);
+        get_shell(Some(shell)).unwrap().export_env(&env)
+    }
+
+    #[test]
+    fn test_export_env_bash() {
+        let script = export_env(ShellType::Bash);
+        assert_str_eq!(
+            script,
+            "export FOO=1\nexport GREETING='it'\\''s a \"quoted\" value'\n"
+        );
+        let out = cmd!(
+            "bash",
+            "--norc",
+            "-c",
+            format!("{script}printf %s \"$GREETING\"")
+        )
+        .read()
+        .unwrap();
+        assert_str_eq!(out, r#"it's a "quoted" value"#);
+    }
+
+    #[test]
+    fn test_export_env_zsh() {
+        assert_str_eq!(export_env(ShellType::Zsh), export_env(ShellType::Bash));
+    }
+
+    #[test]
+    fn test_export_env_fish() {
+        assert_str_eq!(
+            export_env(ShellType::Fish),
+            "set -gx FOO 1\nset -gx GREETING 'it'\\''s a \"quoted\" value'\n"
+        );
+    }
+}
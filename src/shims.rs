@@ -113,7 +113,7 @@ pub fn reshim(ts: &Toolset) -> Result<()> {
         remove_all(&symlink_path)?;
     }
     for plugin in backend::list() {
-        match dirs::PLUGINS.join(plugin.id()).join("shims").read_dir() {
+        match dirs::plugin_path(plugin.id()).join("shims").read_dir() {
             Ok(files) => {
                 for bin in files {
                     let bin = bin?;
@@ -216,7 +216,21 @@ fn list_tool_bins(t: Arc<dyn Backend>, tv: &ToolVersion) -> Result<Vec<String>>
         .collect())
 }
 
-fn make_shim(target: &Path, shim: &Path) -> Result<()> {
+/// removes the shims `reshim` made for the scripts in a plugin's `shims` dir, unless the shim
+/// has since been replaced by something else
+pub fn remove_plugin_shims(plugin_shims_dir: &Path, bins: &[String]) -> Result<()> {
+    for bin in bins {
+        let shim = dirs::SHIMS.join(bin);
+        let exec = format!("mise x -- {} ", plugin_shims_dir.join(bin).display());
+        if !shim.is_symlink() && file::read_to_string(&shim).is_ok_and(|s| s.contains(&exec)) {
+            trace!("removing plugin shim {}", display_path(&shim));
+            file::remove_file(&shim)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn make_shim(target: &Path, shim: &Path) -> Result<()> {
     if shim.exists() {
         file::remove_file(shim)?;
     }
@@ -32,6 +32,23 @@ pub fn get_shorthands(settings: &Settings) -> Shorthands {
     shorthands
 }
 
+/// expands `gitea:<host>/<owner>/<repo>` and `forgejo:<host>/<owner>/<repo>` into the https git url
+/// of that repo, keeping any `#ref`. Other urls are returned unchanged.
+pub fn expand_git_host_shorthand(url: &str) -> String {
+    for scheme in ["gitea:", "forgejo:"] {
+        if let Some(repo) = url.strip_prefix(scheme) {
+            let (repo, ref_) = match repo.split_once('#') {
+                Some((repo, ref_)) => (repo, format!("#{ref_}")),
+                None => (repo, String::new()),
+            };
+            let repo = repo.trim_start_matches("//").trim_end_matches('/');
+            let repo = repo.trim_end_matches(".git");
+            return format!("https://{repo}.git{ref_}");
+        }
+    }
+    url.to_string()
+}
+
 fn parse_shorthands_file(mut f: PathBuf) -> Result<Shorthands> {
     if f.starts_with("~") {
         f = dirs::HOME.join(f.strip_prefix("~")?);
@@ -74,6 +91,22 @@ mod tests {
         assert_str_eq!(shorthands["xxxxxx"], "https://xxxxxx");
     }
 
+    #[test]
+    fn test_expand_git_host_shorthand() {
+        assert_str_eq!(
+            expand_git_host_shorthand("gitea:codeberg.org/someone/asdf-foo"),
+            "https://codeberg.org/someone/asdf-foo.git"
+        );
+        assert_str_eq!(
+            expand_git_host_shorthand("forgejo:git.example.com/someone/asdf-foo.git#v1.0.0"),
+            "https://git.example.com/someone/asdf-foo.git#v1.0.0"
+        );
+        assert_str_eq!(
+            expand_git_host_shorthand("https://github.com/mise-plugins/mise-tiny.git"),
+            "https://github.com/mise-plugins/mise-tiny.git"
+        );
+    }
+
     #[test]
     fn test_get_shorthands_missing_file() {
         reset();
@@ -1,5 +1,10 @@
 use std::env::join_paths;
-use std::path::PathBuf;
+use std::ffi::OsStr;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
 
 use color_eyre::{Help, SectionExt};
 use indoc::indoc;
@@ -153,6 +158,41 @@ echo "TEST_BUILDSCRIPT_ENV_VAR: $TEST_BUILDSCRIPT_ENV_VAR" > test-build-output.t
     assert_cli!("install");
 }
 
+/// sets env vars for the rest of a test and removes them, along with the paths to clean up, when
+/// dropped so a test that fails partway doesn't leak them into the tests that run after it
+#[must_use]
+#[derive(Default)]
+pub struct TestGuard {
+    vars: Vec<&'static str>,
+    paths: Vec<PathBuf>,
+}
+
+impl TestGuard {
+    pub fn with_env(mut self, key: &'static str, value: impl AsRef<OsStr>) -> Self {
+        env::set_var(key, value);
+        Config::reset();
+        self.vars.push(key);
+        self
+    }
+
+    pub fn with_cleanup(mut self, path: impl Into<PathBuf>) -> Self {
+        self.paths.push(path.into());
+        self
+    }
+}
+
+impl Drop for TestGuard {
+    fn drop(&mut self) {
+        for key in &self.vars {
+            env::remove_var(key);
+        }
+        for path in &self.paths {
+            let _ = file::remove_all(path);
+        }
+        Config::reset();
+    }
+}
+
 pub fn setup_git_repo() {
     cmd!("git", "init", "-b", "trunk").run().unwrap();
     file::write("README.md", "# testing123").unwrap();
@@ -171,6 +211,143 @@ pub fn setup_git_repo() {
     .unwrap();
 }
 
+/// initializes a git repo at `dir` on branch "main", commits its contents, and returns the sha
+pub fn git_init(dir: &Path, message: &str) -> String {
+    cmd!("git", "init", "-q", "-b", "main", dir).run().unwrap();
+    git_commit(dir, message)
+}
+
+/// a git repo of an asdf plugin with the given bin/ scripts
+pub fn plugin_origin(scripts: &[(&str, &str)]) -> tempfile::TempDir {
+    let origin = tempfile::tempdir().unwrap();
+    for (script, body) in scripts {
+        let path = origin.path().join("bin").join(script);
+        file::create_dir_all(path.parent().unwrap()).unwrap();
+        file::write(&path, format!("#!/usr/bin/env bash\n{body}\n")).unwrap();
+        file::make_executable(&path).unwrap();
+    }
+    git_init(origin.path(), "init");
+    origin
+}
+
+/// commits everything in the git repo at `dir` and returns the new sha
+pub fn git_commit(dir: &Path, message: &str) -> String {
+    cmd!("git", "-C", dir, "add", "-A").run().unwrap();
+    cmd!(
+        "git",
+        "-C",
+        dir,
+        "-c",
+        "user.name=ferris",
+        "-c",
+        "user.email=ferris@example.com",
+        "commit",
+        "-q",
+        "--allow-empty",
+        "-m",
+        message
+    )
+    .run()
+    .unwrap();
+    cmd!("git", "-C", dir, "rev-parse", "HEAD").read().unwrap()
+}
+
+/// serves `body` over http on localhost for the next `requests` requests and returns the base url
+pub fn serve(body: Vec<u8>, requests: usize) -> String {
+    let responses = (0..requests).map(|_| Response::ok(body.clone())).collect();
+    serve_responses(responses).0
+}
+
+/// a canned response for [`serve_responses`]
+pub struct Response {
+    status: &'static str,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+    hang: bool,
+    on_request: Option<Box<dyn FnOnce() + Send>>,
+}
+
+impl Response {
+    /// e.g.: `Response::new("429 Too Many Requests")`
+    pub fn new(status: &'static str) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body: vec![],
+            hang: false,
+            on_request: None,
+        }
+    }
+
+    pub fn ok(body: impl Into<Vec<u8>>) -> Self {
+        Self::new("200 OK").with_body(body)
+    }
+
+    /// accepts the request but never responds, e.g.: to hit a client timeout
+    pub fn hang() -> Self {
+        Self {
+            hang: true,
+            ..Self::new("200 OK")
+        }
+    }
+
+    pub fn with_header(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.headers.push((name, value.to_string()));
+        self
+    }
+
+    pub fn with_body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// runs `f` once the request has been read, before responding
+    pub fn with_on_request(mut self, f: impl FnOnce() + Send + 'static) -> Self {
+        self.on_request = Some(Box::new(f));
+        self
+    }
+}
+
+/// serves `responses` in order over http on localhost, one per request, and returns the base url
+/// along with a handle that yields the request lines (e.g.: "GET / HTTP/1.1") once all are served
+pub fn serve_responses(responses: Vec<Response>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        let mut requests = vec![];
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            reader.read_line(&mut request).unwrap();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            requests.push(request.trim_end().to_string());
+            if let Some(on_request) = response.on_request {
+                on_request();
+            }
+            if response.hang {
+                thread::sleep(Duration::from_secs(5));
+                continue;
+            }
+            let mut header = format!("HTTP/1.1 {}\r\n", response.status);
+            for (name, value) in &response.headers {
+                header.push_str(&format!("{name}: {value}\r\n"));
+            }
+            header.push_str(&format!(
+                "content-length: {}\r\nconnection: close\r\n\r\n",
+                response.body.len()
+            ));
+            stream.write_all(header.as_bytes()).unwrap();
+            stream.write_all(&response.body).unwrap();
+        }
+        requests
+    });
+    (format!("http://{addr}"), server)
+}
+
 pub fn cleanup() {
     let _ = file::remove_all(".github");
     let _ = file::remove_all(".git");
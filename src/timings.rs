@@ -0,0 +1,94 @@
+//! durations of the phases of installing plugins and tools for `mise install --timings`.
+//! These are only kept in memory and printed at the end of the command, nothing is sent anywhere.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tabled::Tabled;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timing {
+    /// e.g.: "plugin:node" or "node@20.0.0"
+    pub subject: String,
+    /// e.g.: "clone" or "download"
+    pub phase: String,
+    pub duration: Duration,
+}
+
+/// None unless enabled so nothing is recorded by default
+static TIMINGS: Mutex<Option<Vec<Timing>>> = Mutex::new(None);
+
+pub fn enable() {
+    *TIMINGS.lock().unwrap() = Some(vec![]);
+}
+
+/// runs `f`, recording how long it took as `phase` of `subject` if timings are enabled
+pub fn time<T>(subject: &str, phase: &str, f: impl FnOnce() -> T) -> T {
+    if TIMINGS.lock().unwrap().is_none() {
+        return f();
+    }
+    let start = Instant::now();
+    let result = f();
+    if let Some(timings) = TIMINGS.lock().unwrap().as_mut() {
+        timings.push(Timing {
+            subject: subject.to_string(),
+            phase: phase.to_string(),
+            duration: start.elapsed(),
+        });
+    }
+    result
+}
+
+/// the timings recorded so far, in the order the phases finished. Recording stops until
+/// `enable` is called again
+pub fn take() -> Vec<Timing> {
+    TIMINGS.lock().unwrap().take().unwrap_or_default()
+}
+
+#[derive(Tabled)]
+pub struct TimingRow {
+    subject: String,
+    phase: String,
+    duration: String,
+}
+
+impl From<&Timing> for TimingRow {
+    fn from(t: &Timing) -> Self {
+        Self {
+            subject: t.subject.clone(),
+            phase: t.phase.clone(),
+            duration: format!("{:.3}s", t.duration.as_secs_f64()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use test_log::test;
+
+    use super::*;
+
+    #[test]
+    fn test_timings() {
+        assert_eq!(time("plugin:a", "clone", || 1), 1);
+        enable();
+        time("plugin:a", "clone", || ());
+        time("a@1.0.0", "install", || ());
+        let timings = take();
+        time("plugin:a", "clone", || ());
+
+        let phases = timings
+            .iter()
+            .map(|t| (t.subject.as_str(), t.phase.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(phases, vec![("plugin:a", "clone"), ("a@1.0.0", "install")]);
+        assert_eq!(take(), vec![]);
+        let row = TimingRow::from(&Timing {
+            subject: "plugin:a".into(),
+            phase: "clone".into(),
+            duration: Duration::from_millis(1500),
+        });
+        assert_eq!(row.duration, "1.500s");
+    }
+}
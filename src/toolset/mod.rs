@@ -3,7 +3,7 @@ use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::{panic, thread};
 
 use console::truncate_str;
@@ -46,6 +46,8 @@ pub struct InstallOptions {
     pub jobs: Option<usize>,
     pub raw: bool,
     pub latest_versions: bool,
+    /// install the rest of the tools if one fails, then report all of the failures together
+    pub keep_going: bool,
 }
 
 impl InstallOptions {
@@ -150,6 +152,35 @@ impl Toolset {
         versions: Vec<ToolRequest>,
         mpr: &MultiProgressReport,
         opts: &InstallOptions,
+    ) -> Result<Vec<ToolVersion>> {
+        let deadline = match &Settings::try_get()?.total_install_timeout {
+            Some(timeout) => Some(Instant::now() + humantime::parse_duration(timeout)?),
+            None => None,
+        };
+        let mut failures = vec![];
+        let installed =
+            self.install_versions_until(config, versions, mpr, opts, deadline, &mut failures)?;
+        match failures.len() {
+            0 => Ok(installed),
+            1 => Err(failures.pop().unwrap()),
+            n => Err(eyre!(
+                "{n} tools failed to install:\n{}",
+                failures.iter().map(|e| format!("{e:#}")).join("\n")
+            )),
+        }
+    }
+
+    /// installs `versions`, failing the plugins and versions that haven't started installing by
+    /// `deadline` instead of installing them. With `keep_going` the failures are added to
+    /// `failures` instead of being returned
+    fn install_versions_until(
+        &mut self,
+        config: &Config,
+        versions: Vec<ToolRequest>,
+        mpr: &MultiProgressReport,
+        opts: &InstallOptions,
+        deadline: Option<Instant>,
+        failures: &mut Vec<eyre::Report>,
     ) -> Result<Vec<ToolVersion>> {
         if versions.is_empty() {
             return Ok(vec![]);
@@ -157,29 +188,48 @@ impl Toolset {
         let leaf_deps = get_leaf_dependencies(&versions)?;
         if leaf_deps.len() < versions.len() {
             debug!("installing {} leaf tools first", leaf_deps.len());
-            self.install_versions(config, leaf_deps.into_iter().cloned().collect(), mpr, opts)?;
+            let leaf_deps = leaf_deps.into_iter().cloned().collect();
+            match self.install_versions_until(config, leaf_deps, mpr, opts, deadline, failures) {
+                Ok(_) => {}
+                Err(err) if opts.keep_going => failures.push(err),
+                Err(err) => return Err(err),
+            }
         }
         debug!("install_versions: {}", versions.iter().join(" "));
         let settings = Settings::try_get()?;
-        let queue: Vec<_> = versions
+        let mut queue: Vec<_> = versions
             .into_iter()
             .rev()
             .chunk_by(|v| v.backend().clone())
             .into_iter()
             .map(|(fa, v)| (backend::get(&fa), v.collect_vec()))
             .collect();
+        let mut plugin_failures = HashSet::new();
         for (t, _) in &queue {
             if !t.is_installed() {
-                t.ensure_installed(mpr, false).or_else(|err| {
-                    if let Some(&Error::PluginNotInstalled(_)) = err.downcast_ref::<Error>() {
-                        Ok(())
-                    } else {
-                        Err(err)
+                let result = ensure_before(deadline, t.id())
+                    .and_then(|_| t.ensure_installed(mpr, false).map(|_| ()))
+                    .or_else(|err| {
+                        // only a declined install, not a failure caused by another missing plugin
+                        if let Some(&Error::PluginNotInstalled(_)) = err.downcast_ref::<Error>() {
+                            Ok(())
+                        } else {
+                            Err(err)
+                        }
+                    });
+                match result {
+                    Ok(()) => {}
+                    Err(err) if opts.keep_going => {
+                        failures.push(tag_failure(err, format!("[{}] plugin install", t.id())));
+                        plugin_failures.insert(t.id().to_string());
                     }
-                })?;
+                    Err(err) => return Err(err),
+                }
             }
         }
+        queue.retain(|(t, _)| !plugin_failures.contains(t.id()));
         let queue = Arc::new(Mutex::new(queue));
+        let install_failures = Arc::new(Mutex::new(vec![]));
         let raw = opts.raw || settings.raw;
         let jobs = match raw {
             true => 1,
@@ -193,31 +243,44 @@ impl Toolset {
                 .map(|_| {
                     let queue = queue.clone();
                     let installing = installing.clone();
+                    let failures = install_failures.clone();
                     let ts = &*self;
                     s.spawn(move || {
                         let next_job = || queue.lock().unwrap().pop();
                         let mut installed = vec![];
                         while let Some((t, versions)) = next_job() {
                             installing.lock().unwrap().insert(t.id().into());
-                            for tv in versions {
-                                // TODO: this logic should be able to be removed now I think
-                                for dep in t.get_all_dependencies(&tv)? {
-                                    while installing.lock().unwrap().contains(&dep.to_string()) {
-                                        trace!(
-                                        "{tv} waiting for dependency {dep} to finish installing"
-                                    );
-                                        sleep(Duration::from_millis(100));
+                            for tr in versions {
+                                let install = || -> Result<ToolVersion> {
+                                    // TODO: this logic should be able to be removed now I think
+                                    for dep in t.get_all_dependencies(&tr)? {
+                                        while installing.lock().unwrap().contains(&dep.to_string())
+                                        {
+                                            trace!(
+                                            "{tr} waiting for dependency {dep} to finish installing"
+                                        );
+                                            sleep(Duration::from_millis(100));
+                                        }
                                     }
-                                }
-                                let tv = tv.resolve(t.as_ref(), opts.latest_versions)?;
-                                let ctx = InstallContext {
-                                    ts,
-                                    pr: mpr.add(&tv.style()),
-                                    tv: tv.clone(),
-                                    force: opts.force,
+                                    ensure_before(deadline, &tr.to_string())?;
+                                    let tv = tr.resolve(t.as_ref(), opts.latest_versions)?;
+                                    let ctx = InstallContext {
+                                        ts,
+                                        pr: mpr.add(&tv.style()),
+                                        tv: tv.clone(),
+                                        force: opts.force,
+                                    };
+                                    t.install_version(ctx)?;
+                                    Ok(tv)
                                 };
-                                t.install_version(ctx)?;
-                                installed.push(tv);
+                                match install() {
+                                    Ok(tv) => installed.push(tv),
+                                    Err(err) if opts.keep_going => failures
+                                        .lock()
+                                        .unwrap()
+                                        .push(tag_failure(err, format!("[{tr}] install"))),
+                                    Err(err) => return Err(err),
+                                }
                             }
                             installing.lock().unwrap().remove(t.id());
                         }
@@ -241,6 +304,7 @@ impl Toolset {
         shims::reshim(self)?;
         runtime_symlinks::rebuild(config)?;
         trace!("install: done");
+        failures.append(&mut install_failures.lock().unwrap());
         Ok(installed)
     }
 
@@ -362,36 +426,30 @@ impl Toolset {
         Ok(env)
     }
     pub fn env(&self, config: &Config) -> Result<BTreeMap<String, String>> {
-        let entries = self
-            .list_current_installed_versions()
+        let (mut entries, collisions) = merge_tool_envs(self.tool_envs(config));
+        for c in collisions {
+            debug!("{c}");
+        }
+        entries.extend(config.env()?.clone());
+        Ok(entries)
+    }
+    /// env vars set to different values by more than one tool, `env` uses the first of them
+    pub fn env_collisions(&self, config: &Config) -> Vec<EnvCollision> {
+        merge_tool_envs(self.tool_envs(config)).1
+    }
+    /// `exec_env` of each installed tool, resolved concurrently but returned in toolset order
+    fn tool_envs(&self, config: &Config) -> Vec<(String, BTreeMap<String, String>)> {
+        self.list_current_installed_versions()
             .into_par_iter()
             .filter(|(_, tv)| !matches!(tv.request, ToolRequest::System(_)))
-            .flat_map(|(p, tv)| match p.exec_env(config, self, &tv) {
-                Ok(env) => env.into_iter().collect(),
+            .map(|(p, tv)| match p.exec_env(config, self, &tv) {
+                Ok(env) => (tv.to_string(), env),
                 Err(e) => {
                     warn!("Error running exec-env: {:#}", e);
-                    Vec::new()
+                    (tv.to_string(), BTreeMap::new())
                 }
             })
-            .collect::<Vec<(String, String)>>();
-        let add_paths = entries
-            .iter()
-            .filter(|(k, _)| k == "MISE_ADD_PATH" || k == "RTX_ADD_PATH")
-            .map(|(_, v)| v)
-            .join(":");
-        let mut entries: BTreeMap<String, String> = entries
-            .into_iter()
-            .filter(|(k, _)| k != "RTX_ADD_PATH")
-            .filter(|(k, _)| k != "MISE_ADD_PATH")
-            .filter(|(k, _)| !k.starts_with("RTX_TOOL_OPTS__"))
-            .filter(|(k, _)| !k.starts_with("MISE_TOOL_OPTS__"))
-            .rev()
-            .collect();
-        if !add_paths.is_empty() {
-            entries.insert("PATH".to_string(), add_paths);
-        }
-        entries.extend(config.env()?.clone());
-        Ok(entries)
+            .collect()
     }
     pub fn list_paths(&self) -> Vec<PathBuf> {
         self.list_current_installed_versions()
@@ -524,6 +582,50 @@ impl From<ToolRequestSet> for Toolset {
     }
 }
 
+/// resolves each request to a concrete version, `jobs` at a time so the remote versions of
+/// different tools are fetched concurrently. Each request gets its own result so one that no
+/// version matches doesn't keep the others from resolving. Results are in the order of `requests`
+pub fn resolve_all(
+    requests: Vec<ToolRequest>,
+    latest_versions: bool,
+) -> Result<Vec<(ToolRequest, Result<ToolVersion>)>> {
+    let settings = Settings::try_get()?;
+    let resolved = rayon::ThreadPoolBuilder::new()
+        .num_threads(settings.jobs)
+        .build()?
+        .install(|| {
+            requests
+                .into_par_iter()
+                .map(|tr| {
+                    let tv = resolve_concrete(&tr, latest_versions);
+                    (tr, tv)
+                })
+                .collect()
+        });
+    Ok(resolved)
+}
+
+/// like [`ToolRequest::resolve`] but fails instead of falling back to the requested version
+/// when the plugin isn't installed or no version matches
+fn resolve_concrete(tr: &ToolRequest, latest_versions: bool) -> Result<ToolVersion> {
+    let backend = backend::get(tr.backend());
+    if !backend.is_installed() {
+        Err(Error::PluginNotInstalled(tr.backend().to_string()))?
+    }
+    let tv = tr.resolve(backend.as_ref(), latest_versions)?;
+    let concrete = match tr {
+        ToolRequest::Version { .. } | ToolRequest::Prefix { .. } | ToolRequest::Sub { .. } => {
+            backend.is_version_installed(&tv)
+                || backend.list_remote_versions()?.contains(&tv.version)
+        }
+        _ => true,
+    };
+    if !concrete {
+        return Err(eyre!("no version of {} matches {tr}", tr.backend()));
+    }
+    Ok(tv)
+}
+
 fn get_leaf_dependencies(requests: &[ToolRequest]) -> eyre::Result<Vec<&ToolRequest>> {
     let versions_hash = requests
         .iter()
@@ -555,13 +657,94 @@ fn is_outdated_version(current: &str, latest: &str) -> bool {
     current != latest
 }
 
+/// an env var set by `exec_env` of more than one tool with different values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvCollision {
+    pub key: String,
+    /// the tool whose value is used
+    pub used: String,
+    pub ignored: String,
+}
+
+impl Display for EnvCollision {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is set by both {} and {}, using the value from {}",
+            self.key, self.used, self.ignored, self.used
+        )
+    }
+}
+
+/// merges the env of each tool (in toolset order) into one. When tools set the same var the
+/// first of them wins, except for MISE_ADD_PATH which is joined into PATH
+fn merge_tool_envs(
+    envs: Vec<(String, BTreeMap<String, String>)>,
+) -> (BTreeMap<String, String>, Vec<EnvCollision>) {
+    let mut merged = BTreeMap::new();
+    // the tool each var in `merged` came from
+    let mut set_by: HashMap<String, String> = HashMap::new();
+    let mut add_paths = vec![];
+    let mut collisions = vec![];
+    for (tool, env) in envs {
+        for (k, v) in env {
+            if k == "MISE_ADD_PATH" || k == "RTX_ADD_PATH" {
+                add_paths.push(v);
+                continue;
+            }
+            if k.starts_with("RTX_TOOL_OPTS__") || k.starts_with("MISE_TOOL_OPTS__") {
+                continue;
+            }
+            match merged.get(&k) {
+                Some(existing) if existing != &v => collisions.push(EnvCollision {
+                    used: set_by[&k].clone(),
+                    ignored: tool.clone(),
+                    key: k,
+                }),
+                Some(_) => {}
+                None => {
+                    set_by.insert(k.clone(), tool.clone());
+                    merged.insert(k, v);
+                }
+            }
+        }
+    }
+    if !add_paths.is_empty() {
+        merged.insert("PATH".to_string(), add_paths.join(":"));
+    }
+    (merged, collisions)
+}
+
+/// fails if the total_install_timeout `deadline` has passed so `what` isn't started
+fn ensure_before(deadline: Option<Instant>, what: &str) -> Result<()> {
+    match deadline {
+        Some(deadline) if Instant::now() >= deadline => {
+            Err(Error::InstallTimeoutExceeded(what.to_string()))?
+        }
+        _ => Ok(()),
+    }
+}
+
+/// prefixes a keep_going failure with `tag` unless it already names what failed, e.g.: a timeout
+fn tag_failure(err: eyre::Report, tag: String) -> eyre::Report {
+    match err.downcast_ref::<Error>() {
+        Some(Error::InstallTimeoutExceeded(_)) => err,
+        _ => eyre!("{tag}: {err:#}"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::backend::reset;
     use pretty_assertions::assert_eq;
     use test_log::test;
 
-    use super::is_outdated_version;
+    use crate::plugins::asdf_plugin::AsdfPlugin;
+    use crate::plugins::{Plugin, Provenance};
+    use crate::test::TestGuard;
+    use crate::{dirs, file, test};
+
+    use super::*;
 
     #[test]
     fn test_is_outdated_version() {
@@ -588,4 +771,240 @@ mod tests {
             false
         );
     }
+
+    #[test]
+    fn test_resolve_all() {
+        crate::test::reset();
+        let requests = [
+            ("tiny", "2"),
+            ("dummy", "1.1"),
+            ("tiny", "9"),
+            ("tiny", "3.0"),
+        ]
+        .map(|(name, v)| ToolRequest::new(name.into(), v).unwrap())
+        .to_vec();
+
+        let resolved = resolve_all(requests.clone(), true)
+            .unwrap()
+            .into_iter()
+            .map(|(tr, tv)| (tr, tv.map(|tv| tv.version).map_err(|e| e.to_string())))
+            .collect::<Vec<_>>();
+
+        let expected = [
+            Ok("2.1.0".to_string()),
+            Ok("1.1.0".to_string()),
+            Err("no version of tiny matches tiny@9".to_string()),
+            Ok("3.0.1".to_string()),
+        ];
+        assert_eq!(
+            resolved,
+            requests.into_iter().zip(expected).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_total_install_timeout() {
+        crate::test::reset();
+        // a plugin that takes longer to install than the budget
+        let origin = test::plugin_origin(&[
+            ("list-all", "echo 1.0.0"),
+            ("install", "mkdir -p \"$ASDF_INSTALL_PATH\""),
+            ("post-plugin-add", "sleep 1"),
+        ]);
+        let shorthands = tempfile::NamedTempFile::new().unwrap();
+        let url = origin.path().display();
+        file::write(
+            shorthands.path(),
+            format!("budget-slow = \"{url}\"\nbudget-next = \"{url}\"\n"),
+        )
+        .unwrap();
+        let _guard = TestGuard::default()
+            .with_env("MISE_SHORTHANDS_FILE", shorthands.path())
+            .with_env("MISE_TOTAL_INSTALL_TIMEOUT", "500ms")
+            .with_cleanup(dirs::PLUGINS.join("budget-slow"))
+            .with_cleanup(dirs::PLUGINS.join("budget-next"))
+            .with_cleanup(dirs::INSTALLS.join("budget-slow"));
+        let config = Config::get();
+        let mut ts = Toolset::default();
+        let versions = ["budget-next", "budget-slow"]
+            .map(|name| ToolRequest::new(name.into(), "1.0.0").unwrap())
+            .to_vec();
+        let opts = InstallOptions {
+            jobs: Some(1),
+            keep_going: true,
+            ..InstallOptions::new()
+        };
+
+        let start = Instant::now();
+        let err = ts
+            .install_versions(&config, versions, &MultiProgressReport::get(), &opts)
+            .unwrap_err();
+        let elapsed = start.elapsed();
+        let slow_installed = backend::get(&"budget-slow".into()).is_installed();
+        let next_installed = backend::get(&"budget-next".into()).is_installed();
+
+        let err = format!("{err:?}");
+        assert!(err.contains("2 tools failed to install"), "{err}");
+        assert!(
+            err.contains("\n[budget-next] not started, total_install_timeout exceeded"),
+            "{err}"
+        );
+        assert!(
+            err.contains("\n[budget-slow@1.0.0] not started, total_install_timeout exceeded"),
+            "{err}"
+        );
+        assert!(slow_installed);
+        assert!(!next_installed);
+        assert!(elapsed < Duration::from_secs(5), "{elapsed:?}");
+    }
+
+    #[test]
+    fn test_install_timings() {
+        crate::test::reset();
+        let origin = test::plugin_origin(&[
+            ("list-all", "echo 1.0.0"),
+            ("download", "mkdir -p \"$ASDF_DOWNLOAD_PATH\""),
+            ("install", "mkdir -p \"$ASDF_INSTALL_PATH\""),
+        ]);
+        let shorthands = tempfile::NamedTempFile::new().unwrap();
+        let url = origin.path().display();
+        file::write(shorthands.path(), format!("timed = \"{url}\"\n")).unwrap();
+        let _guard = TestGuard::default()
+            .with_env("MISE_SHORTHANDS_FILE", shorthands.path())
+            .with_cleanup(dirs::PLUGINS.join("timed"))
+            .with_cleanup(dirs::INSTALLS.join("timed"));
+        let config = Config::get();
+        let mut ts = Toolset::default();
+        let versions = vec![ToolRequest::new("timed".into(), "1.0.0").unwrap()];
+
+        crate::timings::enable();
+        let installed = ts.install_versions(
+            &config,
+            versions,
+            &MultiProgressReport::get(),
+            &InstallOptions::new(),
+        );
+        let timings = crate::timings::take();
+
+        installed.unwrap();
+        let phases = timings
+            .iter()
+            .map(|t| format!("{} {}", t.subject, t.phase))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            phases,
+            vec![
+                "plugin:timed resolve url",
+                "plugin:timed clone",
+                "plugin:timed post-plugin-add",
+                "plugin:timed total",
+                "timed@1.0.0 download",
+                "timed@1.0.0 install",
+                "timed@1.0.0 total",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_install_provenance() {
+        crate::test::reset();
+        let origin = test::plugin_origin(&[
+            ("list-all", "echo 1.0.0"),
+            (
+                "download",
+                "mkdir -p \"$ASDF_DOWNLOAD_PATH\" && echo artifact > \"$ASDF_DOWNLOAD_PATH/tool.tar.gz\"",
+            ),
+            ("install", "mkdir -p \"$ASDF_INSTALL_PATH\""),
+        ]);
+        let shorthands = tempfile::NamedTempFile::new().unwrap();
+        let url = origin.path().to_string_lossy().to_string();
+        file::write(shorthands.path(), format!("provenance = \"{url}\"\n")).unwrap();
+        let _guard = TestGuard::default()
+            .with_env("MISE_SHORTHANDS_FILE", shorthands.path())
+            .with_cleanup(dirs::PLUGINS.join("provenance"))
+            .with_cleanup(dirs::INSTALLS.join("provenance"))
+            .with_cleanup(dirs::DOWNLOADS.join("provenance"));
+        let config = Config::get();
+        let mut ts = Toolset::default();
+        let versions = vec![ToolRequest::new("provenance".into(), "1.0.0").unwrap()];
+
+        let installed = ts.install_versions(
+            &config,
+            versions,
+            &MultiProgressReport::get(),
+            &InstallOptions::new(),
+        );
+        let plugin = AsdfPlugin::new("provenance".into());
+        let provenance = plugin.provenance("1.0.0");
+        let missing = plugin.provenance("2.0.0");
+
+        installed.unwrap();
+        let provenance = provenance.unwrap().unwrap();
+        let sha = cmd!("git", "-C", origin.path(), "rev-parse", "HEAD")
+            .read()
+            .unwrap();
+        let artifact = tempfile::tempdir().unwrap();
+        file::write(artifact.path().join("tool.tar.gz"), "artifact\n").unwrap();
+        assert_eq!(provenance.version, "1.0.0");
+        assert_eq!(provenance.source_url, Some(url));
+        assert_eq!(provenance.plugin_sha, Some(sha));
+        assert_eq!(
+            provenance.checksum,
+            Some(crate::hash::dir_hash_sha256(artifact.path()).unwrap())
+        );
+        assert!(chrono::DateTime::parse_from_rfc3339(&provenance.timestamp).is_ok());
+        let json = serde_json::to_string(&provenance).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Provenance>(&json).unwrap(),
+            provenance
+        );
+        assert_eq!(missing.unwrap(), None);
+    }
+
+    #[test]
+    fn test_merge_tool_envs() {
+        let env = |vars: &[(&str, &str)]| {
+            vars.iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let envs = vec![
+            (
+                "java@21".to_string(),
+                env(&[("JAVA_HOME", "/java/21"), ("MISE_ADD_PATH", "/java/21/bin")]),
+            ),
+            (
+                "maven@3".to_string(),
+                env(&[
+                    ("JAVA_HOME", "/maven/jdk"),
+                    ("M2_HOME", "/maven/3"),
+                    ("MISE_ADD_PATH", "/maven/3/bin"),
+                    ("MISE_TOOL_OPTS__FOO", "bar"),
+                ]),
+            ),
+            ("gradle@8".to_string(), env(&[("JAVA_HOME", "/java/21")])),
+        ];
+
+        let (merged, collisions) = merge_tool_envs(envs);
+        assert_eq!(
+            merged,
+            env(&[
+                ("JAVA_HOME", "/java/21"),
+                ("M2_HOME", "/maven/3"),
+                ("PATH", "/java/21/bin:/maven/3/bin"),
+            ])
+        );
+        assert_eq!(
+            collisions,
+            vec![EnvCollision {
+                key: "JAVA_HOME".into(),
+                used: "java@21".into(),
+                ignored: "maven@3".into(),
+            }]
+        );
+        assert_eq!(
+            collisions[0].to_string(),
+            "JAVA_HOME is set by both java@21 and maven@3, using the value from java@21"
+        );
+    }
 }
@@ -96,23 +96,26 @@ impl ToolVersion {
             style(&format!("@{}", &self.version)).for_stderr()
         )
     }
+    /// the directory name `version` is installed to under the backend's installs path
+    pub fn pathname(version: &str) -> String {
+        version.replace([':', '/'], "-")
+    }
     fn tv_pathname(&self) -> String {
-        match &self.request {
+        let pathname = match &self.request {
             ToolRequest::Version { .. } => self.version.to_string(),
             ToolRequest::Prefix { .. } => self.version.to_string(),
             ToolRequest::Sub { .. } => self.version.to_string(),
             ToolRequest::Ref { ref_: r, .. } => format!("ref-{}", r),
             ToolRequest::Path(_, p) => format!("path-{}", hash_to_str(p)),
             ToolRequest::System(_) => "system".to_string(),
-        }
-        .replace([':', '/'], "-")
+        };
+        Self::pathname(&pathname)
     }
     fn tv_short_pathname(&self) -> String {
         match &self.request {
-            ToolRequest::Version { version: v, .. } => v.to_string(),
+            ToolRequest::Version { version: v, .. } => Self::pathname(v),
             _ => self.tv_pathname(),
         }
-        .replace([':', '/'], "-")
     }
 
     fn resolve_version(
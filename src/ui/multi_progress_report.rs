@@ -1,14 +1,23 @@
+use std::fs::File;
+use std::path::Path;
 use std::sync::{Arc, Mutex, Weak};
 
+use eyre::Result;
 use indicatif::MultiProgress;
 
 use crate::config::Settings;
-use crate::ui::progress_report::{ProgressReport, QuietReport, SingleReport, VerboseReport};
+use crate::file;
+use crate::file::display_path;
+use crate::ui::progress_report::{
+    LogFileReport, ProgressReport, QuietReport, SingleReport, VerboseReport,
+};
 
 #[derive(Debug)]
 pub struct MultiProgressReport {
     mp: Option<MultiProgress>,
     quiet: bool,
+    silent: bool,
+    log_file: Option<Arc<Mutex<File>>>,
 }
 
 static INSTANCE: Mutex<Option<Weak<MultiProgressReport>>> = Mutex::new(None);
@@ -42,13 +51,38 @@ impl MultiProgressReport {
             true => None,
             false => Some(MultiProgress::new()),
         };
+        let log_file = settings.install_log_file.as_ref().and_then(|path| {
+            let path = file::replace_path(path);
+            match open_log_file(&path) {
+                Ok(f) => Some(Arc::new(Mutex::new(f))),
+                Err(err) => {
+                    warn!("failed to open {}: {err:#}", display_path(&path));
+                    None
+                }
+            }
+        });
         MultiProgressReport {
             mp,
             quiet: settings.quiet,
+            silent: false,
+            log_file,
+        }
+    }
+    /// for embedding mise as a library: never renders to the terminal and makes anything that
+    /// would prompt (e.g.: installing a community plugin) fail instead
+    pub fn silent() -> Self {
+        MultiProgressReport {
+            mp: None,
+            quiet: true,
+            silent: true,
+            log_file: None,
         }
     }
+    pub fn is_silent(&self) -> bool {
+        self.silent
+    }
     pub fn add(&self, prefix: &str) -> Box<dyn SingleReport> {
-        match &self.mp {
+        let pr: Box<dyn SingleReport> = match &self.mp {
             _ if self.quiet => Box::new(QuietReport::new()),
             Some(mp) => {
                 let mut pr = ProgressReport::new(prefix.into());
@@ -56,6 +90,10 @@ impl MultiProgressReport {
                 Box::new(pr)
             }
             None => Box::new(VerboseReport::new(prefix.to_string())),
+        };
+        match &self.log_file {
+            Some(log_file) => Box::new(LogFileReport::new(pr, prefix, log_file.clone())),
+            None => pr,
         }
     }
     pub fn suspend_if_active<F: FnOnce() -> R, R>(f: F) -> R {
@@ -72,8 +110,24 @@ impl MultiProgressReport {
     }
 }
 
+/// starts a fresh log for this run, keeping the previous run's log at `<path>.1`
+fn open_log_file(path: &Path) -> Result<File> {
+    if let Some(parent) = path.parent() {
+        file::create_dir_all(parent)?;
+    }
+    if path.exists() {
+        let mut prev = path.as_os_str().to_owned();
+        prev.push(".1");
+        file::rename(path, prev)?;
+    }
+    Ok(File::create(path)?)
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::file;
+    use crate::test::{reset, TestGuard};
+
     use super::*;
 
     #[test]
@@ -84,4 +138,39 @@ mod tests {
         pr.println("".into());
         pr.set_message("test".into());
     }
+
+    #[test]
+    fn test_silent() {
+        let mpr = MultiProgressReport::silent();
+        assert!(mpr.is_silent());
+        assert!(!MultiProgressReport::get().is_silent());
+        let pr = mpr.add("PREFIX");
+        pr.set_message("test".into());
+        pr.finish_with_message("test".into());
+    }
+
+    #[test]
+    fn test_install_log_file() {
+        reset();
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("install.log");
+        let guard = TestGuard::default().with_env("MISE_INSTALL_LOG_FILE", &log);
+        assert_cli!("install", "-f", "tiny@1.0.1");
+        let first = file::read_to_string(&log).unwrap();
+        assert_cli!("install", "-f", "tiny@1.0.1");
+        drop(guard);
+        assert_cli!("uninstall", "tiny@1.0.1");
+
+        assert!(
+            first.contains("tiny@1.0.1 ~/data/plugins/tiny/bin/install"),
+            "{first}"
+        );
+        assert!(first.contains("tiny@1.0.1 installed"), "{first}");
+        let mut rotated = log.as_os_str().to_owned();
+        rotated.push(".1");
+        assert_eq!(file::read_to_string(rotated).unwrap(), first);
+        let second = file::read_to_string(&log).unwrap();
+        assert!(second.contains("tiny@1.0.1 removing"), "{second}");
+        assert!(second.ends_with("tiny@1.0.1 installed\n"), "{second}");
+    }
 }
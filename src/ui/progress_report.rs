@@ -1,3 +1,6 @@
+use std::fs::File;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use indicatif::{ProgressBar, ProgressStyle};
@@ -164,6 +167,56 @@ impl SingleReport for VerboseReport {
     }
 }
 
+/// tees everything reported to `inner` into the install log file
+pub struct LogFileReport {
+    inner: Box<dyn SingleReport>,
+    prefix: String,
+    file: Arc<Mutex<File>>,
+}
+
+impl LogFileReport {
+    pub fn new(inner: Box<dyn SingleReport>, prefix: &str, file: Arc<Mutex<File>>) -> Self {
+        LogFileReport {
+            inner,
+            prefix: console::strip_ansi_codes(prefix).to_string(),
+            file,
+        }
+    }
+
+    fn log(&self, message: &str) {
+        let mut file = self.file.lock().unwrap();
+        for line in console::strip_ansi_codes(message).lines() {
+            let _ = writeln!(file, "{} {line}", self.prefix);
+        }
+        let _ = file.flush();
+    }
+}
+
+impl SingleReport for LogFileReport {
+    fn println(&self, message: String) {
+        self.log(&message);
+        self.inner.println(message);
+    }
+    fn set_message(&self, message: String) {
+        self.log(&message);
+        self.inner.set_message(message);
+    }
+    fn inc(&self, delta: u64) {
+        self.inner.inc(delta);
+    }
+    fn set_length(&self, length: u64) {
+        self.inner.set_length(length);
+    }
+    fn finish(&self) {
+        self.log("done");
+        self.inner.finish();
+    }
+    fn finish_with_message(&self, message: String) {
+        self.log(&message);
+        self.inner.finish_with_message(message);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use test_log::test;
@@ -20,19 +20,21 @@ pub fn confirm<S: Into<String>>(message: S) -> eyre::Result<bool> {
     Ok(result)
 }
 
+/// like `confirm` but also offers "All". Answering "All" accepts this and every later
+/// `confirm_with_all` prompt for the rest of the current command (e.g.: each plugin of a
+/// batch install). It is never persisted, the next mise command will prompt again.
 pub fn confirm_with_all<S: Into<String>>(message: S) -> eyre::Result<bool> {
     let _lock = MUTEX.lock().unwrap(); // Prevent multiple prompts at once
+    let mut skip_prompt = SKIP_PROMPT.lock().unwrap();
+    if *skip_prompt {
+        return Ok(true);
+    }
     let _ctrlc = ctrlc::handle_ctrlc()?;
 
     if !console::user_attended_stderr() || env::__USAGE.is_some() {
         return Ok(false);
     }
 
-    let mut skip_prompt = SKIP_PROMPT.lock().unwrap();
-    if *skip_prompt {
-        return Ok(true);
-    }
-
     let answer = Dialog::new(message)
         .buttons(vec![
             DialogButton::new("Yes"),
@@ -53,3 +55,8 @@ pub fn confirm_with_all<S: Into<String>>(message: S) -> eyre::Result<bool> {
     };
     Ok(result)
 }
+
+#[cfg(test)]
+pub fn set_confirmed_all(all: bool) {
+    *SKIP_PROMPT.lock().unwrap() = all;
+}